@@ -0,0 +1,81 @@
+use std::io::Read;
+
+use anyhow::{anyhow, Context, Result};
+use polodb_core::bson::oid::ObjectId;
+use serde_json::Value;
+
+use crate::db::TodoDb;
+use crate::display::{print_info, print_success};
+use crate::json::patch_todo;
+use crate::models::Todo;
+
+/// The todo a patch is for: by `id` when it has one (stable however the list has
+/// changed), else by `number`
+fn target<'a>(todos: &'a [Todo], patch: &serde_json::Map<String, Value>) -> Result<&'a Todo> {
+    if let Some(id) = patch.get("id").or_else(|| patch.get("_id")) {
+        let id = id
+            .as_str()
+            .and_then(|s| ObjectId::parse_str(s).ok())
+            .ok_or_else(|| anyhow!("'id' should be a todo id string, got {}", id))?;
+        return todos
+            .iter()
+            .find(|t| t.id == id)
+            .ok_or_else(|| anyhow!("no todo with id {}", id));
+    }
+    let number = patch
+        .get("number")
+        .and_then(Value::as_u64)
+        .ok_or_else(|| anyhow!("each update needs an 'id' or a 'number'"))?;
+    (number as usize)
+        .checked_sub(1)
+        .and_then(|i| todos.get(i))
+        .ok_or_else(|| anyhow!("Todo #{} not found. You have {} todos.", number, todos.len()))
+}
+
+/// `todo apply <patch.json|->`: apply partial updates, e.g. `[{"id": "...", "title": "New
+/// title"}]`, in the field format `--json` prints. Every update is checked before any is
+/// written, so a bad patch changes nothing.
+pub fn cmd_apply(db: &TodoDb, args: Vec<String>) -> Result<()> {
+    let path = args
+        .first()
+        .ok_or_else(|| anyhow!("Usage: todo apply <patch.json>  (or - to read stdin)"))?;
+    let contents = if path == "-" {
+        let mut text = String::new();
+        std::io::stdin().read_to_string(&mut text).context("failed to read stdin")?;
+        text
+    } else {
+        std::fs::read_to_string(path).with_context(|| format!("failed to read {}", path))?
+    };
+
+    let patches = match serde_json::from_str(&contents).context("the patch is not valid JSON")? {
+        Value::Array(items) => items,
+        single @ Value::Object(_) => vec![single],
+        _ => return Err(anyhow!("expected a JSON array of updates")),
+    };
+
+    let todos = db.list_all()?;
+    let mut updates = Vec::new();
+    for (n, patch) in patches.iter().enumerate() {
+        let patch = patch
+            .as_object()
+            .ok_or_else(|| anyhow!("update {}: expected a JSON object", n + 1))?;
+        let todo = target(&todos, patch).with_context(|| format!("update {}", n + 1))?;
+        let (patched, changed) =
+            patch_todo(todo, patch).with_context(|| format!("update {} ({})", n + 1, todo.title))?;
+        if !changed.is_empty() {
+            updates.push((patched, changed));
+        }
+    }
+
+    if updates.is_empty() {
+        print_info("Nothing to change; the todos already match the patch");
+        return Ok(());
+    }
+    for (todo, changed) in &updates {
+        db.replace(todo)?;
+        let number = todos.iter().position(|t| t.id == todo.id).unwrap_or_default() + 1;
+        print_info(&format!("  #{} {}: {}", number, todo.title, changed.join(", ")));
+    }
+    print_success(&format!("Applied {} update(s)", updates.len()));
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use anyhow::{anyhow, Result};
+
+use super::resolve_selection;
+use super::trash::restore_trashed;
+use crate::config::Config;
+use crate::db::TodoDb;
+use crate::display::{print_success, print_warning};
+use crate::models::Todo;
+
+/// Move the selected todos with `apply` and say what happened; `verb` is "Archived" or
+/// "Restored"
+pub(super) fn move_selected(
+    todos: &[Todo],
+    args: &[String],
+    usage: &str,
+    verb: &str,
+    apply: impl FnOnce(&[Todo]) -> Result<u64>,
+) -> Result<()> {
+    if args.is_empty() {
+        return Err(anyhow!("Usage: {}", usage));
+    }
+    let rows = resolve_selection(todos, args)?;
+    let selected: Vec<Todo> = rows.iter().map(|&i| todos[i].clone()).collect();
+
+    match rows.as_slice() {
+        [] => print_warning("No todos matched"),
+        [i] => {
+            apply(&selected)?;
+            print_success(&format!("{} #{}: {}", verb, i + 1, todos[*i].title));
+        }
+        _ => {
+            let count = apply(&selected)?;
+            print_success(&format!("{} {} todo(s)", verb, count));
+        }
+    }
+    Ok(())
+}
+
+/// `todo archive <#>|<range>|<filter>...`: move todos out of the list without deleting
+/// them; `todo list --archived` shows them and `todo restore` brings them back
+pub fn cmd_archive(db: &TodoDb, args: Vec<String>) -> Result<()> {
+    let todos = db.list_all()?;
+    let usage = "todo archive <#>  (or a range like 2-6, or a filter like status:done)";
+    move_selected(&todos, &args, usage, "Archived", |t| db.archive_many(t))
+}
+
+/// `todo restore <#>|<range>|<filter>...`: bring archived todos back, numbered as in
+/// `todo list --archived`; with `--trash`, deleted ones, numbered as in `todo trash`
+pub fn cmd_restore(db: &TodoDb, config: &Config, args: Vec<String>) -> Result<()> {
+    if args.iter().any(|a| a == "--trash") {
+        let args: Vec<String> = args.into_iter().filter(|a| a != "--trash").collect();
+        return restore_trashed(db, config, &args);
+    }
+    let archived = db.list_archived()?;
+    if archived.is_empty() {
+        print_warning("The archive is empty");
+        return Ok(());
+    }
+    let usage = "todo restore <#>  (numbers from todo list --archived)";
+    move_selected(&archived, &args, usage, "Restored", |t| db.unarchive_many(t))
+}
@@ -0,0 +1,69 @@
+use std::io;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context, Result};
+use polodb_core::bson;
+
+use super::extract_option;
+use crate::config::Config;
+use crate::db::TodoDb;
+use crate::display::{print_bench, write_todo_rows};
+use crate::filter::{sort_rows, tree_order, Filter};
+use crate::models::Todo;
+
+/// Run `f` and return its result with how long it took
+fn timed<T>(f: impl FnOnce() -> Result<T>) -> Result<(T, Duration)> {
+    let started = Instant::now();
+    let value = f()?;
+    Ok((value, started.elapsed()))
+}
+
+/// `todo bench [--runs 5]`: time the steps behind a typical `todo list` against the
+/// current database, to see where a slow command spends its time
+pub fn cmd_bench(config: &Config, args: Vec<String>) -> Result<()> {
+    let (_, runs) = extract_option(&args, "--runs", Some("-n"));
+    let runs: usize = match runs {
+        Some(n) => n
+            .parse()
+            .ok()
+            .filter(|&n| n > 0)
+            .ok_or_else(|| anyhow!("--runs takes a positive number, got '{}'", n))?,
+        None => 5,
+    };
+
+    let names = ["open", "query", "decode", "list (all)", "filter + sort", "render"];
+    let mut samples: Vec<Vec<Duration>> = vec![Vec::new(); names.len()];
+    let mut count = 0;
+
+    for _ in 0..runs {
+        let (db, open) = timed(|| TodoDb::open(config))?;
+        let (docs, query) = timed(|| db.list_documents())?;
+        let (_, decode) = timed(|| {
+            docs.into_iter()
+                .map(|d| bson::from_document::<Todo>(d).context("failed to decode todo"))
+                .collect::<Result<Vec<_>>>()
+        })?;
+        let (todos, list) = timed(|| db.list_all())?;
+        let (_, filter) = timed(|| {
+            let filter = Filter::parse(&["status:pending".to_string()])?;
+            let mut rows: Vec<usize> =
+                (0..todos.len()).filter(|&i| filter.matches(&todos[i])).collect();
+            sort_rows(&todos, &mut rows, "urgency", config)?;
+            Ok(rows)
+        })?;
+        let (_, render) = timed(|| {
+            let mut all: Vec<usize> = (0..todos.len()).collect();
+            tree_order(&todos, &mut all);
+            write_todo_rows(&mut io::sink(), &todos, &all).context("failed to render")
+        })?;
+
+        for (phase, took) in [open, query, decode, list, filter, render].into_iter().enumerate() {
+            samples[phase].push(took);
+        }
+        count = todos.len();
+    }
+
+    let phases: Vec<(&str, Vec<Duration>)> = names.into_iter().zip(samples).collect();
+    print_bench(count, runs, &phases);
+    Ok(())
+}
@@ -0,0 +1,220 @@
+use std::fs::{self, File};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context, Result};
+use notify::event::ModifyKind;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use polodb_core::bson::oid::ObjectId;
+
+use super::notify::send_due;
+use super::snooze::{parse_snooze, snooze, DEFAULT_SNOOZE};
+use super::{cmd_notify, extract_option};
+use crate::config::Config;
+use crate::db::{db_path, TodoDb};
+use crate::display::{print_info, print_success, print_warning};
+use crate::remind::{format_remind_at, parse_minutes};
+use crate::schedule::set_calendar;
+
+/// Changes are collected for this long before checking, so one command's burst of writes
+/// (and the daemon's own) leads to a single check
+const SETTLE: Duration = Duration::from_millis(300);
+
+/// Push a reminder back for each file in `<data_dir>/snooze`. A file is named after the
+/// todo's number or id and holds a duration like "30m" (empty means 10m); it's removed
+/// once applied.
+fn apply_snoozes(db: &TodoDb, dir: &Path) -> Result<()> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Ok(());
+    };
+    let todos = db.list_all()?;
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        let contents = fs::read_to_string(&path).unwrap_or_default();
+        let duration = match contents.trim() {
+            "" => DEFAULT_SNOOZE.to_string(),
+            text => text.to_string(),
+        };
+        fs::remove_file(&path).with_context(|| format!("failed to remove {}", path.display()))?;
+
+        let todo = match name.parse::<usize>() {
+            Ok(n) => n.checked_sub(1).and_then(|i| todos.get(i)),
+            Err(_) => ObjectId::parse_str(&name)
+                .ok()
+                .and_then(|id| todos.iter().find(|t| t.id == id)),
+        };
+        let Some(todo) = todo else {
+            print_warning(&format!("Snooze file '{}' doesn't name a todo", name));
+            continue;
+        };
+        let minutes = match parse_snooze(&duration) {
+            Ok(minutes) => minutes,
+            Err(e) => {
+                print_warning(&format!("{} ({})", e, todo.title));
+                continue;
+            }
+        };
+
+        let until = snooze(db, todo, minutes)?;
+        print_info(&format!("Snoozed: {} ({})", todo.title, format_remind_at(&until)));
+    }
+    Ok(())
+}
+
+/// One pass: apply snoozes, then send whatever `todo notify` would. Scheduled passes take
+/// part in `todo notify`'s run gap; passes triggered by a change skip it.
+fn check(config: &Config, changed: bool) -> Result<()> {
+    let db = TodoDb::open(config)?;
+    set_calendar(&config.weekend, &db.get_holidays()?)?;
+    apply_snoozes(&db, &config.data_dir()?.join("snooze"))?;
+    if changed {
+        send_due(&db, config)
+    } else {
+        cmd_notify(&db, config)
+    }
+}
+
+/// Watch the database and the snooze folder for writes by other processes
+fn watch(config: &Config) -> Result<(RecommendedWatcher, Receiver<notify::Result<Event>>)> {
+    let snooze = config.data_dir()?.join("snooze");
+    fs::create_dir_all(&snooze).context("failed to create the snooze folder")?;
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx).context("failed to start watching")?;
+    // The database may be a single file or a directory, depending on the storage engine
+    watcher
+        .watch(&db_path(config)?, RecursiveMode::Recursive)
+        .context("failed to watch the database")?;
+    watcher
+        .watch(&snooze, RecursiveMode::NonRecursive)
+        .context("failed to watch the snooze folder")?;
+    Ok((watcher, rx))
+}
+
+/// Whether an event means something was written, as opposed to merely read
+fn is_write(event: &notify::Result<Event>) -> bool {
+    match event {
+        Ok(event) => matches!(
+            event.kind,
+            EventKind::Create(_) | EventKind::Remove(_) | EventKind::Modify(_)
+        ) && !matches!(event.kind, EventKind::Modify(ModifyKind::Metadata(_))),
+        Err(_) => false,
+    }
+}
+
+/// Discard events until things have been quiet for `SETTLE`
+fn settle(rx: &Receiver<notify::Result<Event>>) {
+    while rx.recv_timeout(SETTLE).is_ok() {}
+}
+
+/// Wait up to `timeout` for a write; true if one came (and has settled)
+fn wait_for_write(rx: &Receiver<notify::Result<Event>>, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    loop {
+        let left = deadline.saturating_duration_since(Instant::now());
+        match rx.recv_timeout(left) {
+            Ok(event) if is_write(&event) => {
+                settle(rx);
+                return true;
+            }
+            Ok(_) => {}
+            Err(RecvTimeoutError::Timeout) => return false,
+            Err(RecvTimeoutError::Disconnected) => {
+                std::thread::sleep(left);
+                return false;
+            }
+        }
+    }
+}
+
+/// Start a copy of this daemon in the background, logging to `<data_dir>/daemon.log`
+fn detach(config: &Config, interval: &str) -> Result<()> {
+    let data_dir = config.data_dir()?;
+    let log_path = data_dir.join("daemon.log");
+    let log = File::options()
+        .create(true)
+        .append(true)
+        .open(&log_path)
+        .with_context(|| format!("failed to open {}", log_path.display()))?;
+
+    let exe = std::env::current_exe().context("failed to find the todo executable")?;
+    let mut command = Command::new(exe);
+    command.args(["daemon", "--interval", interval]);
+    if let Some(ref path) = config.db_path {
+        command.args(["--db", path]);
+    } else if let Some(ref name) = config.list {
+        command.args(["--list", name]);
+    }
+    let child = command
+        .stdin(Stdio::null())
+        .stdout(log.try_clone()?)
+        .stderr(log)
+        .spawn()
+        .context("failed to start the daemon")?;
+
+    let pid_path = data_dir.join("daemon.pid");
+    fs::write(&pid_path, child.id().to_string())
+        .with_context(|| format!("failed to write {}", pid_path.display()))?;
+    print_success(&format!(
+        "Reminder daemon running in the background (pid {}), logging to {}",
+        child.id(),
+        log_path.display()
+    ));
+    print_info(&format!("  Stop it with: kill $(cat {})", pid_path.display()));
+    Ok(())
+}
+
+/// `todo daemon [--interval 1m] [--detach]`: check reminders on a timer instead of cron.
+/// The database is opened only while checking, so other commands aren't held up.
+pub fn cmd_daemon(config: &Config, args: Vec<String>) -> Result<()> {
+    let (args, interval) = extract_option(&args, "--interval", Some("-i"));
+    let interval = interval.unwrap_or_else(|| "1m".to_string()).to_lowercase();
+    let minutes = parse_minutes(&interval)
+        .filter(|&m| m > 0)
+        .ok_or_else(|| anyhow!("Invalid interval: {}. Examples: 1m, 5m", interval))?;
+
+    if config.read_only {
+        return Err(anyhow!("The daemon needs to record sent reminders; drop --read-only"));
+    }
+    if args.iter().any(|a| a == "--detach" || a == "-d") {
+        return detach(config, &interval);
+    }
+
+    let interval_time = Duration::from_secs(minutes as u64 * 60);
+    let watched = match watch(config) {
+        Ok(watched) => Some(watched),
+        Err(e) => {
+            print_warning(&format!("{:#}; checking on the interval only", e));
+            None
+        }
+    };
+
+    print_info(&format!(
+        "Checking reminders every {}{} (Ctrl-C to stop)",
+        interval,
+        if watched.is_some() { " and whenever the list changes" } else { "" }
+    ));
+    let mut changed = false;
+    loop {
+        // A failed pass (say, the database is busy) is retried on the next tick
+        if let Err(e) = check(config, changed) {
+            print_warning(&format!("Reminder check failed: {:#}", e));
+        }
+
+        changed = match watched {
+            Some((_, ref rx)) => {
+                // The check's own writes show up as changes; let them go by first
+                settle(rx);
+                wait_for_write(rx, interval_time)
+            }
+            None => {
+                std::thread::sleep(interval_time);
+                false
+            }
+        };
+    }
+}
@@ -0,0 +1,276 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::io::{self, IsTerminal};
+
+use anyhow::{anyhow, Result};
+use polodb_core::bson::oid::ObjectId;
+
+use super::extract_option;
+use crate::db::TodoDb;
+use crate::display::{json_output, print_info, print_success, print_warning, prompt};
+use crate::models::Todo;
+use crate::remind::to_local;
+
+/// How alike two titles must be to count as duplicates, unless `--threshold` says otherwise
+const DEFAULT_THRESHOLD: f64 = 0.85;
+
+/// A title as compared: its words of letters and digits, lowercased and sorted, so
+/// "Buy milk!" and "milk, buy" come out the same
+fn normalized(title: &str) -> Vec<char> {
+    let lower = title.to_lowercase();
+    let mut words: Vec<&str> = lower
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect();
+    words.sort_unstable();
+    words.join(" ").chars().collect()
+}
+
+/// Characters to insert, delete or change to turn `a` into `b`
+fn distance(a: &[char], b: &[char]) -> usize {
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = (above + 1).min(row[j] + 1).min(diagonal + usize::from(ca != cb));
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+/// 1.0 for titles that normalize the same, falling toward 0.0 as more characters differ
+fn similarity(a: &[char], b: &[char]) -> f64 {
+    let longest = a.len().max(b.len());
+    1.0 - distance(a, b) as f64 / longest.max(1) as f64
+}
+
+fn find_root(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
+
+/// Groups of pending todos whose titles are at least `threshold` alike, as positions in
+/// `todos`; a todo alike to any one member joins the whole group
+fn clusters(todos: &[Todo], threshold: f64) -> Vec<Vec<usize>> {
+    let pending: Vec<(usize, Vec<char>)> = todos
+        .iter()
+        .enumerate()
+        .filter(|(_, t)| !t.done)
+        .map(|(i, t)| (i, normalized(&t.title)))
+        .filter(|(_, title)| !title.is_empty())
+        .collect();
+
+    let mut parent: Vec<usize> = (0..pending.len()).collect();
+    for a in 0..pending.len() {
+        for b in a + 1..pending.len() {
+            let (x, y) = (&pending[a].1, &pending[b].1);
+            // Too far apart in length to be that alike, without working it out
+            let longest = x.len().max(y.len()) as f64;
+            if x.len().abs_diff(y.len()) as f64 > (1.0 - threshold) * longest {
+                continue;
+            }
+            if similarity(x, y) >= threshold {
+                let root = find_root(&mut parent, a);
+                let other = find_root(&mut parent, b);
+                parent[other] = root;
+            }
+        }
+    }
+
+    let mut groups: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for (i, (position, _)) in pending.iter().enumerate() {
+        groups.entry(find_root(&mut parent, i)).or_default().push(*position);
+    }
+    let mut groups: Vec<Vec<usize>> = groups.into_values().filter(|g| g.len() > 1).collect();
+    groups.sort();
+    groups
+}
+
+/// Fold duplicates into the one added first. Its title and settings stay; tags and
+/// comments are combined, the others' notes are added to its own, and a due date,
+/// priority, project or estimate it lacks is taken from them. Their subtasks and
+/// dependents move over to it, and they go to the trash.
+fn merge(db: &TodoDb, ids: &[ObjectId]) -> Result<Todo> {
+    let all = db.list_all()?;
+    let mut members: Vec<&Todo> = all.iter().filter(|t| ids.contains(&t.id)).collect();
+    members.sort_by_key(|t| t.created_at);
+    let (first, others) = members
+        .split_first()
+        .ok_or_else(|| anyhow!("Those todos are gone; was the list changed meanwhile?"))?;
+    let gone: HashSet<ObjectId> = others.iter().map(|t| t.id).collect();
+
+    let mut keeper = (*first).clone();
+    for other in others {
+        for tag in &other.tags {
+            if !keeper.tags.contains(tag) {
+                keeper.tags.push(tag.clone());
+            }
+        }
+        if let Some(ref notes) = other.notes {
+            let own = keeper.notes.get_or_insert_default();
+            if !own.contains(notes.as_str()) {
+                if !own.is_empty() {
+                    own.push_str("\n\n");
+                }
+                own.push_str(notes);
+            }
+        }
+        keeper.comments.extend(other.comments.iter().cloned());
+        keeper.due_at = keeper.due_at.or(other.due_at);
+        keeper.priority = keeper.priority.or(other.priority);
+        keeper.project = keeper.project.take().or_else(|| other.project.clone());
+        keeper.estimate_minutes = keeper.estimate_minutes.or(other.estimate_minutes);
+    }
+    keeper.comments.sort_by_key(|c| c.at);
+    keeper.depends_on.retain(|d| !gone.contains(d));
+    if keeper.parent_id.is_some_and(|p| gone.contains(&p)) {
+        keeper.parent_id = None;
+    }
+    db.replace(&keeper)?;
+
+    for todo in all.iter().filter(|t| t.id != keeper.id && !gone.contains(&t.id)) {
+        let child = todo.parent_id.is_some_and(|p| gone.contains(&p));
+        let dependent = todo.depends_on.iter().any(|d| gone.contains(d));
+        if !child && !dependent {
+            continue;
+        }
+        let mut moved = todo.clone();
+        if child {
+            moved.parent_id = Some(keeper.id);
+        }
+        let mut depends_on = Vec::new();
+        for id in todo.depends_on.iter().map(|d| if gone.contains(d) { keeper.id } else { *d }) {
+            if !depends_on.contains(&id) && id != todo.id {
+                depends_on.push(id);
+            }
+        }
+        moved.depends_on = depends_on;
+        db.replace(&moved)?;
+    }
+
+    let trashed: Vec<Todo> = others.iter().map(|t| (*t).clone()).collect();
+    db.trash_many(&trashed)?;
+    Ok(keeper)
+}
+
+/// A cluster as shown: each todo with its number in the cluster and in the list
+fn describe(todos: &[Todo], group: &[usize]) -> String {
+    let mut lines = vec![format!("{} look alike:", group.len())];
+    for (n, &i) in group.iter().enumerate() {
+        let todo = &todos[i];
+        let added = to_local(&todo.created_at)
+            .map(|d| d.format("%Y-%m-%d").to_string())
+            .unwrap_or_default();
+        let mut details = vec![format!("added {}", added)];
+        if !todo.tags.is_empty() {
+            details.push(todo.tags.iter().map(|t| format!("#{}", t)).collect::<Vec<_>>().join(" "));
+        }
+        if todo.notes.is_some() {
+            details.push("notes".to_string());
+        }
+        lines.push(format!(
+            "  {}) #{} {} ({})",
+            n + 1,
+            i + 1,
+            todo.title,
+            details.join(", ")
+        ));
+    }
+    lines.join("\n")
+}
+
+/// `todo dedupe [--threshold 0.85] [--yes]`: find pending todos with near-identical
+/// titles and merge each group into the one added first, asking about every group unless
+/// `--yes` merges them all
+pub fn cmd_dedupe(db: &TodoDb, args: Vec<String>) -> Result<()> {
+    let (args, threshold) = extract_option(&args, "--threshold", None);
+    let threshold = match threshold {
+        Some(t) => t
+            .parse::<f64>()
+            .ok()
+            .filter(|t| (0.0..=1.0).contains(t))
+            .ok_or_else(|| anyhow!("Invalid threshold: {}. Use a number from 0 to 1", t))?,
+        None => DEFAULT_THRESHOLD,
+    };
+    let skip_confirm = args.iter().any(|a| a == "--yes" || a == "-y");
+
+    let todos = db.list_all()?;
+    let groups = clusters(&todos, threshold);
+    if groups.is_empty() {
+        print_success("No duplicates among the pending todos");
+        return Ok(());
+    }
+    let ask = !skip_confirm;
+    if ask && (json_output() || !io::stdin().is_terminal()) {
+        for group in &groups {
+            print_info(&describe(&todos, group));
+        }
+        print_info("Run this in a terminal to choose what to merge, or pass --yes to merge it all");
+        return Ok(());
+    }
+
+    if ask {
+        print_info(&format!(
+            "{} group(s) of look-alike todos. Enter or y merges a group into its first todo, \
+             numbers (\"1 3\") merge just those, n skips, q stops.",
+            groups.len()
+        ));
+    }
+    let mut merged = 0;
+    let mut removed = 0;
+    for group in &groups {
+        let mut chosen: Vec<usize> = group.clone();
+        if ask {
+            println!();
+            print_info(&describe(&todos, group));
+            loop {
+                let Some(answer) = prompt("Merge? [Y/n/1 2…/q]")? else {
+                    return finish(merged, removed);
+                };
+                let picked: Option<BTreeSet<usize>> = answer
+                    .split([' ', ','])
+                    .filter(|p| !p.is_empty())
+                    .map(|p| p.parse::<usize>().ok().and_then(|n| group.get(n.wrapping_sub(1))))
+                    .map(|p| p.copied())
+                    .collect();
+                match answer.to_lowercase().as_str() {
+                    "" | "y" | "yes" => {}
+                    "n" | "no" => chosen.clear(),
+                    "q" | "quit" => return finish(merged, removed),
+                    _ => match picked {
+                        Some(picked) if picked.len() > 1 => chosen = picked.into_iter().collect(),
+                        _ => {
+                            print_warning("Answer y, n, q, or two or more numbers from the group");
+                            continue;
+                        }
+                    },
+                }
+                break;
+            }
+        }
+        if chosen.is_empty() {
+            continue;
+        }
+        let ids: Vec<ObjectId> = chosen.iter().map(|&i| todos[i].id).collect();
+        let keeper = merge(db, &ids)?;
+        merged += 1;
+        removed += ids.len() - 1;
+        if ask {
+            print_success(&format!("Merged {} into \"{}\"", ids.len(), keeper.title));
+        }
+    }
+    finish(merged, removed)
+}
+
+fn finish(merged: usize, removed: usize) -> Result<()> {
+    print_success(&format!(
+        "Merged {} group(s); {} duplicate(s) moved to the trash",
+        merged, removed
+    ));
+    Ok(())
+}
@@ -0,0 +1,327 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Context, Result};
+use polodb_core::bson::{self, Bson, DateTime as BsonDateTime};
+use serde_json::Value;
+
+use super::extract_option;
+use crate::db::TodoDb;
+use crate::display::print_success;
+use crate::ics::render_calendar;
+use crate::json::{bson_to_json, todo_json};
+use crate::models::{Todo, Urgency};
+use crate::remind::to_local;
+
+/// Quote an argument for a POSIX shell
+fn shell_quote(s: &str) -> String {
+    let plain = !s.is_empty()
+        && s
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_.,:/+@%".contains(c));
+    if plain {
+        s.to_string()
+    } else {
+        format!("'{}'", s.replace('\'', r"'\''"))
+    }
+}
+
+/// Absolute local time in the "YYYY-MM-DD HH:MM" form `parse_reminder` accepts
+fn format_when(dt: &BsonDateTime) -> Result<String> {
+    to_local(dt)
+        .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+        .ok_or_else(|| anyhow!("Invalid timestamp in todo"))
+}
+
+/// Escalation steps back in `--escalate` syntax, e.g. "30m:high,60m:critical:push"
+fn format_escalation(todo: &Todo) -> String {
+    todo.escalation
+        .iter()
+        .map(|step| {
+            let urgency = match step.urgency {
+                Urgency::Normal => "normal",
+                Urgency::High => "high",
+                Urgency::Critical => "critical",
+            };
+            match step.backend {
+                Some(ref backend) => format!("{}m:{}:{}", step.after_minutes, urgency, backend),
+                None => format!("{}m:{}", step.after_minutes, urgency),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Shell script of `todo` commands that recreates the pending list in an empty database
+fn export_script(todos: &[Todo]) -> Result<String> {
+    let pending: Vec<&Todo> = todos.iter().filter(|t| !t.done).collect();
+    // Numbers the todos will get when the script is replayed into an empty list
+    let numbers: HashMap<_, usize> =
+        pending.iter().enumerate().map(|(i, t)| (t.id, i + 1)).collect();
+
+    let mut out = String::from("#!/bin/sh\n");
+    out.push_str("# Recreates pending todos. Run against an empty list, e.g. with TODO_DATA_DIR\n");
+    out.push_str("# pointing at a fresh directory; todos are numbered in the order they're added.\n");
+    out.push_str("set -e\n\n");
+
+    let mut depends = Vec::new();
+
+    for (i, todo) in pending.iter().enumerate() {
+        let mut cmd = vec!["todo".to_string(), "add".to_string(), shell_quote(&todo.title)];
+
+        if !todo.tags.is_empty() {
+            cmd.push(format!("--tag {}", shell_quote(&todo.tags.join(","))));
+        }
+        if let Some(minutes) = todo.estimate_minutes {
+            cmd.push(format!("--estimate {}m", minutes));
+        }
+        if let Some(ref project) = todo.project {
+            cmd.push(format!("--project {}", shell_quote(project)));
+        }
+        if let Some(priority) = todo.priority {
+            cmd.push(format!("--priority {}", priority.as_str()));
+        }
+        if let Some(effort) = todo.effort {
+            cmd.push(format!("--effort {}", effort.as_str()));
+        }
+        if let Some(ref location) = todo.location {
+            cmd.push(format!("--location {}", shell_quote(location)));
+        }
+        if let Some(ref assignee) = todo.assigned_to {
+            cmd.push(format!("--assign {}", shell_quote(assignee)));
+        }
+        // Parents are added first; --parent can only name a todo that already exists
+        if let Some(&parent) = todo.parent_id.and_then(|id| numbers.get(&id))
+            && parent <= i
+        {
+            cmd.push(format!("--parent {}", parent));
+        }
+        if let Some(ref start) = todo.start_at {
+            cmd.push(format!("--start {}", shell_quote(&format_when(start)?)));
+        }
+        if let Some(ref due) = todo.due_at {
+            cmd.push(format!("--due {}", shell_quote(&format_when(due)?)));
+            if !todo.notify_before.is_empty() {
+                let leads: Vec<String> =
+                    todo.notify_before.iter().map(|m| format!("{}m", m)).collect();
+                cmd.push(format!("--notify {}", leads.join(",")));
+            }
+        }
+        if let Some(ref review) = todo.review_at {
+            cmd.push(format!("--review {}", shell_quote(&format_when(review)?)));
+        }
+        if let Some(ref rule) = todo.recur {
+            cmd.push(format!("--every {}", shell_quote(rule)));
+        }
+        if let Some(ref rule) = todo.remind_every {
+            cmd.push(format!("-r {}", shell_quote(rule)));
+        } else if let Some(ref remind_at) = todo.remind_at
+            && !todo.notified
+        {
+            cmd.push(format!("-r {}", shell_quote(&format_when(remind_at)?)));
+        }
+        if todo.remind_at.is_some() && !todo.escalation.is_empty() {
+            cmd.push(format!("--escalate {}", shell_quote(&format_escalation(todo))));
+        }
+
+        out.push_str(&cmd.join(" "));
+        out.push('\n');
+        if let Some(ref week) = todo.sprint {
+            out.push_str(&format!("todo sprint assign {} {}\n", i + 1, shell_quote(week)));
+        }
+        if let Some(ref notes) = todo.notes {
+            out.push_str(&format!("todo note {} {}\n", i + 1, shell_quote(notes)));
+        }
+
+        // Dependencies may point forward, so they're set once every todo exists
+        let blockers: Vec<String> = todo
+            .depends_on
+            .iter()
+            .filter_map(|id| numbers.get(id))
+            .map(|n| n.to_string())
+            .collect();
+        if !blockers.is_empty() {
+            depends.push(format!("todo depend {} {}", i + 1, blockers.join(" ")));
+        }
+    }
+
+    if !depends.is_empty() {
+        out.push('\n');
+        for line in depends {
+            out.push_str(&line);
+            out.push('\n');
+        }
+    }
+
+    Ok(out)
+}
+
+/// A todo.txt date (2025-09-01), in local time
+fn todotxt_date(dt: &BsonDateTime) -> String {
+    to_local(dt).map(|d| d.format("%Y-%m-%d").to_string()).unwrap_or_default()
+}
+
+/// Every todo as a todo.txt line (todotxt.org): done ones start with `x` and the day they
+/// were done, the priority is a letter, the project a `+project`, tags `@contexts`, and
+/// the due and start dates `due:` and `t:`
+fn export_todotxt(todos: &[Todo]) -> String {
+    let mut out = String::new();
+    for todo in todos {
+        let mut words = Vec::new();
+        if todo.done {
+            words.push("x".to_string());
+            // A creation date needs a completion date before it
+            words.push(todotxt_date(todo.done_at.as_ref().unwrap_or(&todo.created_at)));
+        } else if let Some(priority) = todo.priority {
+            words.push(format!("({})", priority.letter()));
+        }
+        words.push(todotxt_date(&todo.created_at));
+        words.push(todo.title.split_whitespace().collect::<Vec<_>>().join(" "));
+        if let Some(ref project) = todo.project {
+            words.push(format!("+{}", project.replace(char::is_whitespace, "-")));
+        }
+        for tag in &todo.tags {
+            words.push(format!("@{}", tag.trim_start_matches('@')));
+        }
+        if let Some(ref due) = todo.due_at {
+            words.push(format!("due:{}", todotxt_date(due)));
+        }
+        if let Some(ref start) = todo.start_at {
+            words.push(format!("t:{}", todotxt_date(start)));
+        }
+        // Done lines can't lead with a priority, so it goes in a tag
+        if let Some(priority) = todo.priority.filter(|_| todo.done) {
+            words.push(format!("pri:{}", priority.letter()));
+        }
+        out.push_str(&words.join(" "));
+        out.push('\n');
+    }
+    out
+}
+
+/// Every todo (done ones too) as a JSON array, one object per line
+fn export_json(todos: &[Todo]) -> String {
+    let items: Vec<String> = todos
+        .iter()
+        .enumerate()
+        .map(|(i, todo)| format!("  {}", todo_json(i + 1, todo)))
+        .collect();
+    format!("[\n{}\n]\n", items.join(",\n"))
+}
+
+/// Quote a CSV cell when it holds a comma, quote or line break (RFC 4180)
+fn csv_cell(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// One column per field. Text, dates and ids are written as-is; lists, flags and numbers
+/// as JSON so `todo import` can read them back.
+fn export_csv(todos: &[Todo]) -> Result<String> {
+    let docs = todos
+        .iter()
+        .map(bson::to_document)
+        .collect::<Result<Vec<_>, _>>()
+        .context("failed to encode todos")?;
+    let Some(first) = docs.first() else {
+        return Ok(String::new());
+    };
+
+    let keys: Vec<&String> = first.keys().collect();
+    let mut header = vec!["number".to_string()];
+    header.extend(keys.iter().map(|k| if *k == "_id" { "id".to_string() } else { k.to_string() }));
+    let mut out = header.join(",");
+    out.push('\n');
+
+    for (i, doc) in docs.iter().enumerate() {
+        let mut cells = vec![(i + 1).to_string()];
+        for key in &keys {
+            let cell = match doc.get(key.as_str()) {
+                None | Some(Bson::Null) => String::new(),
+                Some(Bson::String(s)) => s.clone(),
+                Some(Bson::ObjectId(id)) => id.to_hex(),
+                Some(value) => match bson_to_json(value) {
+                    // Dates as their RFC 3339 text, unquoted
+                    Value::String(s) => s,
+                    value => value.to_string(),
+                },
+            };
+            cells.push(csv_cell(&cell));
+        }
+        out.push_str(&cells.join(","));
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// A Markdown checklist with subtasks nested under their parent; each item carries its
+/// full record in an HTML comment so `todo import` restores every field
+fn export_markdown(todos: &[Todo]) -> String {
+    fn push_item(todos: &[Todo], i: usize, depth: usize, out: &mut String) {
+        let todo = &todos[i];
+        let checkbox = if todo.done { "[x]" } else { "[ ]" };
+        out.push_str(&format!(
+            "{}- {} {} <!-- todo {} -->\n",
+            "  ".repeat(depth),
+            checkbox,
+            todo.title.replace('\n', " "),
+            todo_json(i + 1, todo).to_string().replace("--", "\\u002d\\u002d")
+        ));
+        for (child, _) in todos.iter().enumerate().filter(|(_, t)| t.parent_id == Some(todo.id)) {
+            push_item(todos, child, depth + 1, out);
+        }
+    }
+
+    let mut out = String::from("# Todos\n\n");
+    for (i, todo) in todos.iter().enumerate() {
+        let has_parent = todo.parent_id.is_some_and(|p| todos.iter().any(|t| t.id == p));
+        if !has_parent {
+            push_item(todos, i, 0, &mut out);
+        }
+    }
+    out
+}
+
+/// `todo export --format script|md|csv|json|todotxt|ics [file]`: the pending list as
+/// replayable commands, every todo in a format `todo import` reads back, or due dates and
+/// reminders for a calendar app
+pub fn cmd_export(db: &TodoDb, args: Vec<String>) -> Result<()> {
+    let (args, format) = extract_option(&args, "--format", Some("-f"));
+    let todos = db.list_all()?;
+
+    let contents = match format.as_deref() {
+        Some("script") => export_script(&todos)?,
+        Some("md" | "markdown") => export_markdown(&todos),
+        Some("csv") => export_csv(&todos)?,
+        Some("json") => export_json(&todos),
+        Some("todotxt" | "todo.txt" | "txt") => export_todotxt(&todos),
+        Some("ics" | "ical") => render_calendar(&todos),
+        Some(other) => {
+            return Err(anyhow!(
+                "Unknown export format '{}'. Supported: script, md, csv, json, todotxt, ics",
+                other
+            ));
+        }
+        None => {
+            let usage = "Usage: todo export --format script|md|csv|json|todotxt|ics [file]";
+            return Err(anyhow!(usage));
+        }
+    };
+    let count = match format.as_deref() {
+        Some("ics" | "ical") => {
+            todos.iter().filter(|t| t.due_at.is_some() || t.remind_at.is_some()).count()
+        }
+        _ => todos.len(),
+    };
+
+    match args.first() {
+        Some(path) => {
+            std::fs::write(path, contents).with_context(|| format!("failed to write {}", path))?;
+            print_success(&format!("Exported {} todo(s) to {}", count, path));
+        }
+        None => print!("{}", contents),
+    }
+    Ok(())
+}
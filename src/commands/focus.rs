@@ -0,0 +1,49 @@
+use anyhow::Result;
+use polodb_core::bson::DateTime as BsonDateTime;
+
+use super::get_todo_by_index;
+use crate::db::TodoDb;
+use crate::display::{print_focus, print_info, print_success};
+use crate::models::{Focus, Todo};
+
+/// The focused todo with its list number and when focus began, if it's still pending
+pub fn current_focus(db: &TodoDb) -> Result<Option<(usize, Todo, BsonDateTime)>> {
+    let Some(focus) = db.get_focus()? else {
+        return Ok(None);
+    };
+    let todos = db.list_all()?;
+    Ok(todos
+        .into_iter()
+        .enumerate()
+        .find(|(_, t)| t.id == focus.todo_id && !t.done)
+        .map(|(i, t)| (i + 1, t, focus.since)))
+}
+
+/// `todo focus [<#>|clear]`: show, set or end the single task you're working on
+pub fn cmd_focus(db: &TodoDb, args: Vec<String>) -> Result<()> {
+    match args.first().map(String::as_str) {
+        None => {
+            // A focus whose todo was completed or removed counts as no focus
+            match current_focus(db)? {
+                Some((index, todo, since)) => print_focus(index, &todo, &since),
+                None => print_info("No focus set. Pick one with: todo focus <#>"),
+            }
+            Ok(())
+        }
+        Some("clear" | "off" | "none") => {
+            db.set_focus(None)?;
+            print_success("Focus ended");
+            Ok(())
+        }
+        Some(index_str) => {
+            let (index, todo) = get_todo_by_index(db, index_str)?;
+            let focus = Focus {
+                todo_id: todo.id,
+                since: BsonDateTime::now(),
+            };
+            db.set_focus(Some(&focus))?;
+            print_success(&format!("Focusing on #{}: {}", index, todo.title));
+            Ok(())
+        }
+    }
+}
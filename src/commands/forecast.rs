@@ -0,0 +1,134 @@
+use anyhow::{anyhow, Result};
+use chrono::{Duration, Local};
+use polodb_core::bson::DateTime as BsonDateTime;
+
+use super::extract_option;
+use super::stats::completion_days;
+use crate::db::TodoDb;
+use crate::display::print_forecast;
+use crate::i18n;
+use crate::models::Todo;
+use crate::remind::parse_minutes;
+
+/// `todo forecast [--window 28d]`: at the pace todos were finished over the window, when
+/// the pending list would be cleared and which due dates won't be met. With estimates,
+/// pace and backlog are measured in estimated work (a todo without one counts as the
+/// average estimate); otherwise each todo counts the same. Work is assumed to go in due
+/// date order, with undated todos last.
+pub fn cmd_forecast(db: &TodoDb, args: Vec<String>) -> Result<()> {
+    let (_, window) = extract_option(&args, "--window", None);
+    let window = window.unwrap_or_else(|| "28d".to_string());
+    let window_days = parse_minutes(&window.to_lowercase())
+        .map(|m| m / (24 * 60))
+        .filter(|&d| d >= 1)
+        .ok_or_else(|| anyhow!("Invalid window: {}. Examples: 14d, 4w, 3mo", window))?;
+
+    let todos = db.list_all()?;
+    let estimates: Vec<i64> = todos.iter().filter_map(|t| t.estimate_minutes).collect();
+    let pending: Vec<usize> = (0..todos.len()).filter(|&i| !todos[i].done).collect();
+    let by_estimate = !estimates.is_empty()
+        && pending.iter().any(|&i| todos[i].estimate_minutes.is_some());
+    let average = estimates.iter().sum::<i64>() / estimates.len().max(1) as i64;
+    let load = |todo: &Todo| {
+        if by_estimate {
+            todo.estimate_minutes.unwrap_or(average) as f64
+        } else {
+            1.0
+        }
+    };
+
+    let since = Local::now().date_naive() - Duration::days(window_days);
+    let mut finished = 0;
+    let mut finished_load = 0.0;
+    for todo in &todos {
+        let count = completion_days(todo).iter().filter(|&&d| d > since).count();
+        finished += count;
+        finished_load += count as f64 * load(todo);
+    }
+    let per_day = finished_load / window_days as f64;
+    let todos_per_day = finished as f64 / window_days as f64;
+
+    let mut narrative = vec![format!(
+        "Over the last {} day(s) you finished {} todo(s), about {} a day.",
+        window_days,
+        finished,
+        rate(todos_per_day)
+    )];
+    if pending.is_empty() {
+        narrative.push("Nothing is pending, so there's nothing to forecast.".to_string());
+        print_forecast(&todos, &narrative, todos_per_day, None, &[]);
+        return Ok(());
+    }
+    // Todos finished with zero-minute estimates leave no pace in estimated work either
+    if finished == 0 || per_day <= 0.0 {
+        let nothing = if finished == 0 { "nothing" } else { "no estimated work" };
+        narrative.push(format!(
+            "With {} finished in that time there's no pace to forecast {} pending todo(s) \
+             from; try a longer --window.",
+            nothing,
+            pending.len()
+        ));
+        print_forecast(&todos, &narrative, todos_per_day, None, &[]);
+        return Ok(());
+    }
+
+    // Due dates first, soonest first, then the rest in list order
+    let mut order = pending.clone();
+    order.sort_by_key(|&i| todos[i].due_at.map_or(i64::MAX, |d| d.timestamp_millis()));
+    let now = BsonDateTime::now().timestamp_millis();
+    let mut done_load = 0.0;
+    // (position, due, when the forecast has it done) for each todo with a due date
+    let mut rows = Vec::new();
+    for &i in &order {
+        done_load += load(&todos[i]);
+        let Some(due_at) = todos[i].due_at else {
+            continue;
+        };
+        let done_by = now.saturating_add((done_load / per_day * 86_400_000.0) as i64);
+        rows.push((i, due_at, BsonDateTime::from_millis(done_by)));
+    }
+    let backlog = done_load;
+    let days = backlog / per_day;
+    // None when the pace is so slow the date is past what chrono can represent
+    let clear = Duration::try_minutes((days * 24.0 * 60.0) as i64)
+        .and_then(|wait| Local::now().checked_add_signed(wait));
+
+    let work = if by_estimate {
+        format!(" (about {} of estimated work)", i18n::duration(backlog as i64))
+    } else {
+        String::new()
+    };
+    let by = clear.map_or(String::new(), |c| format!(" by {},", c.format("%a %Y-%m-%d")));
+    narrative.push(format!(
+        "At that pace the {} pending todo(s){} would be cleared{} in about {} day(s).",
+        pending.len(),
+        work,
+        by,
+        days.ceil() as i64
+    ));
+    let overdue = rows.iter().filter(|(_, due, _)| due.timestamp_millis() < now).count();
+    let at_risk = rows
+        .iter()
+        .filter(|(_, due, done_by)| due.timestamp_millis() >= now && done_by > due)
+        .count();
+    narrative.push(match (rows.len(), at_risk, overdue) {
+        (0, _, _) => "No pending todo has a due date.".to_string(),
+        (n, 0, 0) => format!("All {} due date(s) look safe.", n),
+        (n, risk, overdue) => format!(
+            "Of {} due date(s), {} are at risk and {} already passed.",
+            n, risk, overdue
+        ),
+    });
+    let clear_by = clear.map(|c| c.date_naive());
+    print_forecast(&todos, &narrative, todos_per_day, clear_by, &rows);
+    Ok(())
+}
+
+/// 0.4, 1.5, 12: a rate with a decimal only while it's small
+fn rate(per_day: f64) -> String {
+    if per_day < 10.0 {
+        format!("{:.1}", per_day)
+    } else {
+        format!("{:.0}", per_day)
+    }
+}
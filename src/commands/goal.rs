@@ -0,0 +1,106 @@
+use anyhow::{anyhow, Result};
+
+use super::{extract_option, get_todo_by_index};
+use crate::db::TodoDb;
+use crate::display::{print_goal_status, print_success};
+use crate::models::Goal;
+use crate::remind::parse_reminder;
+
+pub(super) fn get_goal_by_index(db: &TodoDb, index_str: &str) -> Result<(usize, Goal)> {
+    let index: usize = index_str
+        .parse()
+        .map_err(|_| anyhow!("Invalid goal number: {}. Use a number like 1, 2, 3...", index_str))?;
+
+    let goals = db.list_goals()?;
+    if index == 0 || index > goals.len() {
+        return Err(anyhow!(
+            "Goal #{} not found. You have {} goals.",
+            index,
+            goals.len()
+        ));
+    }
+
+    Ok((index, goals[index - 1].clone()))
+}
+
+/// Dispatch `todo goal <subcommand>`
+pub fn cmd_goal(db: &TodoDb, args: Vec<String>) -> Result<()> {
+    let sub = args.first().map(String::as_str).unwrap_or("status");
+    let rest = args.get(1..).unwrap_or_default().to_vec();
+
+    match sub {
+        "add" => cmd_goal_add(db, rest),
+        "status" | "list" | "ls" => cmd_goal_status(db),
+        "link" => cmd_goal_link(db, rest),
+        "unlink" => cmd_goal_unlink(db, rest),
+        "rm" | "remove" => cmd_goal_remove(db, rest),
+        unknown => Err(anyhow!(
+            "Unknown goal command: {}. Use add, status, link, unlink or rm",
+            unknown
+        )),
+    }
+}
+
+fn cmd_goal_add(db: &TodoDb, args: Vec<String>) -> Result<()> {
+    let (title_args, due_str) = extract_option(&args, "--due", None);
+
+    let title = title_args.join(" ").trim().to_string();
+    if title.is_empty() {
+        return Err(anyhow!(
+            "Missing title. Usage: todo goal add \"Ship v1\" [--due 2025-09-01]"
+        ));
+    }
+
+    let due_at = due_str.as_deref().map(parse_reminder).transpose()?;
+    let goal = Goal::new(title.clone(), due_at);
+    db.insert_goal(&goal)?;
+
+    let index = db.list_goals()?.len();
+    print_success(&format!("Added goal #{}: {}", index, title));
+    Ok(())
+}
+
+fn cmd_goal_status(db: &TodoDb) -> Result<()> {
+    let goals = db.list_goals()?;
+    let todos = db.list_all()?;
+    print_goal_status(&goals, &todos);
+    Ok(())
+}
+
+fn cmd_goal_link(db: &TodoDb, args: Vec<String>) -> Result<()> {
+    if args.len() < 2 {
+        return Err(anyhow!("Usage: todo goal link <todo #> <goal #>"));
+    }
+
+    let (index, todo) = get_todo_by_index(db, &args[0])?;
+    let (goal_index, goal) = get_goal_by_index(db, &args[1])?;
+
+    db.set_goal(&todo.id, Some(goal.id))?;
+    print_success(&format!(
+        "Linked #{} to goal #{}: {}",
+        index, goal_index, goal.title
+    ));
+    Ok(())
+}
+
+fn cmd_goal_unlink(db: &TodoDb, args: Vec<String>) -> Result<()> {
+    let index_str = args
+        .first()
+        .ok_or_else(|| anyhow!("Missing todo number. Usage: todo goal unlink <#>"))?;
+    let (index, todo) = get_todo_by_index(db, index_str)?;
+
+    db.set_goal(&todo.id, None)?;
+    print_success(&format!("Unlinked #{} from its goal: {}", index, todo.title));
+    Ok(())
+}
+
+fn cmd_goal_remove(db: &TodoDb, args: Vec<String>) -> Result<()> {
+    let index_str = args
+        .first()
+        .ok_or_else(|| anyhow!("Missing goal number. Usage: todo goal rm <#>"))?;
+    let (index, goal) = get_goal_by_index(db, index_str)?;
+
+    db.delete_goal(&goal.id)?;
+    print_success(&format!("Removed goal #{}: {}", index, goal.title));
+    Ok(())
+}
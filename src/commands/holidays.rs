@@ -0,0 +1,59 @@
+use anyhow::{anyhow, Result};
+use chrono::{Local, NaiveDate};
+
+use crate::db::TodoDb;
+use crate::display::{print_holidays, print_info, print_success};
+use crate::schedule::add_business_days;
+
+fn parse_dates(args: &[String], usage: &str) -> Result<Vec<NaiveDate>> {
+    if args.is_empty() {
+        return Err(anyhow!("Usage: {}", usage));
+    }
+    args.iter()
+        .map(|d| {
+            NaiveDate::parse_from_str(d, "%Y-%m-%d")
+                .map_err(|_| anyhow!("Invalid date: {}. Use YYYY-MM-DD, e.g. 2025-12-25", d))
+        })
+        .collect()
+}
+
+/// `todo holidays [list]` / `add <date>...` / `remove <date>...`: days that business-day
+/// due dates and schedules skip, on top of the weekend
+pub fn cmd_holidays(db: &TodoDb, args: Vec<String>) -> Result<()> {
+    let mut holidays = db.get_holidays()?;
+    match args.first().map(String::as_str) {
+        None | Some("list") => {
+            if holidays.is_empty() {
+                print_info("No holidays. Add one with: todo holidays add 2025-12-25");
+                return Ok(());
+            }
+            print_holidays(&holidays);
+            if let Some(next) = add_business_days(Local::now().date_naive(), 1) {
+                print_info(&format!("Next business day: {}", next.format("%Y-%m-%d %a")));
+            }
+            Ok(())
+        }
+        Some("add") => {
+            let dates = parse_dates(&args[1..], "todo holidays add <YYYY-MM-DD>...")?;
+            let before = holidays.len();
+            holidays.extend(dates);
+            holidays.sort();
+            holidays.dedup();
+            db.set_holidays(&holidays)?;
+            print_success(&format!("Added {} holiday(s)", holidays.len() - before));
+            Ok(())
+        }
+        Some("remove" | "rm") => {
+            let dates = parse_dates(&args[1..], "todo holidays remove <YYYY-MM-DD>...")?;
+            let before = holidays.len();
+            holidays.retain(|d| !dates.contains(d));
+            db.set_holidays(&holidays)?;
+            print_success(&format!("Removed {} holiday(s)", before - holidays.len()));
+            Ok(())
+        }
+        Some(other) => Err(anyhow!(
+            "Unknown holidays command '{}'. Use: todo holidays [list|add|remove]",
+            other
+        )),
+    }
+}
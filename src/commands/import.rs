@@ -0,0 +1,405 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use chrono::NaiveDate;
+use polodb_core::bson::{self, oid::ObjectId, DateTime as BsonDateTime};
+use serde_json::{Map, Value};
+
+use super::extract_option;
+use crate::config::Config;
+use crate::db::TodoDb;
+use crate::display::{print_import_plan, print_info, print_success, print_warning};
+use crate::json::todo_from_json;
+use crate::models::{Priority, Todo};
+use crate::remind::parse_reminder;
+
+/// Turn a heading into a tag: "Home Repairs" -> "home-repairs"
+fn heading_tag(heading: &str) -> String {
+    heading
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// `- [ ] task` / `* [x] task` -> (indent, done, title)
+fn parse_checklist_line(line: &str) -> Option<(usize, bool, String)> {
+    let indent: usize = line
+        .chars()
+        .take_while(|c| c.is_whitespace())
+        .map(|c| if c == '\t' { 4 } else { 1 })
+        .sum();
+    let rest = line.trim_start();
+    let rest = rest
+        .strip_prefix("- ")
+        .or_else(|| rest.strip_prefix("* "))
+        .or_else(|| rest.strip_prefix("+ "))?;
+
+    let (done, title) = if let Some(title) = rest.strip_prefix("[ ]") {
+        (false, title)
+    } else if let Some(title) = rest
+        .strip_prefix("[x]")
+        .or_else(|| rest.strip_prefix("[X]"))
+    {
+        (true, title)
+    } else {
+        return None;
+    };
+
+    let title = title.trim();
+    (!title.is_empty()).then(|| (indent, done, title.to_string()))
+}
+
+/// Checklist items from a Markdown file; nested items become subtasks of the item above
+/// them, and the nearest heading becomes a tag
+fn parse_markdown(contents: &str, owner: Option<String>) -> Result<Vec<Todo>> {
+    let mut todos = Vec::new();
+    let mut heading: Option<String> = None;
+    // Open ancestors as (indent, id), innermost last
+    let mut parents: Vec<(usize, ObjectId)> = Vec::new();
+
+    for line in contents.lines() {
+        if let Some(text) = line.trim_start().strip_prefix('#') {
+            let tag = heading_tag(text.trim_start_matches('#'));
+            heading = (!tag.is_empty()).then_some(tag);
+            parents.clear();
+            continue;
+        }
+
+        let Some((indent, done, title)) = parse_checklist_line(line) else {
+            continue;
+        };
+
+        while parents.last().is_some_and(|&(i, _)| i >= indent) {
+            parents.pop();
+        }
+
+        // Items written by `todo export --format md` carry their full record
+        if let Some((_, record)) = title.split_once("<!-- todo ")
+            && let Some(record) = record.trim_end().strip_suffix("-->")
+        {
+            let value: Value = serde_json::from_str(record.trim())
+                .with_context(|| format!("invalid todo record in: {}", line.trim()))?;
+            let todo = todo_from_json(&value)?;
+            parents.push((indent, todo.id));
+            todos.push(todo);
+            continue;
+        }
+
+        let mut todo = Todo::new(title);
+        todo.done = done;
+        if done {
+            todo.done_at = Some(BsonDateTime::now());
+        }
+        todo.parent_id = parents.last().map(|&(_, id)| id);
+        todo.tags = heading.iter().cloned().collect();
+        todo.owner = owner.clone();
+
+        parents.push((indent, todo.id));
+        todos.push(todo);
+    }
+
+    Ok(todos)
+}
+
+/// A todo.txt date (2025-09-01), at `reminder_time`
+fn todotxt_date(word: &str) -> Option<BsonDateTime> {
+    NaiveDate::parse_from_str(word, "%Y-%m-%d").ok()?;
+    parse_reminder(word).ok()
+}
+
+/// Todos from a todo.txt file (todotxt.org): `x` marks done ones, `(A)` to `(D)` the
+/// priority, the first `+project` the project (later ones become tags), `@contexts` tags,
+/// and `due:` and `t:` the due and start dates. Other `key:value` pairs stay in the title.
+fn parse_todotxt(contents: &str, owner: Option<String>) -> Vec<Todo> {
+    let mut todos = Vec::new();
+    for line in contents.lines() {
+        let mut words = line.split_whitespace().peekable();
+        let done = words.next_if_eq(&"x").is_some();
+        let done_at = if done { words.next_if(|w| todotxt_date(w).is_some()) } else { None };
+        let mut priority = words
+            .next_if(|w| w.len() == 3 && w.starts_with('(') && w.ends_with(')'))
+            .and_then(|w| w[1..].chars().next())
+            .and_then(Priority::from_letter);
+        let created_at = words.next_if(|w| todotxt_date(w).is_some());
+
+        let (mut title, mut project, mut tags) = (Vec::new(), None, Vec::new());
+        let (mut due_at, mut start_at) = (None, None);
+        for word in words {
+            let date = |key: &str| word.strip_prefix(key).and_then(todotxt_date);
+            if let Some(name) = word.strip_prefix('+').filter(|n| !n.is_empty()) {
+                if project.is_none() {
+                    project = Some(name.to_string());
+                } else {
+                    tags.push(name.to_string());
+                }
+            } else if let Some(context) = word.strip_prefix('@').filter(|c| !c.is_empty()) {
+                tags.push(context.to_string());
+            } else if let Some(due) = date("due:") {
+                due_at = Some(due);
+            } else if let Some(start) = date("t:") {
+                start_at = Some(start);
+            } else if let Some(letter) = word.strip_prefix("pri:").and_then(|p| p.chars().next())
+                && let Some(found) = Priority::from_letter(letter)
+            {
+                priority = Some(found);
+            } else {
+                title.push(word);
+            }
+        }
+        if title.is_empty() {
+            continue;
+        }
+
+        let mut todo = Todo::new(title.join(" "));
+        if let Some(created_at) = created_at.and_then(todotxt_date) {
+            todo.created_at = created_at;
+        }
+        todo.done = done;
+        if done {
+            todo.done_at = Some(done_at.and_then(todotxt_date).unwrap_or_else(BsonDateTime::now));
+        }
+        todo.priority = priority;
+        todo.project = project;
+        todo.tags = tags;
+        todo.due_at = due_at;
+        todo.start_at = start_at;
+        todo.owner = owner.clone();
+        todos.push(todo);
+    }
+    todos
+}
+
+/// Split CSV text into rows of cells (RFC 4180: quoted cells may hold commas, doubled
+/// quotes and line breaks)
+fn parse_csv(contents: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut cell = String::new();
+    let mut quoted = false;
+    let mut chars = contents.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                cell.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => row.push(std::mem::take(&mut cell)),
+            '\n' if !quoted => {
+                row.push(std::mem::take(&mut cell));
+                rows.push(std::mem::take(&mut row));
+            }
+            '\r' if !quoted => {}
+            c => cell.push(c),
+        }
+    }
+    if !cell.is_empty() || !row.is_empty() {
+        row.push(cell);
+        rows.push(row);
+    }
+    rows
+}
+
+/// Columns `todo export --format csv` writes as JSON rather than plain text
+const CSV_JSON_COLUMNS: &[&str] = &[
+    "number",
+    "done",
+    "notified",
+    "inbox",
+    "escalation_step",
+    "estimate_minutes",
+    "depends_on",
+    "tags",
+    "git",
+    "escalation",
+    "history",
+    "comments",
+    "notify_before",
+    "notifications",
+];
+
+/// Todos from a CSV export; empty cells fall back to the field's default
+fn parse_csv_todos(contents: &str) -> Result<Vec<Todo>> {
+    let mut rows = parse_csv(contents).into_iter();
+    let Some(header) = rows.next() else {
+        return Ok(Vec::new());
+    };
+
+    rows.filter(|row| row.iter().any(|cell| !cell.is_empty()))
+        .enumerate()
+        .map(|(n, row)| {
+            let mut record = Map::new();
+            for (key, cell) in header.iter().zip(row) {
+                if cell.is_empty() {
+                    continue;
+                }
+                let value = if CSV_JSON_COLUMNS.contains(&key.as_str()) {
+                    serde_json::from_str(&cell)
+                        .with_context(|| format!("row {}: invalid {} '{}'", n + 2, key, cell))?
+                } else {
+                    Value::String(cell)
+                };
+                record.insert(key.clone(), value);
+            }
+            todo_from_json(&Value::Object(record)).with_context(|| format!("row {}", n + 2))
+        })
+        .collect()
+}
+
+/// How each field of the source maps onto a todo: (source, todo field or what happens to
+/// it, whether it's understood)
+fn field_mapping(format: &str, contents: &str) -> Result<Vec<(String, String, bool)>> {
+    let row = |source: &str, field: &str| (source.to_string(), field.to_string(), true);
+    if matches!(format, "md" | "markdown") {
+        let mut mapping = vec![
+            row("- [ ] / - [x]", "done"),
+            row("item text", "title"),
+            row("indentation", "parent_id (subtask of the item above)"),
+            row("# heading", "tags"),
+        ];
+        if contents.contains("<!-- todo ") {
+            mapping.push(row("<!-- todo {...} -->", "every field, as exported"));
+        }
+        return Ok(mapping);
+    }
+    if format == "todotxt" {
+        return Ok(vec![
+            row("x <date>", "done, done_at"),
+            row("(A) (B) (C) (D)", "priority: critical, high, medium, low"),
+            row("<date>", "created_at"),
+            row("+project", "project (the first; later ones become tags)"),
+            row("@context", "tags"),
+            row("due:<date>", "due_at"),
+            row("t:<date>", "start_at"),
+            row("pri:<letter>", "priority (of a done item)"),
+            row("other text", "title"),
+        ]);
+    }
+
+    // Columns or keys, in the order they first appear
+    let mut keys: Vec<String> = Vec::new();
+    if format == "csv" {
+        keys = parse_csv(contents).into_iter().next().unwrap_or_default();
+    } else {
+        let values: Vec<Value> = serde_json::from_str(contents).unwrap_or_default();
+        for key in values.iter().filter_map(Value::as_object).flat_map(|o| o.keys()) {
+            if !keys.contains(key) {
+                keys.push(key.clone());
+            }
+        }
+    }
+
+    let fields = bson::to_document(&Todo::new(String::new())).context("failed to encode todo")?;
+    Ok(keys
+        .into_iter()
+        .map(|key| match key.as_str() {
+            "number" => (key, "not kept: list numbers are given out again".to_string(), true),
+            "id" | "_id" => (key, "id (matched against your list)".to_string(), true),
+            k if fields.contains_key(k) => (key.clone(), key, true),
+            _ => (key, "ignored: not a todo field".to_string(), false),
+        })
+        .collect())
+}
+
+/// `todo import [--format md|csv|json|todotxt] [--dry-run] <file>`: add todos from a
+/// Markdown checklist, a todo.txt file or a `todo export` file, skipping any whose id is already in the list.
+/// `--dry-run` shows what would happen and how fields map, without changing anything.
+pub fn cmd_import(db: &TodoDb, config: &Config, args: Vec<String>) -> Result<()> {
+    let (args, format) = extract_option(&args, "--format", Some("-f"));
+    let dry_run = args.iter().any(|a| a == "--dry-run");
+    let args: Vec<String> = args.into_iter().filter(|a| a != "--dry-run").collect();
+    let usage = "Usage: todo import [--format md|csv|json|todotxt] [--dry-run] <file>";
+    let path = args.first().ok_or_else(|| anyhow!(usage))?;
+
+    // Without --format, go by the file extension
+    let format = format.unwrap_or_else(|| {
+        Path::new(path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default()
+            .to_lowercase()
+    });
+    let format = match format.as_str() {
+        "todo.txt" | "txt" => "todotxt".to_string(),
+        _ => format,
+    };
+    let contents =
+        std::fs::read_to_string(path).with_context(|| format!("failed to read {}", path))?;
+
+    let todos = match format.as_str() {
+        "md" | "markdown" => parse_markdown(&contents, config.user_name())?,
+        "csv" => parse_csv_todos(&contents)?,
+        "todotxt" => parse_todotxt(&contents, config.user_name()),
+        "json" => {
+            let values: Vec<Value> = serde_json::from_str(&contents)
+                .with_context(|| format!("{} is not a JSON array of todos", path))?;
+            values.iter().map(todo_from_json).collect::<Result<_>>()?
+        }
+        "" => return Err(anyhow!(usage)),
+        other => {
+            return Err(anyhow!(
+                "Unknown import format '{}'. Supported: md, csv, json, todotxt",
+                other
+            ));
+        }
+    };
+
+    if todos.is_empty() {
+        print_info(&format!("No todos found in {}", path));
+        return Ok(());
+    }
+
+    // What happens to each todo: None to create it, or why it's skipped
+    let existing: HashSet<ObjectId> = db.list_all()?.iter().map(|t| t.id).collect();
+    let mut seen = HashSet::new();
+    let plan: Vec<(&Todo, Option<&str>)> = todos
+        .iter()
+        .map(|t| {
+            let skip = if existing.contains(&t.id) {
+                Some("already in your list")
+            } else if !seen.insert(t.id) {
+                Some("repeated in the file")
+            } else {
+                None
+            };
+            (t, skip)
+        })
+        .collect();
+    let mapping = field_mapping(&format, &contents)?;
+
+    if dry_run {
+        print_import_plan(&format, &mapping, &plan);
+        return Ok(());
+    }
+
+    let mut imported = Vec::new();
+    for &(todo, skip) in &plan {
+        if skip.is_none() {
+            db.insert(todo)?;
+            imported.push(todo);
+        }
+    }
+
+    let skipped = plan.len() - imported.len();
+    let done = imported.iter().filter(|t| t.done).count();
+    let subtasks = imported.iter().filter(|t| t.parent_id.is_some()).count();
+    print_success(&format!(
+        "Imported {} todo(s) from {} ({} done, {} subtask(s))",
+        imported.len(),
+        path,
+        done,
+        subtasks
+    ));
+    if skipped > 0 {
+        print_info(&format!("  Skipped {} already in your list or repeated", skipped));
+    }
+    let ignored: Vec<&str> = mapping.iter().filter(|m| !m.2).map(|m| m.0.as_str()).collect();
+    if !ignored.is_empty() {
+        print_warning(&format!("  Ignored: {}", ignored.join(", ")));
+    }
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::config::{LIST_COLUMNS, PROJECT_CONFIG};
+use crate::display::{print_info, print_success};
+use crate::git;
+
+/// What a new project config starts as: every setting there is, commented out
+fn template() -> String {
+    format!(
+        "# How `todo list` looks inside this project. Uncomment what you want to change.\n\
+         \n\
+         # Filter terms used when `todo list` is given none (`todo list --all` skips them)\n\
+         # filter = \"+myproject status:pending\"\n\
+         \n\
+         # Order: default, created, title, due, remind, priority, done or urgency\n\
+         # sort = \"due\"\n\
+         \n\
+         # Optional columns to show: {}\n\
+         # columns = [\"priority\", \"due\", \"age\"]\n",
+        LIST_COLUMNS.join(", ")
+    )
+}
+
+/// `todo init [--force]`: create `.todo/config.toml` at the top of the git repository
+/// (or in the working directory outside one) for `todo list` to use there
+pub fn cmd_init(args: Vec<String>) -> Result<()> {
+    let force = args.iter().any(|a| a == "--force");
+    let root = match git::current_repo() {
+        Some(repo) => PathBuf::from(repo),
+        None => std::env::current_dir().context("failed to read current directory")?,
+    };
+    let path = root.join(PROJECT_CONFIG);
+    if path.exists() && !force {
+        return Err(anyhow!("{} already exists; --force replaces it", path.display()));
+    }
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    }
+    fs::write(&path, template()).with_context(|| format!("failed to write {}", path.display()))?;
+    print_success(&format!("Created {}", path.display()));
+    print_info("  Edit it to set the filter, sort and columns `todo list` uses in this project");
+    Ok(())
+}
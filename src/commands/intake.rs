@@ -0,0 +1,151 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, IsTerminal};
+
+use anyhow::{anyhow, Context, Result};
+use polodb_core::bson::oid::ObjectId;
+
+use super::cmd_add;
+use crate::config::Config;
+use crate::db::TodoDb;
+use crate::display::{json_output, print_info, print_success, prompt};
+
+/// Spoken lead-ins that say "this is a todo" without being part of it (any case)
+const LEAD_INS: &[&str] = &[
+    "and then ", "and ", "then ", "also ", "so ", "okay ", "ok ", "um ", "uh ", "oh ",
+    "remind me to ", "don't forget to ", "dont forget to ", "i need to ", "need to ",
+    "i have to ", "have to ", "i should ", "i must ", "i want to ", "todo: ",
+];
+
+/// Break a line after each sentence: a `.`, `?`, `!` or `;` followed by a space
+fn sentences(line: &str) -> Vec<&str> {
+    let mut found = Vec::new();
+    let mut start = 0;
+    let mut chars = line.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let ends = matches!(c, '.' | '?' | '!' | ';')
+            && chars.peek().is_some_and(|(_, next)| next.is_whitespace());
+        if ends {
+            found.push(&line[start..=i]);
+            start = i + 1;
+        }
+    }
+    found.push(&line[start..]);
+    found
+}
+
+/// A sentence as a title: list markers, lead-ins and closing punctuation taken off
+fn clean(sentence: &str) -> String {
+    let mut text = sentence.trim();
+    for marker in ["- [ ] ", "[ ] ", "- ", "* ", "• "] {
+        text = text.strip_prefix(marker).unwrap_or(text);
+    }
+    // "1. " and "2) "
+    let digits = text.len() - text.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if digits > 0
+        && let Some(rest) = [". ", ") "].iter().find_map(|m| text[digits..].strip_prefix(m))
+    {
+        text = rest;
+    }
+
+    let mut text = text.trim().to_string();
+    let starts = |text: &str, lead: &str| {
+        text.get(..lead.len()).is_some_and(|p| p.eq_ignore_ascii_case(lead))
+    };
+    while let Some(lead) = LEAD_INS.iter().find(|l| starts(&text, l)) {
+        text = text[lead.len()..].trim_start().to_string();
+    }
+    text.trim_end_matches(['.', '?', '!', ';', ',']).trim().to_string()
+}
+
+/// Candidate todos in a dumped transcript or note: one per line, and one per sentence
+/// within a line. Bits with fewer than three letters or digits ("ok.", "um") are dropped.
+fn candidates(text: &str) -> Vec<String> {
+    text.lines()
+        .flat_map(sentences)
+        .map(clean)
+        .filter(|c| c.chars().filter(|c| c.is_alphanumeric()).count() >= 3)
+        .collect()
+}
+
+/// `todo add --from-file note.txt [--split] [--yes] [add options]`: the whole file as one
+/// todo (first line the title, the rest its notes), or with `--split`, a todo for each
+/// line or sentence, confirming each. Other options apply to every todo added.
+pub(super) fn add_from_file(
+    db: &TodoDb,
+    config: &Config,
+    path: &str,
+    args: Vec<String>,
+) -> Result<()> {
+    let text = fs::read_to_string(path).with_context(|| format!("failed to read {}", path))?;
+    let split = args.iter().any(|a| a == "--split");
+    let skip_confirm = args.iter().any(|a| a == "--yes" || a == "-y");
+    let options: Vec<String> = args
+        .into_iter()
+        .filter(|a| !matches!(a.as_str(), "--split" | "--yes" | "-y"))
+        .collect();
+    let add = |title: String| {
+        let mut args = vec![title];
+        args.extend(options.iter().cloned());
+        cmd_add(db, config, args)
+    };
+
+    if !split {
+        let mut lines = text.lines().map(str::trim).filter(|l| !l.is_empty());
+        let title = lines.next().ok_or_else(|| anyhow!("{} is empty", path))?;
+        let before: HashSet<ObjectId> = db.list_all()?.iter().map(|t| t.id).collect();
+        add(title.to_string())?;
+        let notes = lines.collect::<Vec<_>>().join("\n");
+        if !notes.is_empty()
+            && let Some(todo) = db.list_all()?.iter().find(|t| !before.contains(&t.id))
+        {
+            db.set_notes(&todo.id, Some(&notes))?;
+        }
+        return Ok(());
+    }
+
+    let found = candidates(&text);
+    if found.is_empty() {
+        print_info(&format!("Nothing in {} looks like a todo", path));
+        return Ok(());
+    }
+    if !skip_confirm && (json_output() || !io::stdin().is_terminal()) {
+        return Err(anyhow!(
+            "Found {} candidate(s) but no one to confirm them; pass --yes to add them all",
+            found.len()
+        ));
+    }
+
+    print_info(&format!(
+        "{} candidate(s) in {}. Enter or y adds, n skips, e edits, q stops.",
+        found.len(),
+        path
+    ));
+    let mut added = 0;
+    for (i, candidate) in found.iter().enumerate() {
+        let mut title = candidate.clone();
+        if !skip_confirm {
+            let question = format!("({}/{}) \"{}\" [Y/n/e/q]", i + 1, found.len(), candidate);
+            let Some(answer) = prompt(&question)? else {
+                break;
+            };
+            match answer.to_lowercase().as_str() {
+                "" | "y" | "yes" => {}
+                "n" | "no" => continue,
+                "e" | "edit" => match prompt("Title:")? {
+                    Some(edited) if !edited.is_empty() => title = edited,
+                    _ => continue,
+                },
+                "q" | "quit" => break,
+                _ => {
+                    print_info("Skipped; answer y, n, e or q");
+                    continue;
+                }
+            }
+        }
+        add(title)?;
+        added += 1;
+    }
+    print_success(&format!("Added {} of {} candidate(s) from {}", added, found.len(), path));
+    Ok(())
+}
@@ -0,0 +1,82 @@
+use std::fs;
+
+use anyhow::{Context, Result};
+
+use crate::config::Config;
+use crate::db::TodoDb;
+use crate::display::{print_info, print_lists, print_success};
+
+/// Names of every list with a database: "default" first, then the rest alphabetically
+fn list_names(config: &Config) -> Result<Vec<String>> {
+    let mut names = vec!["default".to_string()];
+    if let Ok(entries) = fs::read_dir(config.data_dir()?.join("lists")) {
+        let mut named: Vec<String> = entries
+            .flatten()
+            .filter_map(|e| {
+                let name = e.file_name().to_string_lossy().to_string();
+                name.strip_suffix(".db").map(String::from)
+            })
+            .collect();
+        named.sort();
+        names.extend(named);
+    }
+    Ok(names)
+}
+
+/// `todo lists`: every named list with its pending and done counts; the one in use is
+/// marked
+pub fn cmd_lists(config: &Config) -> Result<()> {
+    let current = match config.list {
+        Some(ref name) => name.clone(),
+        None => config.current_list()?,
+    };
+
+    let mut rows = Vec::new();
+    for name in list_names(config)? {
+        let path = config.list_path(&name)?;
+        let (pending, done) = if path.exists() {
+            let todos = TodoDb::open_path(&path, true)
+                .and_then(|db| db.list_all())
+                .with_context(|| format!("failed to read list '{}'", name))?;
+            let done = todos.iter().filter(|t| t.done).count();
+            (todos.len() - done, done)
+        } else {
+            (0, 0)
+        };
+        let in_use = name == current;
+        rows.push((name, pending, done, in_use));
+    }
+    if !rows.iter().any(|r| r.3) {
+        rows.push((current, 0, 0, true));
+    }
+
+    print_lists(&rows);
+    if config.db_path.is_some() {
+        print_info("db_path / TODO_DB_PATH / --db is set, so commands use that file instead");
+    }
+    Ok(())
+}
+
+/// `todo use [name]`: switch every later command to a named list (created on first use);
+/// without a name, say which list is in use
+pub fn cmd_use(config: &Config, args: Vec<String>) -> Result<()> {
+    let Some(name) = args.first() else {
+        print_info(&format!("Using list '{}'", config.current_list()?));
+        return Ok(());
+    };
+
+    let path = config.list_path(name)?;
+    let data_dir = config.data_dir()?;
+    fs::create_dir_all(&data_dir).context("failed to create data directory")?;
+    fs::write(data_dir.join("current-list"), name).context("failed to switch lists")?;
+
+    if path.exists() {
+        print_success(&format!("Switched to list '{}'", name));
+    } else {
+        print_success(&format!("Switched to new list '{}'", name));
+    }
+    if config.list.is_some() || config.db_path.is_some() {
+        print_info("--list, --db, TODO_LIST, TODO_DB_PATH or the config still take precedence");
+    }
+    Ok(())
+}
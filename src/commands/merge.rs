@@ -0,0 +1,297 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use polodb_core::bson::{self, oid::ObjectId, Bson, Document};
+use serde::{Deserialize, Serialize};
+
+use super::note::edit_in_editor;
+use super::undo::write_synced;
+use crate::config::Config;
+use crate::db::{db_path, TodoDb};
+use crate::display::{json_output, print_info, print_success, print_warning, prompt};
+use crate::json::{bson_to_json, json_to_bson};
+use crate::models::Todo;
+use crate::snapshot;
+use crate::sync::device_name;
+
+/// The list as it was after the last merge: the common ancestor that tells an edit on one
+/// side apart from an edit on both
+#[derive(Serialize, Deserialize)]
+struct Base {
+    todos: Vec<Todo>,
+}
+
+/// One base per source, since each copy was last merged at a different point
+fn base_path(config: &Config, source: &str) -> Result<PathBuf> {
+    let source: String = source
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .collect();
+    Ok(db_path(config)?.with_extension(format!("merge-base.{}.bson", source)))
+}
+
+fn load_base(config: &Config, source: &str) -> Result<HashMap<ObjectId, Document>> {
+    let path = base_path(config, source)?;
+    let Ok(bytes) = fs::read(&path) else {
+        return Ok(HashMap::new());
+    };
+    let base: Base = bson::from_slice(&bytes)
+        .with_context(|| format!("failed to read {}; delete it to carry on", path.display()))?;
+    documents(&base.todos)
+}
+
+fn documents(todos: &[Todo]) -> Result<HashMap<ObjectId, Document>> {
+    todos
+        .iter()
+        .map(|t| Ok((t.id, bson::to_document(t).context("failed to encode todo")?)))
+        .collect()
+}
+
+/// Settles fields both copies changed differently: remembered choices first, then the user
+struct Resolver {
+    prefer: HashMap<String, String>,
+    interactive: bool,
+    /// Conflicts left for later when there's no one to ask
+    unresolved: Vec<String>,
+    remembered: bool,
+    asked: usize,
+}
+
+impl Resolver {
+    fn resolve(&mut self, title: &str, key: &str, local: &Bson, remote: &Bson) -> Result<Bson> {
+        match self.prefer.get(key).map(String::as_str) {
+            Some("local") => return Ok(local.clone()),
+            Some("remote") => return Ok(remote.clone()),
+            _ => {}
+        }
+        if !self.interactive {
+            self.unresolved.push(format!("{}: {}", title, key));
+            return Ok(local.clone());
+        }
+
+        self.asked += 1;
+        print_warning(&format!("Both copies changed {} of \"{}\"", key, title));
+        print_info(&format!("  local:  {}", bson_to_json(local)));
+        print_info(&format!("  remote: {}", bson_to_json(remote)));
+        loop {
+            let answer = prompt("Keep [l]ocal, [r]emote or [e]dit? (L/R: always, for this field)")?
+                .ok_or_else(|| anyhow!("Merge cancelled; nothing was changed"))?;
+            match answer.as_str() {
+                "l" | "local" => return Ok(local.clone()),
+                "r" | "remote" => return Ok(remote.clone()),
+                "L" | "R" => {
+                    let side = if answer == "L" { "local" } else { "remote" };
+                    self.prefer.insert(key.to_string(), side.to_string());
+                    self.remembered = true;
+                    return Ok(if answer == "L" { local } else { remote }.clone());
+                }
+                "e" | "edit" => return edit_value(key, local, remote),
+                _ => print_info("Answer l, r, e, L or R"),
+            }
+        }
+    }
+}
+
+/// Plain text for strings, JSON for anything else
+fn editable(value: &Bson) -> String {
+    match value {
+        Bson::String(s) => s.clone(),
+        other => bson_to_json(other).to_string(),
+    }
+}
+
+/// Both values between conflict markers in $EDITOR; what's saved becomes the field
+fn edit_value(key: &str, local: &Bson, remote: &Bson) -> Result<Bson> {
+    let text = format!(
+        "<<<<<<< local\n{}\n=======\n{}\n>>>>>>> remote\n",
+        editable(local),
+        editable(remote)
+    );
+    let edited = edit_in_editor(&text, &format!("merge-{}", key))?;
+    if edited
+        .lines()
+        .any(|l| l.starts_with("<<<<<<<") || l.starts_with("=======") || l.starts_with(">>>>>>>"))
+    {
+        return Err(anyhow!("Conflict markers left in the merged {}; nothing was changed", key));
+    }
+
+    let edited = edited.trim_end_matches(['\n', '\r']);
+    if matches!(local, Bson::String(_)) && matches!(remote, Bson::String(_)) {
+        return Ok(Bson::String(edited.to_string()));
+    }
+    let value = serde_json::from_str(edited)
+        .with_context(|| format!("the merged {} is not valid JSON", key))?;
+    json_to_bson(key, &value)
+}
+
+/// Field-by-field three-way merge of one todo both copies have
+fn merge_fields(
+    local: &Document,
+    remote: &Document,
+    base: Option<&Document>,
+    resolver: &mut Resolver,
+) -> Result<Document> {
+    let title = local.get_str("title").unwrap_or_default();
+    let keys: BTreeSet<&String> = local.keys().chain(remote.keys()).collect();
+    let mut merged = local.clone();
+    for key in keys {
+        let (l, r) = (local.get(key), remote.get(key));
+        if l == r {
+            continue;
+        }
+        let b = base.and_then(|b| b.get(key));
+        let value = if b == l {
+            r.cloned()
+        } else if b == r {
+            l.cloned()
+        } else {
+            let null = Bson::Null;
+            Some(resolver.resolve(title, key, l.unwrap_or(&null), r.unwrap_or(&null))?)
+        };
+        match value {
+            Some(value) => merged.insert(key.clone(), value),
+            None => merged.remove(key),
+        };
+    }
+    Ok(merged)
+}
+
+/// `todo merge <other.db|sync folder> [--forget]`: bring in changes from another copy of
+/// the database (one a sync fetched from another machine), or from every other device's
+/// snapshot in a `sync_dir`. Fields only one copy changed since the last merge are taken
+/// as is; fields both changed are asked about.
+pub fn cmd_merge(db: &TodoDb, config: &Config, args: Vec<String>) -> Result<()> {
+    if args.iter().any(|a| a == "--forget") {
+        let count = db.get_merge_preferences()?.len();
+        db.set_merge_preferences(&HashMap::new())?;
+        print_success(&format!("Forgot {} remembered merge choice(s)", count));
+        return Ok(());
+    }
+    let usage = "Usage: todo merge <other.db|sync folder>  (--forget drops remembered choices)";
+    let path = args.first().ok_or_else(|| anyhow!(usage))?;
+    let path = Path::new(path);
+
+    if path.is_dir() {
+        let device = device_name(config)?.unwrap_or_default();
+        let snapshots = snapshot::read_others(path, &device)?;
+        if snapshots.is_empty() {
+            print_info(&format!("No other device has left a snapshot in {}", path.display()));
+        }
+        for (other, todos) in snapshots {
+            merge_from(db, config, &other, todos)?;
+        }
+        return Ok(());
+    }
+    if path == db_path(config)? {
+        return Err(anyhow!("{} is this list's own database", path.display()));
+    }
+    let other = TodoDb::open_path(path, true)?;
+    let source = path.file_stem().unwrap_or_default().to_string_lossy();
+    merge_from(db, config, &source, other.list_all()?)
+}
+
+/// Three-way merge of `todos` from `source` into the list
+fn merge_from(db: &TodoDb, config: &Config, source: &str, todos: Vec<Todo>) -> Result<()> {
+    let remote = documents(&todos)?;
+    let local = documents(&db.list_all()?)?;
+    let base = load_base(config, source)?;
+    if base.is_empty() {
+        print_info(&format!(
+            "First merge with {}: every field the two copies disagree on counts as a conflict",
+            source
+        ));
+    }
+
+    let mut resolver = Resolver {
+        prefer: db.get_merge_preferences()?,
+        interactive: !config.read_only && !json_output() && io::stdin().is_terminal(),
+        unresolved: Vec::new(),
+        remembered: false,
+        asked: 0,
+    };
+    let mut ids: Vec<&ObjectId> = local.keys().chain(remote.keys()).collect();
+    ids.sort();
+    ids.dedup();
+
+    let (mut added, mut updated, mut removed) = (0, 0, 0);
+    let mut touched = Vec::new();
+    let mut results = Vec::new();
+    for id in ids {
+        let b = base.get(id);
+        let merged = match (local.get(id), remote.get(id)) {
+            (Some(l), Some(r)) if l == r => continue,
+            (Some(l), Some(r)) => {
+                let merged = merge_fields(l, r, b, &mut resolver)?;
+                if &merged == l {
+                    continue;
+                }
+                updated += 1;
+                Some(merged)
+            }
+            // Deleted on the other side: follow unless it was edited here since
+            (Some(l), None) => {
+                if b != Some(l) {
+                    if b.is_some() {
+                        print_info(&format!(
+                            "Kept \"{}\": deleted in the other copy but edited here",
+                            l.get_str("title").unwrap_or_default()
+                        ));
+                    }
+                    continue;
+                }
+                removed += 1;
+                None
+            }
+            // Deleted here: stays deleted unless the other copy edited it since
+            (None, Some(r)) => {
+                if b.is_some() && b == Some(r) {
+                    continue;
+                }
+                added += 1;
+                Some(r.clone())
+            }
+            (None, None) => continue,
+        };
+        touched.push(*id);
+        if let Some(doc) = merged {
+            results.push(bson::from_document::<Todo>(doc).context("failed to decode merged todo")?);
+        }
+    }
+
+    if !resolver.unresolved.is_empty() {
+        if !config.read_only {
+            db.set_merge_conflicts(&resolver.unresolved)?;
+        }
+        return Err(anyhow!(
+            "both copies changed {}; nothing was merged. Run todo merge in a terminal to choose",
+            resolver.unresolved.join(", ")
+        ));
+    }
+    if !touched.is_empty() {
+        db.restore_todos(&touched, &results)?;
+    }
+    db.set_merge_conflicts(&[])?;
+    if resolver.remembered {
+        db.set_merge_preferences(&resolver.prefer)?;
+    }
+    let base = Base { todos: db.list_all()? };
+    let bytes = bson::to_vec(&base).context("failed to encode the merge base")?;
+    write_synced(&base_path(config, source)?, &bytes)?;
+
+    if touched.is_empty() {
+        print_info(&format!("Already up to date with {}", source));
+        return Ok(());
+    }
+    print_success(&format!(
+        "Merged {}: {} added, {} updated, {} removed ({} conflict(s) asked about)",
+        source,
+        added,
+        updated,
+        removed,
+        resolver.asked
+    ));
+    Ok(())
+}
@@ -1,16 +1,131 @@
-use anyhow::{anyhow, Result};
+mod apply;
+mod archive;
+mod bench;
+mod daemon;
+mod dedupe;
+mod export;
+mod focus;
+mod forecast;
+mod goal;
+mod holidays;
+mod import;
+mod init;
+mod intake;
+mod lists;
+mod merge;
+mod note;
+mod notify;
+mod plan;
+mod print;
+mod quick;
+mod recur;
+mod review;
+mod rules;
+mod search;
+mod serve;
+mod settings;
+mod share;
+mod snooze;
+mod sprint;
+mod stats;
+mod trash;
+mod triage;
+mod tui;
+mod undo;
 
+pub use apply::cmd_apply;
+pub use archive::{cmd_archive, cmd_restore};
+pub use bench::cmd_bench;
+pub use daemon::cmd_daemon;
+pub use dedupe::cmd_dedupe;
+pub use export::cmd_export;
+pub use focus::{cmd_focus, current_focus};
+pub use forecast::cmd_forecast;
+pub use goal::cmd_goal;
+pub use holidays::cmd_holidays;
+pub use import::cmd_import;
+pub use init::cmd_init;
+pub use lists::{cmd_lists, cmd_use};
+pub use merge::cmd_merge;
+pub use note::cmd_note;
+pub use notify::{cmd_notify, cmd_reminders};
+pub use plan::cmd_plan;
+pub use print::cmd_print;
+pub use quick::cmd_quick;
+pub use recur::{cmd_occurrences, cmd_recur, cmd_skip};
+pub use review::cmd_review;
+pub use rules::cmd_rules;
+pub use search::cmd_search;
+pub use serve::cmd_serve;
+pub use settings::cmd_settings;
+pub use share::cmd_share;
+pub use snooze::cmd_snooze;
+pub use sprint::cmd_sprint;
+pub use stats::cmd_stats;
+pub use trash::cmd_trash;
+pub use triage::cmd_triage;
+pub use tui::cmd_tui;
+pub use undo::{
+    begin_intent, cmd_redo, cmd_undo, end_intent, is_undoable, record_undo, recover_interrupted,
+};
+
+use std::collections::{BTreeMap, HashSet};
+use std::io::{self, IsTerminal};
+
+use anyhow::{anyhow, Context, Result};
+use chrono::Local;
+use polodb_core::bson::{oid::ObjectId, DateTime as BsonDateTime};
+
+use crate::config::{Config, ProjectConfig};
 use crate::db::TodoDb;
 use crate::display::{
-    print_added_todo, print_info, print_success, print_todo_table, print_warning,
+    enable_age_column, json_output, limit_columns, print_added_todo, print_blocked, print_info,
+    print_projects, print_success, print_todo_detail, print_todo_rows, print_todo_table,
+    print_waiting, print_warning, prompt,
 };
-use crate::models::Todo;
-use crate::remind::{format_remind_at, parse_reminder, send_notification};
+use crate::filter::{parse_effort, parse_priority, sort_rows, Filter};
+use crate::git;
+use crate::links::{find_urls, open_url};
+use crate::models::{Comment, HistoryKind, Todo};
+use crate::normalize::normalize_title;
+use crate::remind::{
+    format_remind_at, is_repeating, parse_escalation, parse_minutes, parse_reminder,
+    parse_leads, parse_repeating,
+};
+use crate::schedule::Schedule;
+
+/// Four letters and digits, starting with a letter, as `Todo::short_id` makes them
+fn looks_like_short_id(arg: &str) -> bool {
+    arg.len() == 4
+        && arg.starts_with(|c: char| c.is_ascii_alphabetic())
+        && arg.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+/// The position of the todo with short id `arg`, if `arg` looks like one and names one
+fn find_short_id(todos: &[Todo], arg: &str) -> Result<Option<usize>> {
+    if !looks_like_short_id(arg) {
+        return Ok(None);
+    }
+    let arg = arg.to_lowercase();
+    let mut found = todos.iter().enumerate().filter(|(_, t)| t.short_id() == arg);
+    match (found.next(), found.next()) {
+        (Some((i, _)), None) => Ok(Some(i)),
+        (Some(_), Some(_)) => Err(anyhow!("Several todos have the id {}; use their numbers", arg)),
+        (None, _) => Ok(None),
+    }
+}
 
 fn get_todo_by_index(db: &TodoDb, index_str: &str) -> Result<(usize, Todo)> {
-    let index: usize = index_str
-        .parse()
-        .map_err(|_| anyhow!("Invalid number: {}. Use a number like 1, 2, 3...", index_str))?;
+    let Ok(index) = index_str.parse::<usize>() else {
+        let todos = db.list_all()?;
+        return match find_short_id(&todos, index_str)? {
+            Some(i) => Ok((i + 1, todos[i].clone())),
+            None => Err(anyhow!(
+                "Invalid number: {}. Use a number like 1, 2, 3... or a short id like k3f9",
+                index_str
+            )),
+        };
+    };
 
     if index == 0 {
         return Err(anyhow!("Todo numbers start at 1"));
@@ -30,25 +145,30 @@ fn get_todo_by_index(db: &TodoDb, index_str: &str) -> Result<(usize, Todo)> {
     Ok((index, todos[actual_index].clone()))
 }
 
-/// Parse args to extract --remind or -r flag and its value
-fn extract_reminder(args: &[String]) -> (Vec<String>, Option<String>) {
+/// Pull a `--long <value>` / `-s <value>` (or `--long=value`) option out of args,
+/// returning the remaining args and the option's value if present
+fn extract_option(args: &[String], long: &str, short: Option<&str>) -> (Vec<String>, Option<String>) {
     let mut remaining = Vec::new();
-    let mut reminder = None;
+    let mut value = None;
     let mut i = 0;
 
+    let long_eq = format!("{}=", long);
+    let short_eq = short.map(|s| format!("{}=", s));
+
     while i < args.len() {
-        if args[i] == "--remind" || args[i] == "-r" {
+        let arg = args[i].as_str();
+        if arg == long || Some(arg) == short {
             if i + 1 < args.len() {
-                reminder = Some(args[i + 1].clone());
+                value = Some(args[i + 1].clone());
                 i += 2;
                 continue;
             }
-        } else if args[i].starts_with("--remind=") {
-            reminder = Some(args[i].trim_start_matches("--remind=").to_string());
+        } else if let Some(v) = arg.strip_prefix(long_eq.as_str()) {
+            value = Some(v.to_string());
             i += 1;
             continue;
-        } else if args[i].starts_with("-r=") {
-            reminder = Some(args[i].trim_start_matches("-r=").to_string());
+        } else if let Some(v) = short_eq.as_deref().and_then(|s| arg.strip_prefix(s)) {
+            value = Some(v.to_string());
             i += 1;
             continue;
         }
@@ -56,71 +176,1121 @@ fn extract_reminder(args: &[String]) -> (Vec<String>, Option<String>) {
         i += 1;
     }
 
-    (remaining, reminder)
+    (remaining, value)
 }
 
-pub fn cmd_add(db: &TodoDb, args: Vec<String>) -> Result<()> {
+/// Like `extract_option`, but collects every occurrence; comma-separated values are split
+fn extract_option_all(args: &[String], long: &str, short: Option<&str>) -> (Vec<String>, Vec<String>) {
+    let mut remaining = args.to_vec();
+    let mut values = Vec::new();
+
+    loop {
+        let (rest, value) = extract_option(&remaining, long, short);
+        match value {
+            Some(v) => {
+                values.extend(v.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()));
+                remaining = rest;
+            }
+            None => return (remaining, values),
+        }
+    }
+}
+
+/// Parse args to extract --remind or -r flag and its value
+fn extract_reminder(args: &[String]) -> (Vec<String>, Option<String>) {
+    extract_option(args, "--remind", Some("-r"))
+}
+
+pub fn cmd_add(db: &TodoDb, config: &Config, args: Vec<String>) -> Result<()> {
+    let (args, from_file) = extract_option(&args, "--from-file", None);
+    if let Some(path) = from_file {
+        return intake::add_from_file(db, config, &path, args);
+    }
     if args.is_empty() {
         return Err(anyhow!(
             "Missing title. Usage: todo add \"your task\" [--remind 15m]"
         ));
     }
 
+    let (args, parent_str) = extract_option(&args, "--parent", None);
+    let (args, start_str) = extract_option(&args, "--start", None);
+    let (args, after_str) = extract_option(&args, "--after", None);
+    let (args, goal_str) = extract_option(&args, "--goal", None);
+    let (args, mut tags) = extract_option_all(&args, "--tag", Some("-t"));
+    let (args, escalate_str) = extract_option(&args, "--escalate", None);
+    let (args, due_str) = extract_option(&args, "--due", None);
+    let (args, every_str) = extract_option(&args, "--every", None);
+    let (args, assignee) = extract_option(&args, "--assign", None);
+    let (args, location) = extract_option(&args, "--location", None);
+    let (args, effort_str) = extract_option(&args, "--effort", None);
+    let (args, review_str) = extract_option(&args, "--review", None);
+    let (args, estimate_str) = extract_option(&args, "--estimate", None);
+    let (args, priority_str) = extract_option(&args, "--priority", Some("-p"));
+    let (args, notify_str) = extract_option(&args, "--notify", None);
+    let (args, mut project) = extract_option(&args, "--project", None);
+    let raw = args.iter().any(|a| a == "--raw");
+    let args: Vec<String> = args.into_iter().filter(|a| a != "--raw").collect();
     let (title_args, reminder_str) = extract_reminder(&args);
 
+    // A lone `@work` argument names the project; `@` inside a quoted title stays put
+    let (project_args, title_args): (Vec<String>, Vec<String>) = title_args
+        .into_iter()
+        .partition(|a| a.len() > 1 && a.starts_with('@') && !a.contains(' '));
+    if let Some(last) = project_args.last() {
+        project = project.or_else(|| Some(last[1..].to_string()));
+    }
+
     let title = title_args.join(" ").trim().to_string();
+    let title = if raw {
+        title
+    } else {
+        normalize_title(&title, config)?
+    };
     if title.is_empty() {
         return Err(anyhow!("Title cannot be empty"));
     }
 
-    let todo = if let Some(ref remind_str) = reminder_str {
-        let remind_at = parse_reminder(remind_str)?;
-        Todo::with_reminder(title.clone(), remind_at)
+    // Title rules add tags before per-tag defaults are looked up, so a rule's tag brings
+    // its defaults along
+    let mut rule_priority = None;
+    let mut rule_project = None;
+    for rule in config.rules_matching(&title)? {
+        for tag in &rule.tags {
+            if !tags.contains(tag) {
+                tags.push(tag.clone());
+            }
+        }
+        rule_priority = rule_priority.or_else(|| rule.priority.clone());
+        rule_project = rule_project.or_else(|| rule.project.clone());
+    }
+
+    // Flags beat title rules and per-tag and per-project defaults, which beat the global
+    // default reminder
+    let project = project
+        .or(rule_project)
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty());
+    let defaults = config.defaults_for(&tags, project.as_deref());
+    let reminder_str = reminder_str
+        .or(defaults.remind)
+        .or_else(|| config.default_reminder.clone());
+    let due_str = due_str.or(defaults.due);
+    let review_str = review_str.or(defaults.review);
+    let effort_str = effort_str.or(defaults.effort);
+    let priority_str = priority_str.or(rule_priority).or(defaults.priority);
+    let location = location.or(defaults.location);
+    let assignee = assignee.or(defaults.assign);
+    for tag in defaults.tags {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+
+    let mut todo = if let Some(ref remind_str) = reminder_str {
+        if is_repeating(remind_str) {
+            let (remind_at, _) = parse_repeating(remind_str)?;
+            let mut todo = Todo::with_reminder(title.clone(), remind_at);
+            todo.remind_every = Some(remind_str.trim().to_lowercase());
+            todo
+        } else {
+            Todo::with_reminder(title.clone(), parse_reminder(remind_str)?)
+        }
     } else {
         Todo::new(title.clone())
     };
 
-    db.insert(&todo)?;
+    if let Some(ref parent_str) = parent_str {
+        let (_, parent) = get_todo_by_index(db, parent_str)?;
+        todo.parent_id = Some(parent.id);
+    }
 
-    let todos = db.list_all()?;
-    let index = todos.len();
+    if let Some(ref start_str) = start_str {
+        todo.start_at = Some(parse_reminder(start_str)?);
+    }
+
+    if let Some(ref after_str) = after_str {
+        let (_, blocker) = get_todo_by_index(db, after_str)?;
+        todo.depends_on.push(blocker.id);
+    }
+
+    todo.tags = tags;
+    todo.project = project;
+    todo.owner = config.user_name();
+    todo.assigned_to = assignee.map(|a| a.trim_start_matches('@').to_string());
+    todo.location = location.map(|l| l.trim().to_string()).filter(|l| !l.is_empty());
+    todo.effort = effort_str.as_deref().map(parse_effort).transpose()?;
+    todo.priority = priority_str.as_deref().map(parse_priority).transpose()?;
+    todo.review_at = review_str.as_deref().map(parse_reminder).transpose()?;
+    todo.estimate_minutes = estimate_str.as_deref().map(parse_estimate).transpose()?;
 
-    print_added_todo(index, &title);
+    if let Some(ref due_str) = due_str {
+        todo.due_at = Some(parse_reminder(due_str)?);
+    }
+
+    if let Some(ref every_str) = every_str {
+        let rule = every_str.trim().to_lowercase();
+        let schedule = Schedule::parse(&rule)?;
+        if todo.due_at.is_none() {
+            let first = schedule
+                .next_after(Local::now())
+                .ok_or_else(|| anyhow!("Schedule '{}' never fires", rule))?;
+            todo.due_at = Some(BsonDateTime::from_millis(first.timestamp_millis()));
+        }
+        todo.recur = Some(rule);
+    }
+
+    if let Some(ref notify_str) = notify_str {
+        let due_at = todo
+            .due_at
+            .ok_or_else(|| anyhow!("--notify needs a due date, e.g. --due friday --notify 1d,2h"))?;
+        todo.notify_before = parse_leads(notify_str)?;
+        todo.notifications =
+            Todo::lead_notifications(due_at, &todo.notify_before, BsonDateTime::now());
+    }
+
+    if let Some(ref escalate_str) = escalate_str {
+        if todo.remind_at.is_none() {
+            return Err(anyhow!("--escalate needs a reminder, e.g. -r 15m --escalate 30m:critical"));
+        }
+        todo.escalation = parse_escalation(escalate_str)?;
+    }
+
+    if let Some(ref goal_str) = goal_str {
+        let (_, goal) = goal::get_goal_by_index(db, goal_str)?;
+        todo.goal_id = Some(goal.id);
+    }
+
+    db.insert(&todo)?;
+
+    let index = db.list_all()?.iter().position(|t| t.id == todo.id).unwrap_or(0) + 1;
+    print_added_todo(index, &todo);
 
     if let Some(remind_at) = &todo.remind_at {
         print_info(&format!("  Reminder: {}", format_remind_at(remind_at)));
     }
+    if !todo.notify_before.is_empty() {
+        print_lead_notifications(&todo.notifications, todo.notify_before.len());
+    }
 
     Ok(())
 }
 
-pub fn cmd_list(db: &TodoDb) -> Result<()> {
+/// `todo in "random thought"`: capture without deciding anything; sort it out in `todo triage`
+pub fn cmd_inbox_add(db: &TodoDb, config: &Config, args: Vec<String>) -> Result<()> {
+    let raw = args.iter().any(|a| a == "--raw");
+    let args: Vec<String> = args.into_iter().filter(|a| a != "--raw").collect();
+    let title = args.join(" ").trim().to_string();
+    let title = if raw {
+        title
+    } else {
+        normalize_title(&title, config)?
+    };
+    if title.is_empty() {
+        return Err(anyhow!("Usage: todo in \"random thought\""));
+    }
+
+    let mut todo = Todo::new(title.clone());
+    todo.inbox = true;
+    todo.owner = config.user_name();
+    db.insert(&todo)?;
+
+    let index = db.list_all()?.iter().position(|t| t.id == todo.id).unwrap_or(0) + 1;
+    print_added_todo(index, &todo);
+    print_info("  In your inbox. Sort it out later with: todo triage");
+    Ok(())
+}
+
+/// `todo list [--repo] [--all] [--archived] [--reminders|--no-reminders] [--sort key] [filter terms...]`
+/// `[--reverse] [--done|--pending] [--due-today] [--limit n]`
+pub fn cmd_list(db: &TodoDb, config: &Config, args: Vec<String>) -> Result<()> {
+    // The archive is numbered on its own, for `todo restore`
+    let archived = args.iter().any(|a| a == "--archived");
+    let todos = if archived { db.list_archived()? } else { db.list_all()? };
+
+    let (args, sort_flag) = extract_option(&args, "--sort", None);
+    let (args, location) = extract_option(&args, "--location", None);
+    let (args, effort_str) = extract_option(&args, "--effort", None);
+    let (args, limit) = extract_option(&args, "--limit", Some("-n"));
+    let limit = limit
+        .map(|n| {
+            n.parse::<usize>()
+                .ok()
+                .filter(|&n| n > 0)
+                .ok_or_else(|| anyhow!("--limit takes a positive number, got '{}'", n))
+        })
+        .transpose()?;
+
+    let repo_only = args.iter().any(|a| a == "--repo");
+    let show_all = args.iter().any(|a| a == "--all");
+    let with_reminders = args.iter().any(|a| a == "--reminders");
+    let without_reminders = args.iter().any(|a| a == "--no-reminders");
+    let mine = args.iter().any(|a| a == "--mine");
+    let reverse = args.iter().any(|a| a == "--reverse");
+    let done_only = args.iter().any(|a| a == "--done");
+    let pending_only = args.iter().any(|a| a == "--pending");
+    let due_today = args.iter().any(|a| a == "--due-today");
+    if done_only && pending_only {
+        return Err(anyhow!("--done and --pending can't be used together"));
+    }
+    let age = args.iter().any(|a| a == "--age");
+    let terms: Vec<String> = args.into_iter().filter(|a| !a.starts_with("--")).collect();
+
+    // A project's `.todo/config.toml` says how its list looks when run inside it
+    let cwd = std::env::current_dir().context("failed to read current directory")?;
+    let (project_path, project) = ProjectConfig::find(&cwd)?.unzip();
+    let project = project.unwrap_or_default();
+    if let Some(mut columns) = project.columns {
+        if age {
+            columns.push("age".to_string());
+        }
+        if columns.iter().any(|c| c == "age") {
+            enable_age_column(config);
+        }
+        limit_columns(columns);
+    } else if age {
+        enable_age_column(config);
+    }
+
+    let mut filter = Filter::parse(&terms)?;
+
+    if with_reminders {
+        filter.has_reminder = Some(true);
+    } else if without_reminders {
+        filter.has_reminder = Some(false);
+    }
+    if done_only || pending_only {
+        filter.done = Some(done_only);
+    }
+    if due_today {
+        filter.due_on = Some(Local::now().date_naive());
+    }
+
+    if let Some(location) = location {
+        filter.location = Some(location.to_lowercase());
+    }
+    if let Some(ref effort_str) = effort_str {
+        filter.effort = Some(parse_effort(effort_str)?);
+    }
+
+    if mine {
+        let me = config
+            .user_name()
+            .ok_or_else(|| anyhow!("Set `user` in the config to use --mine"))?;
+        filter.assignee = Some(me);
+    }
+
+    // Reminder audits read best in firing order
+    let sort = sort_flag.unwrap_or_else(|| {
+        if with_reminders {
+            "remind".to_string()
+        } else {
+            project.sort.clone().unwrap_or_else(|| config.sort.clone())
+        }
+    });
+
+    // Inside a project with a `filter`, or a directory mapped in `dir_tags`, default to that
+    if filter.is_empty() && !show_all {
+        if let (Some(terms), Some(path)) = (&project.filter, &project_path) {
+            let terms: Vec<String> = terms.split_whitespace().map(String::from).collect();
+            filter = Filter::parse(&terms)
+                .with_context(|| format!("invalid filter in {}", path.display()))?;
+            print_info(&format!(
+                "Showing {} (from {}). Use --all to see everything.",
+                terms.join(" "),
+                path.display()
+            ));
+        } else if let Some((dir, tag)) = config.tag_for_dir(&cwd) {
+            print_info(&format!("Showing tag:{} (from {}). Use --all to see everything.", tag, dir));
+            filter.tags.push(tag.to_string());
+        }
+    }
+
+    let repo = if repo_only {
+        Some(git::current_repo().ok_or_else(|| anyhow!("Not inside a git repository"))?)
+    } else {
+        None
+    };
+
+    if archived && !json_output() {
+        print_info("Archived todos. Bring one back with: todo restore <#>");
+    }
+
+    if filter.is_empty() && repo.is_none() && sort == "default" && !reverse && limit.is_none() {
+        print_todo_table(&todos);
+        return Ok(());
+    }
+
+    let mut rows: Vec<usize> = todos
+        .iter()
+        .enumerate()
+        .filter(|(_, t)| filter.matches(t))
+        .filter(|(_, t)| match repo {
+            Some(ref repo) => t.git.as_ref().is_some_and(|g| &g.repo == repo),
+            None => true,
+        })
+        .map(|(i, _)| i)
+        .collect();
+
+    sort_rows(&todos, &mut rows, &sort, config)?;
+    if reverse {
+        rows.reverse();
+    }
+
+    // Only the first page is laid out and printed, however long the list is
+    let total = rows.len();
+    if let Some(limit) = limit {
+        rows.truncate(limit);
+    }
+    print_todo_rows(&todos, &rows);
+    if rows.len() < total && !json_output() {
+        print_info(&format!(
+            "Showing {} of {} todos. Raise --limit to see more.",
+            rows.len(),
+            total
+        ));
+    }
+    Ok(())
+}
+
+/// Dispatch `todo git link|unlink <#>`
+pub fn cmd_git(db: &TodoDb, args: Vec<String>) -> Result<()> {
+    let usage = "Usage: todo git link <#>  or  todo git unlink <#>";
+    let sub = args.first().ok_or_else(|| anyhow!(usage))?;
+    let index_str = args.get(1).ok_or_else(|| anyhow!(usage))?;
+    let (index, todo) = get_todo_by_index(db, index_str)?;
+
+    match sub.as_str() {
+        "link" => {
+            let link = git::current_link()?;
+            db.set_git_link(&todo.id, Some(&link))?;
+            print_success(&format!(
+                "Linked #{} to {} ({})",
+                index, link.branch, link.repo
+            ));
+        }
+        "unlink" => {
+            db.set_git_link(&todo.id, None)?;
+            print_success(&format!("Unlinked #{} from git: {}", index, todo.title));
+        }
+        _ => return Err(anyhow!(usage)),
+    }
+    Ok(())
+}
+
+/// List only the todos that can be acted on right now
+pub fn cmd_ready(db: &TodoDb) -> Result<()> {
+    let todos = db.list_all()?;
+    let now = BsonDateTime::now();
+
+    let rows: Vec<usize> = todos
+        .iter()
+        .enumerate()
+        .filter(|(_, t)| t.is_ready(&todos, now))
+        .map(|(i, _)| i)
+        .collect();
+
+    print_todo_rows(&todos, &rows);
+    Ok(())
+}
+
+pub fn cmd_depend(db: &TodoDb, args: Vec<String>) -> Result<()> {
+    let (args, reason_flag) = extract_option(&args, "--reason", None);
+    if args.len() < 2 {
+        return Err(anyhow!(
+            "Usage: todo depend <#> <#>...  or  todo depend <#> clear"
+        ));
+    }
+
+    let (index, todo) = get_todo_by_index(db, &args[0])?;
+
+    if args[1] == "clear" || args[1] == "none" {
+        db.set_dependencies(&todo.id, &[])?;
+        db.set_blocked(&todo.id, None)?;
+        print_success(&format!("Cleared dependencies for #{}: {}", index, todo.title));
+        return Ok(());
+    }
+
+    let mut depends_on = todo.depends_on.clone();
+    for index_str in &args[1..] {
+        let (dep_index, dep) = get_todo_by_index(db, index_str)?;
+        if dep.id == todo.id {
+            return Err(anyhow!("Todo #{} cannot depend on itself", dep_index));
+        }
+        if !depends_on.contains(&dep.id) {
+            depends_on.push(dep.id);
+        }
+    }
+
+    db.set_dependencies(&todo.id, &depends_on)?;
+    if let Some(reason) = block_reason(reason_flag, "Reason (optional):")? {
+        db.set_blocked(&todo.id, Some(&reason))?;
+    }
+    print_success(&format!(
+        "#{} now waits on {} todo(s): {}",
+        index,
+        depends_on.len(),
+        todo.title
+    ));
+    Ok(())
+}
+
+/// Reason from `--reason`, else asked for interactively; `None` when skipped or not a terminal
+fn block_reason(flag: Option<String>, question: &str) -> Result<Option<String>> {
+    let reason = match flag {
+        Some(reason) => Some(reason),
+        None if io::stdin().is_terminal() => prompt(question)?,
+        None => None,
+    };
+    Ok(reason.map(|r| r.trim().to_string()).filter(|r| !r.is_empty()))
+}
+
+pub fn cmd_block(db: &TodoDb, args: Vec<String>) -> Result<()> {
+    let (args, reason_flag) = extract_option(&args, "--reason", None);
+    if args.is_empty() {
+        return Err(anyhow!("Usage: todo block <#> [reason]  or  todo block <#> clear"));
+    }
+
+    let (index, todo) = get_todo_by_index(db, &args[0])?;
+    let rest = args[1..].join(" ");
+
+    if rest == "clear" || rest == "none" {
+        db.set_blocked(&todo.id, None)?;
+        print_success(&format!("Unblocked #{}: {}", index, todo.title));
+        return Ok(());
+    }
+
+    let reason = if rest.is_empty() {
+        block_reason(reason_flag, "Why is it blocked?")?
+    } else {
+        Some(rest)
+    };
+    let reason = reason.unwrap_or_else(|| "no reason given".to_string());
+
+    db.set_blocked(&todo.id, Some(&reason))?;
+    print_success(&format!("Blocked #{} ({}): {}", index, reason, todo.title));
+    Ok(())
+}
+
+/// Blocked todos with their reasons, stuck longest first
+pub fn cmd_blocked(db: &TodoDb) -> Result<()> {
+    let todos = db.list_all()?;
+    let mut rows: Vec<usize> = todos
+        .iter()
+        .enumerate()
+        .filter(|(_, t)| t.is_blocked())
+        .map(|(i, _)| i)
+        .collect();
+    rows.sort_by_key(|&i| todos[i].blocked_since.map(|d| d.timestamp_millis()));
+
+    print_blocked(&todos, &rows);
+    Ok(())
+}
+
+pub fn cmd_delegate(db: &TodoDb, args: Vec<String>) -> Result<()> {
+    let (args, reason_flag) = extract_option(&args, "--reason", None);
+    if args.len() < 2 {
+        return Err(anyhow!("Usage: todo delegate <#> <name>  or  todo delegate <#> clear"));
+    }
+
+    let (index, todo) = get_todo_by_index(db, &args[0])?;
+    let name = args[1..].join(" ").trim().to_string();
+
+    if name == "clear" || name == "none" {
+        db.set_waiting(&todo.id, None)?;
+        db.set_blocked(&todo.id, None)?;
+        print_success(&format!("#{} is back on your plate: {}", index, todo.title));
+        return Ok(());
+    }
+    if name.is_empty() {
+        return Err(anyhow!("Name cannot be empty"));
+    }
+
+    db.set_waiting(&todo.id, Some(&name))?;
+    if let Some(reason) = block_reason(reason_flag, "Reason (optional):")? {
+        db.set_blocked(&todo.id, Some(&reason))?;
+    }
+    print_success(&format!("#{} is now waiting on {}: {}", index, name, todo.title));
+    Ok(())
+}
+
+/// Delegated todos, longest wait first
+pub fn cmd_waiting(db: &TodoDb) -> Result<()> {
+    let todos = db.list_all()?;
+    let mut rows: Vec<usize> = todos
+        .iter()
+        .enumerate()
+        .filter(|(_, t)| t.is_waiting())
+        .map(|(i, _)| i)
+        .collect();
+    rows.sort_by_key(|&i| todos[i].waiting_since.map(|d| d.timestamp_millis()));
+
+    print_waiting(&todos, &rows);
+    Ok(())
+}
+
+pub fn cmd_project(db: &TodoDb, args: Vec<String>) -> Result<()> {
+    if args.len() < 2 {
+        return Err(anyhow!("Usage: todo project <#> <name|clear>"));
+    }
+
+    let (index, todo) = get_todo_by_index(db, &args[0])?;
+    let name = args[1..].join(" ");
+    let name = name.trim().trim_start_matches('@');
+
+    if matches!(name, "clear" | "none" | "") {
+        db.set_project(&todo.id, None)?;
+        print_success(&format!("Removed #{} from its project: {}", index, todo.title));
+    } else {
+        db.set_project(&todo.id, Some(name))?;
+        print_success(&format!("Moved #{} to @{}: {}", index, name, todo.title));
+    }
+    Ok(())
+}
+
+/// `todo projects`: each project with its pending and done counts and next due date
+pub fn cmd_projects(db: &TodoDb) -> Result<()> {
     let todos = db.list_all()?;
-    print_todo_table(&todos);
+    // project -> (pending, done, next due)
+    let mut projects: BTreeMap<String, (usize, usize, Option<BsonDateTime>)> = BTreeMap::new();
+    for todo in &todos {
+        let Some(ref name) = todo.project else {
+            continue;
+        };
+        let (pending, done, next_due) = projects.entry(name.clone()).or_default();
+        if todo.done {
+            *done += 1;
+        } else {
+            *pending += 1;
+            if let Some(due) = todo.due_at
+                && next_due.is_none_or(|d| due < d)
+            {
+                *next_due = Some(due);
+            }
+        }
+    }
+
+    if projects.is_empty() {
+        print_info("No projects yet. Add one with: todo add \"fix bug\" @work");
+        return Ok(());
+    }
+
+    let rows: Vec<(String, usize, usize, Option<BsonDateTime>)> = projects
+        .into_iter()
+        .map(|(name, (pending, done, next_due))| (name, pending, done, next_due))
+        .collect();
+    print_projects(&rows);
+    Ok(())
+}
+
+pub fn cmd_priority(db: &TodoDb, args: Vec<String>) -> Result<()> {
+    if args.len() < 2 {
+        return Err(anyhow!("Usage: todo priority <#> <low|medium|high|critical|clear>"));
+    }
+
+    let (index, todo) = get_todo_by_index(db, &args[0])?;
+    let priority = match args[1].as_str() {
+        "clear" | "none" => None,
+        other => Some(parse_priority(other)?),
+    };
+
+    db.set_priority(&todo.id, priority)?;
+    match priority {
+        Some(priority) => print_success(&format!(
+            "Set priority for #{} to {}: {}",
+            index,
+            priority.as_str(),
+            todo.title
+        )),
+        None => print_success(&format!("Cleared priority for #{}: {}", index, todo.title)),
+    }
+    print_info("  List numbers may have changed; pending todos are ordered by priority");
+    Ok(())
+}
+
+fn parse_estimate(input: &str) -> Result<i64> {
+    parse_minutes(&input.to_lowercase())
+        .ok_or_else(|| anyhow!("Invalid estimate: {}. Examples: 30m, 2h, 1d", input))
+}
+
+pub fn cmd_estimate(db: &TodoDb, args: Vec<String>) -> Result<()> {
+    if args.len() < 2 {
+        return Err(anyhow!("Usage: todo estimate <#> <30m|2h|clear>"));
+    }
+
+    let (index, todo) = get_todo_by_index(db, &args[0])?;
+    let minutes = match args[1].as_str() {
+        "clear" | "none" => None,
+        other => Some(parse_estimate(other)?),
+    };
+
+    db.set_estimate(&todo.id, minutes)?;
+    match minutes {
+        Some(_) => print_success(&format!("Estimated #{} at {}: {}", index, args[1], todo.title)),
+        None => print_success(&format!("Cleared estimate for #{}: {}", index, todo.title)),
+    }
     Ok(())
 }
 
-pub fn cmd_done(db: &TodoDb, args: Vec<String>) -> Result<()> {
+pub fn cmd_effort(db: &TodoDb, args: Vec<String>) -> Result<()> {
+    if args.len() < 2 {
+        return Err(anyhow!("Usage: todo effort <#> <low|medium|high|clear>"));
+    }
+
+    let (index, todo) = get_todo_by_index(db, &args[0])?;
+    let effort = match args[1].as_str() {
+        "clear" | "none" => None,
+        other => Some(parse_effort(other)?),
+    };
+
+    db.set_effort(&todo.id, effort)?;
+    match effort {
+        Some(effort) => print_success(&format!("Set effort for #{} to {}", index, effort.as_str())),
+        None => print_success(&format!("Cleared effort for #{}: {}", index, todo.title)),
+    }
+    Ok(())
+}
+
+pub fn cmd_comment(db: &TodoDb, config: &Config, args: Vec<String>) -> Result<()> {
+    if args.len() < 2 {
+        return Err(anyhow!("Usage: todo comment <#> \"your comment\""));
+    }
+
+    let (index, todo) = get_todo_by_index(db, &args[0])?;
+    let text = args[1..].join(" ").trim().to_string();
+    if text.is_empty() {
+        return Err(anyhow!("Comment cannot be empty"));
+    }
+
+    let mut comments = todo.comments.clone();
+    comments.push(Comment {
+        author: config.user_name(),
+        text,
+        at: BsonDateTime::now(),
+    });
+    db.set_comments(&todo.id, &comments)?;
+
+    print_success(&format!(
+        "Commented on #{}: {} ({} comment(s))",
+        index,
+        todo.title,
+        comments.len()
+    ));
+    Ok(())
+}
+
+/// `todo tag <#> +work -home errand`: bare or `+` names add, `-` names remove
+pub fn cmd_tag(db: &TodoDb, args: Vec<String>) -> Result<()> {
+    if args.len() < 2 {
+        return Err(anyhow!("Usage: todo tag <#> <+tag|-tag>..."));
+    }
+
+    let (index, todo) = get_todo_by_index(db, &args[0])?;
+    let mut tags = todo.tags.clone();
+
+    for arg in &args[1..] {
+        if let Some(name) = arg.strip_prefix('-') {
+            tags.retain(|t| t != name);
+        } else {
+            let name = arg.trim_start_matches('+').to_string();
+            if !name.is_empty() && !tags.contains(&name) {
+                tags.push(name);
+            }
+        }
+    }
+
+    db.set_tags(&todo.id, &tags)?;
+    if tags.is_empty() {
+        print_success(&format!("Removed all tags from #{}: {}", index, todo.title));
+    } else {
+        print_success(&format!("Tagged #{} with +{}", index, tags.join(" +")));
+    }
+    Ok(())
+}
+
+/// `todo review-at <#> <when|clear>`: set when to look at a todo again
+pub fn cmd_review_at(db: &TodoDb, args: Vec<String>) -> Result<()> {
+    if args.len() < 2 {
+        return Err(anyhow!("Usage: todo review-at <#> <when>  (e.g. 6mo, 2025-12-01)  or  clear"));
+    }
+
+    let (index, todo) = get_todo_by_index(db, &args[0])?;
+    let when = args[1..].join(" ");
+
+    if when == "clear" || when == "none" {
+        db.set_review(&todo.id, None)?;
+        print_success(&format!("Cleared review date for #{}: {}", index, todo.title));
+        return Ok(());
+    }
+
+    let review_at = parse_reminder(&when)?;
+    db.set_review(&todo.id, Some(review_at))?;
+    print_success(&format!(
+        "#{} comes up for review {}: {}",
+        index,
+        format_remind_at(&review_at),
+        todo.title
+    ));
+    Ok(())
+}
+
+/// Todos whose review date has passed, oldest first
+pub fn cmd_review_due(db: &TodoDb) -> Result<()> {
+    let todos = db.list_all()?;
+    let now = BsonDateTime::now().timestamp_millis();
+    let mut rows: Vec<usize> = todos
+        .iter()
+        .enumerate()
+        .filter(|(_, t)| t.review_at.is_some_and(|r| r.timestamp_millis() <= now))
+        .map(|(i, _)| i)
+        .collect();
+    rows.sort_by_key(|&i| todos[i].review_at.map(|r| r.timestamp_millis()));
+
+    if rows.is_empty() {
+        print_info("Nothing is up for review.");
+        return Ok(());
+    }
+    print_todo_rows(&todos, &rows);
+    print_info("Push a review out with: todo review-at <#> 6mo (or clear it)");
+    Ok(())
+}
+
+/// `todo due <#> <when>` sets a due date; `clear` removes it
+pub fn cmd_due(db: &TodoDb, args: Vec<String>) -> Result<()> {
+    if args.len() < 2 {
+        return Err(anyhow!("Usage: todo due <#> <when>  or  todo due <#> clear"));
+    }
+
+    let (args, notify_str) = extract_option(&args, "--notify", None);
+    let (index, todo) = get_todo_by_index(db, &args[0])?;
+    let when = args[1..].join(" ");
+
+    if when == "clear" || when == "off" || when == "none" {
+        db.set_due(&todo.id, None)?;
+        db.set_notifications(&todo.id, &[], &[])?;
+        print_success(&format!("Cleared due date for #{}: {}", index, todo.title));
+        return Ok(());
+    }
+
+    let due_at = parse_reminder(&when)?;
+    db.set_due(&todo.id, Some(due_at))?;
+    print_success(&format!(
+        "#{} is due {}: {}",
+        index,
+        format_remind_at(&due_at),
+        todo.title
+    ));
+
+    // Moving the due date moves any earlier lead times along with it
+    let leads = match notify_str {
+        Some(ref notify_str) => parse_leads(notify_str)?,
+        None => todo.notify_before.clone(),
+    };
+    if !leads.is_empty() || !todo.notifications.is_empty() {
+        let pending = Todo::lead_notifications(due_at, &leads, BsonDateTime::now());
+        db.set_notifications(&todo.id, &leads, &pending)?;
+        print_lead_notifications(&pending, leads.len());
+    }
+    Ok(())
+}
+
+/// Confirm which lead-time notifications were scheduled
+fn print_lead_notifications(pending: &[BsonDateTime], lead_count: usize) {
+    let times: Vec<String> = pending.iter().map(format_remind_at).collect();
+    if !times.is_empty() {
+        print_info(&format!("  Notifying {}", times.join(", ")));
+    }
+    if times.len() < lead_count {
+        print_info("  Skipped lead times that have already passed");
+    }
+}
+
+pub fn cmd_open(db: &TodoDb, args: Vec<String>) -> Result<()> {
     let index_str = args
         .first()
-        .ok_or_else(|| anyhow!("Missing todo number. Usage: todo done <#>"))?;
+        .ok_or_else(|| anyhow!("Missing todo number. Usage: todo open <#>"))?;
     let (index, todo) = get_todo_by_index(db, index_str)?;
 
+    let url = find_urls(&todo.title).first().map(|u| u.to_string()).ok_or_else(|| {
+        anyhow!(
+            "Todo #{} has no link. Add one with: todo edit {} \"{} https://...\"",
+            index,
+            index,
+            todo.title
+        )
+    })?;
+
+    open_url(&url)?;
+    print_success(&format!("Opened {}", url));
+    Ok(())
+}
+
+pub fn cmd_show(db: &TodoDb, args: Vec<String>) -> Result<()> {
+    let index_str = args
+        .first()
+        .ok_or_else(|| anyhow!("Missing todo number. Usage: todo show <#>"))?;
+    let (index, todo) = get_todo_by_index(db, index_str)?;
+
+    let todos = db.list_all()?;
+    print_todo_detail(index, &todo, &todos);
+    Ok(())
+}
+
+/// Walk up from a just-completed todo, marking each parent done once all its subtasks are.
+/// Returns the titles of the parents it completed.
+fn complete_finished_parents(db: &TodoDb, todo: &Todo) -> Result<Vec<String>> {
+    let mut parent_id = todo.parent_id;
+    let mut completed = Vec::new();
+
+    while let Some(id) = parent_id {
+        let todos = db.list_all()?;
+        let Some(parent) = todos.iter().find(|t| t.id == id) else {
+            break;
+        };
+
+        match parent.progress(&todos) {
+            Some((done, total)) if done == total && !parent.done => {
+                db.mark_done(&parent.id)?;
+                completed.push(parent.title.clone());
+            }
+            _ => break,
+        }
+
+        parent_id = parent.parent_id;
+    }
+
+    Ok(completed)
+}
+
+/// `todo done --all [filter]` / `todo undone --all [filter]`: flip every matching todo at once
+fn set_done_all(db: &TodoDb, args: &[String], done: bool) -> Result<()> {
+    let terms: Vec<String> = args.iter().filter(|a| *a != "--all").cloned().collect();
+    let filter = Filter::parse(&terms)?;
+
+    let todos = db.list_all()?;
+    let matching: Vec<&Todo> = todos
+        .iter()
+        .filter(|t| t.done != done && filter.matches(t))
+        .collect();
+
+    // Recurring todos move on to their next occurrence instead of being marked done
+    let (recurring, plain): (Vec<&Todo>, Vec<&Todo>) = matching
+        .into_iter()
+        .partition(|t| done && t.recur.is_some());
+
+    let ids: Vec<_> = plain.iter().map(|t| t.id).collect();
+    let mut count = if ids.is_empty() {
+        0
+    } else {
+        db.set_done_many(&ids, done)?
+    };
+    for todo in recurring {
+        recur::advance(db, todo, HistoryKind::Completed)?;
+        count += 1;
+    }
+
+    if count == 0 {
+        print_warning("No matching todos to change");
+    } else if done {
+        print_success(&format!("Marked {} todo(s) as done", count));
+    } else {
+        print_success(&format!("Marked {} todo(s) as pending", count));
+    }
+    Ok(())
+}
+
+/// Whether the arguments pick more than a single todo number: several numbers, a range,
+/// `all` or filter terms
+fn is_bulk(targets: &[String]) -> bool {
+    !matches!(targets, [one] if one.parse::<usize>().is_ok() || looks_like_short_id(one))
+}
+
+/// `todo done 1 3 5` / `todo done 2-6` / `todo done all`: complete several todos after
+/// one confirmation
+fn done_many(
+    db: &TodoDb,
+    config: &Config,
+    targets: &[String],
+    cascade: bool,
+    skip_confirm: bool,
+) -> Result<()> {
+    let todos = db.list_all()?;
+    let rows: Vec<usize> = resolve_selection(&todos, targets)?
+        .into_iter()
+        .filter(|&i| !todos[i].done)
+        .collect();
+    if rows.is_empty() {
+        print_warning("No pending todos matched");
+        return Ok(());
+    }
+    if rows.len() > 1 && !skip_confirm && !confirm_bulk(&todos, &rows, "Complete")? {
+        print_warning("Aborted, nothing changed");
+        return Ok(());
+    }
+
+    // Subtasks that are selected too don't hold their parent back
+    let selected: HashSet<ObjectId> = rows.iter().map(|&i| todos[i].id).collect();
+    let mut ids: Vec<ObjectId> = Vec::new();
+    let mut completed = Vec::new();
+    let mut held_back = Vec::new();
+    for &i in &rows {
+        let todo = &todos[i];
+        let pending: Vec<ObjectId> = todo
+            .descendants(&todos)
+            .into_iter()
+            .filter(|t| !t.done && !selected.contains(&t.id))
+            .map(|t| t.id)
+            .collect();
+        if !pending.is_empty() && !cascade {
+            held_back.push(format!("#{}", i + 1));
+            continue;
+        }
+        ids.extend(pending);
+        if todo.recur.is_some() {
+            recur::advance(db, todo, HistoryKind::Completed)?;
+        } else {
+            ids.push(todo.id);
+        }
+        completed.push(todo);
+    }
+    ids.sort();
+    ids.dedup();
+    if !ids.is_empty() {
+        db.set_done_many(&ids, true)?;
+    }
+
+    if !completed.is_empty() {
+        print_success(&format!("Marked {} todo(s) as done", completed.len()));
+    }
+    if !held_back.is_empty() {
+        print_warning(&format!(
+            "Skipped {} with pending subtasks; add --cascade to complete those too",
+            held_back.join(", ")
+        ));
+    }
+    if config.auto_complete_parents {
+        let mut parents = Vec::new();
+        for todo in completed {
+            parents.extend(complete_finished_parents(db, todo)?);
+        }
+        for title in parents {
+            print_success(&format!("All subtasks done, completed parent: {}", title));
+        }
+    }
+    Ok(())
+}
+
+/// `todo done <#>... [--cascade] [--yes]`, where `<#>` may also be a range, `all` or a filter
+pub fn cmd_done(db: &TodoDb, config: &Config, args: Vec<String>) -> Result<()> {
+    if args.iter().any(|a| a == "--all") {
+        return set_done_all(db, &args, true);
+    }
+
+    let cascade = args.iter().any(|a| a == "--cascade");
+    let skip_confirm = args.iter().any(|a| a == "--yes" || a == "-y");
+    let targets: Vec<String> = args
+        .into_iter()
+        .filter(|a| !a.starts_with("--") && a != "-y")
+        .collect();
+    if targets.is_empty() {
+        return Err(anyhow!(
+            "Missing todo number. Usage: todo done <#> [--cascade]  (or 1 3 5, 2-6, all)"
+        ));
+    }
+    if is_bulk(&targets) {
+        return done_many(db, config, &targets, cascade, skip_confirm);
+    }
+    let (index, todo) = get_todo_by_index(db, &targets[0])?;
+
     if todo.done {
         print_warning(&format!("Todo #{} is already done", index));
         return Ok(());
     }
 
+    // A parent can't be finished while its subtasks aren't, unless they go with it
+    let todos = db.list_all()?;
+    let pending_subtasks: Vec<_> = todo
+        .descendants(&todos)
+        .into_iter()
+        .filter(|t| !t.done)
+        .map(|t| t.id)
+        .collect();
+    if !pending_subtasks.is_empty() {
+        if !cascade {
+            return Err(anyhow!(
+                "#{} has {} pending subtask(s). Finish them first, or run: todo done {} --cascade",
+                index,
+                pending_subtasks.len(),
+                index
+            ));
+        }
+        db.set_done_many(&pending_subtasks, true)?;
+        print_info(&format!("  Also completed {} subtask(s)", pending_subtasks.len()));
+    }
+
+    if todo.recur.is_some() {
+        let next = recur::advance(db, &todo, HistoryKind::Completed)?;
+        print_success(&format!("Completed this occurrence of #{}: {}", index, todo.title));
+        print_info(&format!("  Next due: {}", format_remind_at(&next)));
+        return Ok(());
+    }
+
     db.mark_done(&todo.id)?;
     print_success(&format!("Marked #{} as done: {}", index, todo.title));
+
+    if config.auto_complete_parents {
+        for title in complete_finished_parents(db, &todo)? {
+            print_success(&format!("All subtasks done, completed parent: {}", title));
+        }
+    }
     Ok(())
 }
 
+/// `todo sub <#> "child task" [add options]`: add a subtask under todo #
+pub fn cmd_sub(db: &TodoDb, config: &Config, args: Vec<String>) -> Result<()> {
+    if args.len() < 2 {
+        return Err(anyhow!("Usage: todo sub <#> \"child task\""));
+    }
+
+    let mut add_args = args[1..].to_vec();
+    add_args.push("--parent".to_string());
+    add_args.push(args[0].clone());
+    cmd_add(db, config, add_args)
+}
+
+/// `todo undone <#>... [--yes]`, taking the same selections as `todo done`
 pub fn cmd_undone(db: &TodoDb, args: Vec<String>) -> Result<()> {
-    let index_str = args
-        .first()
-        .ok_or_else(|| anyhow!("Missing todo number. Usage: todo undone <#>"))?;
-    let (index, todo) = get_todo_by_index(db, index_str)?;
+    if args.iter().any(|a| a == "--all") {
+        return set_done_all(db, &args, false);
+    }
+
+    let skip_confirm = args.iter().any(|a| a == "--yes" || a == "-y");
+    let targets: Vec<String> = args
+        .into_iter()
+        .filter(|a| a != "--yes" && a != "-y")
+        .collect();
+    if targets.is_empty() {
+        return Err(anyhow!("Missing todo number. Usage: todo undone <#>  (or 1 3 5, 2-6, all)"));
+    }
+    if is_bulk(&targets) {
+        let todos = db.list_all()?;
+        let rows: Vec<usize> = resolve_selection(&todos, &targets)?
+            .into_iter()
+            .filter(|&i| todos[i].done)
+            .collect();
+        if rows.is_empty() {
+            print_warning("No completed todos matched");
+            return Ok(());
+        }
+        if rows.len() > 1 && !skip_confirm && !confirm_bulk(&todos, &rows, "Reopen")? {
+            print_warning("Aborted, nothing changed");
+            return Ok(());
+        }
+        let ids: Vec<ObjectId> = rows.iter().map(|&i| todos[i].id).collect();
+        db.set_done_many(&ids, false)?;
+        print_success(&format!("Marked {} todo(s) as pending", ids.len()));
+        return Ok(());
+    }
+    let (index, todo) = get_todo_by_index(db, &targets[0])?;
 
     if !todo.done {
         print_warning(&format!("Todo #{} is not marked as done", index));
@@ -157,9 +1327,10 @@ pub fn cmd_edit(db: &TodoDb, args: Vec<String>) -> Result<()> {
 }
 
 pub fn cmd_remind(db: &TodoDb, args: Vec<String>) -> Result<()> {
+    let (args, escalate_str) = extract_option(&args, "--escalate", None);
     if args.is_empty() {
         return Err(anyhow!(
-            "Usage: todo remind <#> <time>  or  todo remind <#> clear"
+            "Usage: todo remind <#> <time|every ...> [--escalate 30m:high,1h:critical:push]  or  todo remind <#> clear"
         ));
     }
 
@@ -180,7 +1351,7 @@ pub fn cmd_remind(db: &TodoDb, args: Vec<String>) -> Result<()> {
         return Ok(());
     }
 
-    let time_str = &args[1];
+    let time_str = args[1..].join(" ");
 
     if time_str == "clear" || time_str == "off" || time_str == "none" {
         db.clear_reminder(&todo.id)?;
@@ -188,62 +1359,286 @@ pub fn cmd_remind(db: &TodoDb, args: Vec<String>) -> Result<()> {
         return Ok(());
     }
 
-    let remind_at = parse_reminder(time_str)?;
-    db.set_reminder(&todo.id, Some(remind_at))?;
+    let escalation = escalate_str.as_deref().map(parse_escalation).transpose()?;
 
-    print_success(&format!(
-        "Set reminder for #{}: {} ({})",
-        index,
-        todo.title,
-        format_remind_at(&remind_at)
-    ));
+    if is_repeating(&time_str) {
+        let rule = time_str.trim().to_lowercase();
+        let (remind_at, _) = parse_repeating(&rule)?;
+        db.set_repeating_reminder(&todo.id, remind_at, &rule)?;
+
+        print_success(&format!(
+            "Set repeating reminder for #{}: {} ({}, next {})",
+            index,
+            todo.title,
+            rule,
+            format_remind_at(&remind_at)
+        ));
+    } else {
+        let remind_at = parse_reminder(&time_str)?;
+        db.set_reminder(&todo.id, Some(remind_at))?;
+
+        print_success(&format!(
+            "Set reminder for #{}: {} ({})",
+            index,
+            todo.title,
+            format_remind_at(&remind_at)
+        ));
+    }
+
+    if let Some(steps) = escalation {
+        db.set_escalation(&todo.id, &steps)?;
+        print_info(&format!("  Escalates {} time(s) while pending", steps.len()));
+    }
 
     Ok(())
 }
 
-pub fn cmd_remove(db: &TodoDb, args: Vec<String>) -> Result<()> {
-    let index_str = args
-        .first()
-        .ok_or_else(|| anyhow!("Missing todo number. Usage: todo rm <#>"))?;
-    let (index, todo) = get_todo_by_index(db, index_str)?;
+/// Parse a range like `2-6` into inclusive list numbers
+fn parse_range(arg: &str) -> Option<(usize, usize)> {
+    let (start, end) = arg.split_once('-')?;
+    Some((start.parse().ok()?, end.parse().ok()?))
+}
 
-    db.delete(&todo.id)?;
-    print_success(&format!("Removed #{}: {}", index, todo.title));
-    Ok(())
+/// Resolve list numbers (`3`), ranges (`2-6`), `all` or filter terms (`tag:work`) to
+/// positions in `todos`
+fn resolve_selection(todos: &[Todo], args: &[String]) -> Result<Vec<usize>> {
+    if matches!(args, [only] if only == "all") {
+        return Ok((0..todos.len()).collect());
+    }
+    // Short ids can be mixed with numbers and ranges
+    let mut by_id = Vec::new();
+    let mut rest = Vec::new();
+    for arg in args {
+        match find_short_id(todos, arg)? {
+            Some(i) => by_id.push(i),
+            None => rest.push(arg.clone()),
+        }
+    }
+    let args = rest;
+    let is_index = |a: &String| a.parse::<usize>().is_ok() || parse_range(a).is_some();
+
+    if args.is_empty() {
+        by_id.sort_unstable();
+        by_id.dedup();
+        return Ok(by_id);
+    }
+    if !by_id.is_empty() && !args.iter().all(is_index) {
+        return Err(anyhow!("Use numbers and short ids, or a filter, but not both at once"));
+    }
+    if !args.iter().all(is_index) {
+        let filter = Filter::parse(&args)?;
+        return Ok(todos
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| filter.matches(t))
+            .map(|(i, _)| i)
+            .collect());
+    }
+
+    let mut rows = by_id;
+    for arg in &args {
+        let (start, end) = parse_range(arg).unwrap_or_else(|| {
+            let n = arg.parse().unwrap_or_default();
+            (n, n)
+        });
+        if start == 0 || start > end {
+            return Err(anyhow!("Invalid range: {}. Todo numbers start at 1", arg));
+        }
+        if end > todos.len() {
+            return Err(anyhow!(
+                "Todo #{} not found. You have {} todos.",
+                end,
+                todos.len()
+            ));
+        }
+        rows.extend((start - 1)..end);
+    }
+
+    rows.sort_unstable();
+    rows.dedup();
+    Ok(rows)
+}
+
+/// Show the rows about to be deleted and require typing `yes` or the count
+fn confirm_bulk(todos: &[Todo], rows: &[usize], action: &str) -> Result<bool> {
+    print_todo_rows(todos, rows);
+    let answer = prompt(&format!(
+        "{} these {} todos? Type 'yes' or {} to confirm:",
+        action,
+        rows.len(),
+        rows.len()
+    ))?;
+
+    Ok(matches!(answer, Some(a) if a == "yes" || a == rows.len().to_string()))
+}
+
+/// Pull the flags `rm` and `clear` share out of args: `--force`/`-f` (or `--yes`/`-y`) to
+/// skip the confirmation, and `--dry-run` to only show what would go
+fn extract_delete_flags(args: Vec<String>) -> (Vec<String>, bool, bool) {
+    let is_force = |a: &str| matches!(a, "--force" | "-f" | "--yes" | "-y");
+    let force = args.iter().any(|a| is_force(a));
+    let dry_run = args.iter().any(|a| a == "--dry-run");
+    let args = args.into_iter().filter(|a| !is_force(a) && a != "--dry-run").collect();
+    (args, force, dry_run)
+}
+
+/// Ask before deleting `rows`: y/N for a single todo, typing `yes` or the count for several.
+/// There's no one to ask in a script, so it has to pass `--force` instead.
+fn confirm_delete(todos: &[Todo], rows: &[usize], action: &str) -> Result<bool> {
+    if json_output() || !io::stdin().is_terminal() {
+        return Err(anyhow!("Not deleting without confirmation; pass --force to skip it"));
+    }
+    let [i] = rows else {
+        return confirm_bulk(todos, rows, action);
+    };
+    let answer = prompt(&format!("{} #{} \"{}\"? [y/N]", action, i + 1, todos[*i].title))?;
+    Ok(matches!(answer.as_deref(), Some("y" | "yes")))
+}
+
+/// What `--dry-run` prints instead of deleting
+fn preview_delete(todos: &[Todo], rows: &[usize]) {
+    print_todo_rows(todos, rows);
+    print_info(&format!("Dry run: {} todo(s) would be deleted; nothing was changed", rows.len()));
 }
 
-pub fn cmd_clear(db: &TodoDb) -> Result<()> {
+/// Move `rows` to the trash, first dropping whatever has been there too long
+fn trash_rows(db: &TodoDb, config: &Config, todos: &[Todo], rows: &[usize]) -> Result<u64> {
+    trash::expire_trash(db, config)?;
+    let selected: Vec<Todo> = rows.iter().map(|&i| todos[i].clone()).collect();
+    db.trash_many(&selected)
+}
+
+/// `todo rm <#>|<range>|<filter>... [--force] [--dry-run]`: deleted todos go to the trash
+pub fn cmd_remove(db: &TodoDb, config: &Config, args: Vec<String>) -> Result<()> {
+    let (args, force, dry_run) = extract_delete_flags(args);
+
+    if args.is_empty() {
+        return Err(anyhow!(
+            "Missing todo number. Usage: todo rm <#>  (or a range like 2-6, or a filter)"
+        ));
+    }
+
     let todos = db.list_all()?;
-    let completed: Vec<_> = todos.iter().filter(|t| t.done).collect();
+    let rows = resolve_selection(&todos, &args)?;
+
+    if rows.is_empty() {
+        print_warning("No todos matched");
+        return Ok(());
+    }
+    if dry_run {
+        preview_delete(&todos, &rows);
+        return Ok(());
+    }
+    if !force && !confirm_delete(&todos, &rows, "Delete")? {
+        print_warning("Aborted, nothing deleted");
+        return Ok(());
+    }
+
+    let count = trash_rows(db, config, &todos, &rows)?;
+    match rows.as_slice() {
+        [i] => print_success(&format!("Removed #{}: {}", i + 1, todos[*i].title)),
+        _ => print_success(&format!("Removed {} todo(s)", count)),
+    }
+    print_info("  In the trash; todo restore --trash brings it back");
+    Ok(())
+}
+
+/// Typed phrase required before `todo purge --all` wipes the database
+const PURGE_ALL_PHRASE: &str = "delete everything";
+
+/// `todo purge --done` drops completed todos for good; `todo purge --all` empties the database
+pub fn cmd_purge(db: &TodoDb, args: Vec<String>) -> Result<()> {
+    let skip_confirm = args.iter().any(|a| a == "--yes" || a == "-y");
+    let all = args.iter().any(|a| a == "--all");
+    let done = args.iter().any(|a| a == "--done");
+
+    if all == done {
+        return Err(anyhow!("Usage: todo purge --done  or  todo purge --all [--yes]"));
+    }
+
+    if all {
+        print_warning("This permanently deletes ALL todos, goals and other saved data.");
+        if !skip_confirm {
+            let answer = prompt(&format!("Type '{}' to confirm:", PURGE_ALL_PHRASE))?;
+            if answer.as_deref() != Some(PURGE_ALL_PHRASE) {
+                print_warning("Aborted, nothing deleted");
+                return Ok(());
+            }
+        }
+        let count = db.purge_all()?;
+        print_success(&format!("Purged database ({} record(s) deleted)", count));
+        return Ok(());
+    }
 
-    if completed.is_empty() {
-        print_warning("No completed todos to clear");
+    let todos = db.list_all()?;
+    let rows: Vec<usize> = (0..todos.len()).filter(|&i| todos[i].done).collect();
+    if rows.is_empty() {
+        print_warning("No completed todos to purge");
         return Ok(());
     }
 
-    let count = completed.len();
-    for todo in completed {
-        db.delete(&todo.id)?;
+    if !skip_confirm && !confirm_bulk(&todos, &rows, "Permanently delete")? {
+        print_warning("Aborted, nothing deleted");
+        return Ok(());
     }
 
-    print_success(&format!("Cleared {} completed todo(s)", count));
+    let count = db.delete_done()?;
+    print_success(&format!("Permanently deleted {} completed todo(s)", count));
     Ok(())
 }
 
-/// Check for due reminders and send notifications (one-shot, for cron/launchd)
-pub fn cmd_notify(db: &TodoDb) -> Result<()> {
-    let due = db.get_due_reminders()?;
+/// `todo clear [--all] [--before 30d] [--older-than 30d] [--tag x] [--force] [--dry-run]`;
+/// without flags, moves completed todos to the trash
+pub fn cmd_clear(db: &TodoDb, config: &Config, args: Vec<String>) -> Result<()> {
+    let (args, force, dry_run) = extract_delete_flags(args);
+    let (args, before_str) = extract_option(&args, "--before", None);
+    let (args, older_str) = extract_option(&args, "--older-than", None);
+    let (args, tags) = extract_option_all(&args, "--tag", Some("-t"));
+    let everything = args.iter().any(|a| a == "--all");
+
+    let age = |s: &str| {
+        parse_minutes(&s.to_lowercase())
+            .map(|m| BsonDateTime::now().timestamp_millis() - m * 60_000)
+            .ok_or_else(|| anyhow!("Invalid age: {}. Examples: 7d, 2w, 30d", s))
+    };
+    let cutoff = before_str.as_deref().map(age).transpose()?;
+    // Completed that long ago; todos done before completion times were kept go by creation
+    let done_cutoff = older_str.as_deref().map(age).transpose()?;
+
+    let todos = db.list_all()?;
+    let rows: Vec<usize> = (0..todos.len())
+        .filter(|&i| {
+            let t = &todos[i];
+            (everything || t.done)
+                && cutoff.is_none_or(|c| t.created_at.timestamp_millis() < c)
+                && done_cutoff.is_none_or(|c| {
+                    t.done && t.done_at.unwrap_or(t.created_at).timestamp_millis() < c
+                })
+                && tags.iter().all(|tag| t.tags.contains(tag))
+        })
+        .collect();
 
-    if due.is_empty() {
+    if rows.is_empty() {
+        print_warning("No matching todos to clear");
         return Ok(());
     }
 
-    for todo in due {
-        if send_notification(&todo.title, "Time for your todo!").is_ok() {
-            db.mark_notified(&todo.id)?;
-            print_info(&format!("Notified: {}", todo.title));
-        }
+    if dry_run {
+        preview_delete(&todos, &rows);
+        return Ok(());
+    }
+    if !force && !confirm_delete(&todos, &rows, "Clear")? {
+        print_warning("Aborted, nothing cleared");
+        return Ok(());
     }
 
+    let count = trash_rows(db, config, &todos, &rows)?;
+
+    if everything {
+        print_success(&format!("Cleared {} todo(s)", count));
+    } else {
+        print_success(&format!("Cleared {} completed todo(s)", count));
+    }
+    print_info("  In the trash; todo restore --trash brings them back");
     Ok(())
 }
@@ -0,0 +1,81 @@
+use std::fs;
+use std::process::Command;
+
+use anyhow::{anyhow, Context, Result};
+
+use super::get_todo_by_index;
+use crate::db::TodoDb;
+use crate::display::{print_info, print_success};
+
+/// Editor from $VISUAL or $EDITOR (which may carry flags, e.g. "code -w"), else a
+/// platform default
+pub(super) fn editor() -> Vec<String> {
+    let configured = std::env::var("VISUAL")
+        .ok()
+        .or_else(|| std::env::var("EDITOR").ok())
+        .filter(|e| !e.trim().is_empty());
+    match configured {
+        Some(e) => e.split_whitespace().map(String::from).collect(),
+        None if cfg!(windows) => vec!["notepad".to_string()],
+        None => vec!["vi".to_string()],
+    }
+}
+
+/// Let the user edit `text` in their editor and return what they saved
+pub(super) fn edit_in_editor(text: &str, name: &str) -> Result<String> {
+    let path = std::env::temp_dir().join(format!("todo-note-{}.md", name));
+    fs::write(&path, text).with_context(|| format!("failed to write {}", path.display()))?;
+
+    let editor = editor();
+    let status = Command::new(&editor[0])
+        .args(&editor[1..])
+        .arg(&path)
+        .status()
+        .with_context(|| format!("failed to start {}", editor[0]));
+    let edited = fs::read_to_string(&path);
+    let _ = fs::remove_file(&path);
+
+    let status = status?;
+    if !status.success() {
+        return Err(anyhow!("{} exited with {}; nothing was changed", editor[0], status));
+    }
+    edited.with_context(|| format!("failed to read {}", path.display()))
+}
+
+/// `todo note <#> ["text"|clear]`: set a todo's notes, or edit them in $EDITOR when no
+/// text is given
+pub fn cmd_note(db: &TodoDb, args: Vec<String>) -> Result<()> {
+    let usage = "Usage: todo note <#> [\"text\"]  (no text opens $EDITOR; clear removes them)";
+    let index_str = args.first().ok_or_else(|| anyhow!(usage))?;
+    let (index, todo) = get_todo_by_index(db, index_str)?;
+
+    let text = match args[1..].join(" ").trim() {
+        "clear" | "none" => String::new(),
+        "" => edit_in_editor(todo.notes.as_deref().unwrap_or_default(), &todo.id.to_hex())?,
+        text => text.to_string(),
+    };
+    let text = text.trim_end();
+
+    if text.is_empty() {
+        if todo.notes.is_some() {
+            db.set_notes(&todo.id, None)?;
+            print_success(&format!("Cleared notes for #{}: {}", index, todo.title));
+        } else {
+            print_info(&format!("Todo #{} has no notes", index));
+        }
+        return Ok(());
+    }
+    if todo.notes.as_deref() == Some(text) {
+        print_info(&format!("Notes for #{} unchanged", index));
+        return Ok(());
+    }
+
+    db.set_notes(&todo.id, Some(text))?;
+    print_success(&format!(
+        "Saved notes for #{}: {} ({} line(s))",
+        index,
+        todo.title,
+        text.lines().count()
+    ));
+    Ok(())
+}
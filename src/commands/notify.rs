@@ -0,0 +1,342 @@
+use std::collections::HashSet;
+
+use anyhow::{anyhow, Result};
+use chrono::{Duration, Local, NaiveTime, TimeZone};
+use polodb_core::bson::{oid::ObjectId, DateTime as BsonDateTime};
+
+use crate::config::Config;
+use crate::db::TodoDb;
+use crate::display::{print_info, print_reminder_preview, print_warning};
+use crate::models::{Todo, Urgency};
+use crate::remind::{deliver, format_remind_at, parse_minutes, to_local};
+use crate::schedule::Schedule;
+
+/// Runs of `todo notify` closer together than this are skipped, so a cron firing every
+/// few seconds or two overlapping schedulers can't send everything twice
+const MIN_RUN_GAP_MS: i64 = 30_000;
+
+/// Next firing of a repeating reminder, skipping any occurrences missed while asleep
+fn next_repeat(todo: &Todo) -> Option<BsonDateTime> {
+    let schedule = Schedule::parse(todo.remind_every.as_deref()?).ok()?;
+    let last = to_local(&todo.remind_at?)?;
+    let next = schedule.next_since(last, Local::now())?;
+    Some(BsonDateTime::from_millis(next.timestamp_millis()))
+}
+
+/// Notification body, mentioning where the todo happens if it has a location
+fn with_location(message: &str, todo: &Todo) -> String {
+    match todo.location {
+        Some(ref location) => format!("{} (at {})", message, location),
+        None => message.to_string(),
+    }
+}
+
+/// Where a todo's notifications go, by the `notify` setting of its tags' `tag_defaults`
+/// or its project's `project_defaults`
+#[derive(Clone, Copy, PartialEq)]
+enum Route {
+    /// The notification backend, or an escalation step's own
+    Normal,
+    /// Held for the daily digest
+    Digest,
+    /// Always the push backend
+    Push,
+}
+
+fn route(config: &Config, todo: &Todo) -> Route {
+    match config.defaults_for(&todo.tags, todo.project.as_deref()).notify.as_deref() {
+        Some("digest") => Route::Digest,
+        Some("push") => Route::Push,
+        _ => Route::Normal,
+    }
+}
+
+/// The backend for a todo's reminders and lead-time notifications
+fn backend_for<'a>(config: &'a Config, todo: &Todo) -> &'a str {
+    match route(config, todo) {
+        Route::Push => "push",
+        _ => config.notification_backend.as_str(),
+    }
+}
+
+/// When the digest last came round: today at `digest_time` if that has passed, else
+/// yesterday
+fn last_digest(config: &Config) -> Result<i64> {
+    let time = NaiveTime::parse_from_str(&config.digest_time, "%H:%M").map_err(|_| {
+        anyhow!("Invalid digest_time '{}'. Use HH:MM, e.g. 18:00", config.digest_time)
+    })?;
+    let now = Local::now();
+    let today = Local
+        .from_local_datetime(&now.date_naive().and_time(time))
+        .earliest()
+        .unwrap_or(now);
+    let last = if today > now { today - Duration::days(1) } else { today };
+    Ok(last.timestamp_millis())
+}
+
+/// Record that a reminder went out: repeating ones move on, one-shot ones are done
+fn finish_reminder(db: &TodoDb, todo: &Todo) -> Result<()> {
+    db.mark_sent(&todo.id, BsonDateTime::now())?;
+    match next_repeat(todo) {
+        Some(next) => {
+            db.advance_reminder(&todo.id, next)?;
+            print_info(&format!("Notified: {} (next {})", todo.title, format_remind_at(&next)));
+        }
+        None => {
+            db.mark_notified(&todo.id)?;
+            print_info(&format!("Notified: {}", todo.title));
+        }
+    }
+    Ok(())
+}
+
+/// One "while you were away" notification for reminders missed while the machine slept
+fn send_catch_up(db: &TodoDb, config: &Config, missed: &[Todo]) -> Result<()> {
+    let mut lines: Vec<String> = missed
+        .iter()
+        .take(5)
+        .filter_map(|t| Some(format!("• {} ({})", t.title, format_remind_at(&t.remind_at?))))
+        .collect();
+    if missed.len() > 5 {
+        lines.push(format!("…and {} more", missed.len() - 5));
+    }
+
+    let title = format!("While you were away: {} reminders", missed.len());
+    let backend = Some(config.notification_backend.as_str());
+    if let Err(e) = deliver(config, backend, Urgency::Normal, &title, &lines.join("\n")) {
+        print_warning(&format!("Could not send the catch-up notification: {:#}", e));
+        return Ok(());
+    }
+
+    print_info(&format!("Caught up on {} missed reminder(s)", missed.len()));
+    for todo in missed {
+        finish_reminder(db, todo)?;
+    }
+    Ok(())
+}
+
+/// One notification listing what came due for `notify = "digest"` tags before the digest
+/// time; reminders and lead-time notifications that came due after it wait for the next
+fn send_digest(db: &TodoDb, config: &Config, reminders: &[Todo], leads: &[Todo]) -> Result<()> {
+    let since = last_digest(config)?;
+    let reminders: Vec<&Todo> = reminders
+        .iter()
+        .filter(|t| t.remind_at.is_some_and(|r| r.timestamp_millis() <= since))
+        .collect();
+    let leads: Vec<&Todo> = leads
+        .iter()
+        .filter(|t| t.notifications.first().is_some_and(|n| n.timestamp_millis() <= since))
+        .collect();
+    let count = reminders.len() + leads.len();
+    if count == 0 {
+        return Ok(());
+    }
+
+    let mut lines: Vec<String> = reminders
+        .iter()
+        .map(|t| format!("• {}", t.title))
+        .chain(leads.iter().filter_map(|t| {
+            Some(format!("• {} (due {})", t.title, format_remind_at(&t.due_at?)))
+        }))
+        .take(8)
+        .collect();
+    if count > 8 {
+        lines.push(format!("…and {} more", count - 8));
+    }
+    let title = format!("Digest: {} todo(s)", count);
+    let backend = Some(config.notification_backend.as_str());
+    if let Err(e) = deliver(config, backend, Urgency::Normal, &title, &lines.join("\n")) {
+        print_warning(&format!("Could not send the digest: {:#}", e));
+        return Ok(());
+    }
+
+    print_info(&format!("Sent the digest of {} todo(s)", count));
+    for todo in reminders {
+        finish_reminder(db, todo)?;
+    }
+    let now = BsonDateTime::now().timestamp_millis();
+    for todo in leads {
+        let pending: Vec<BsonDateTime> =
+            todo.notifications.iter().copied().filter(|n| n.timestamp_millis() > now).collect();
+        db.set_notifications(&todo.id, &todo.notify_before, &pending)?;
+        db.mark_sent(&todo.id, BsonDateTime::now())?;
+    }
+    Ok(())
+}
+
+/// Check for due reminders and send notifications (one-shot, for cron/launchd)
+pub fn cmd_notify(db: &TodoDb, config: &Config) -> Result<()> {
+    if !db.claim_notify_run(MIN_RUN_GAP_MS)? {
+        print_info("Skipped: todo notify already ran in the last 30 seconds");
+        return Ok(());
+    }
+    send_due(db, config)
+}
+
+/// Send every reminder, lead-time notification and escalation that's due. Writes nothing
+/// when nothing is due.
+pub(super) fn send_due(db: &TodoDb, config: &Config) -> Result<()> {
+    let stale_after = parse_minutes(&config.catch_up_after.to_lowercase()).ok_or_else(|| {
+        anyhow!("Invalid catch_up_after: {}. Examples: 15m, 1h", config.catch_up_after)
+    })?;
+    let cooldown = parse_minutes(&config.notify_cooldown.to_lowercase()).ok_or_else(|| {
+        anyhow!("Invalid notify_cooldown: {}. Examples: 5m, 1h", config.notify_cooldown)
+    })?;
+    let tables = [
+        ("tag_defaults", &config.tag_defaults),
+        ("project_defaults", &config.project_defaults),
+    ];
+    for (table, entries) in tables {
+        for (key, defaults) in entries {
+            if let Some(ref notify) = defaults.notify
+                && !matches!(notify.as_str(), "digest" | "push")
+            {
+                return Err(anyhow!(
+                    "Invalid notify '{}' in [{}.{}]. Use digest or push",
+                    notify,
+                    table,
+                    key
+                ));
+            }
+        }
+    }
+
+    let now = BsonDateTime::now();
+    let cutoff = now.timestamp_millis() - stale_after * 60_000;
+
+    // A todo notified within the cooldown waits for a later run, whatever is due for it;
+    // `sent` covers notifications from earlier in this run
+    let cooldown_start = now.timestamp_millis() - cooldown * 60_000;
+    let mut sent: HashSet<ObjectId> = HashSet::new();
+    let cooling_down = |todo: &Todo, sent: &HashSet<ObjectId>| {
+        sent.contains(&todo.id)
+            || todo
+                .last_notified_at
+                .is_some_and(|t| t.timestamp_millis() > cooldown_start)
+    };
+
+    // Reminders that should have fired long ago (the machine was asleep or off) are
+    // bundled into one notification when there's more than one of them
+    let (digest_reminders, due): (Vec<Todo>, Vec<Todo>) = db
+        .get_due_reminders()?
+        .into_iter()
+        .filter(|t| !cooling_down(t, &sent))
+        .partition(|t| route(config, t) == Route::Digest);
+    let (missed, on_time): (Vec<Todo>, Vec<Todo>) = due
+        .into_iter()
+        .partition(|t| t.remind_at.is_some_and(|r| r.timestamp_millis() < cutoff));
+    let on_time = if missed.len() > 1 {
+        send_catch_up(db, config, &missed)?;
+        sent.extend(missed.iter().map(|t| t.id));
+        on_time
+    } else {
+        missed.into_iter().chain(on_time).collect()
+    };
+
+    for todo in on_time {
+        let backend = Some(backend_for(config, &todo));
+        let message = with_location("Time for your todo!", &todo);
+        if let Err(e) = deliver(config, backend, Urgency::Normal, &todo.title, &message) {
+            print_warning(&format!("Could not notify \"{}\": {:#}", todo.title, e));
+            continue;
+        }
+        finish_reminder(db, &todo)?;
+        sent.insert(todo.id);
+    }
+
+    // Lead-time notifications before a due date; several that came due while asleep
+    // collapse into one
+    let mut digest_leads = Vec::new();
+    for todo in db.get_due_notifications()? {
+        if cooling_down(&todo, &sent) {
+            continue;
+        }
+        if route(config, &todo) == Route::Digest && todo.due_at.is_some() {
+            digest_leads.push(todo);
+            continue;
+        }
+        let pending: Vec<BsonDateTime> = todo
+            .notifications
+            .iter()
+            .copied()
+            .filter(|n| n.timestamp_millis() > now.timestamp_millis())
+            .collect();
+        let Some(due) = todo.due_at else {
+            db.set_notifications(&todo.id, &todo.notify_before, &[])?;
+            continue;
+        };
+
+        let backend = Some(backend_for(config, &todo));
+        let message = with_location(&format!("Due {}", format_remind_at(&due)), &todo);
+        if let Err(e) = deliver(config, backend, Urgency::Normal, &todo.title, &message) {
+            print_warning(&format!("Could not notify \"{}\": {:#}", todo.title, e));
+            continue;
+        }
+
+        db.set_notifications(&todo.id, &todo.notify_before, &pending)?;
+        db.mark_sent(&todo.id, BsonDateTime::now())?;
+        sent.insert(todo.id);
+        print_info(&format!("Notified: {} (due {})", todo.title, format_remind_at(&due)));
+    }
+    send_digest(db, config, &digest_reminders, &digest_leads)?;
+
+    // Follow up on reminders that fired earlier but are still pending
+    for todo in db.get_due_escalations()? {
+        let Some(step) = todo.due_escalation(now) else {
+            continue;
+        };
+        // Quiet tags don't follow up; phone tags follow up on the phone
+        let backend = match route(config, &todo) {
+            Route::Digest => continue,
+            Route::Push => Some("push"),
+            Route::Normal => step.backend.as_deref(),
+        };
+        if cooling_down(&todo, &sent) {
+            continue;
+        }
+
+        let message = match step.urgency {
+            Urgency::Normal => "Still pending",
+            Urgency::High => "Still pending - please take a look",
+            Urgency::Critical => "Overdue and still pending!",
+        };
+
+        let message = with_location(message, &todo);
+        if let Err(e) = deliver(config, backend, step.urgency, &todo.title, &message) {
+            print_warning(&format!("Could not escalate \"{}\": {:#}", todo.title, e));
+        } else {
+            db.mark_escalated(&todo.id, todo.escalation_step)?;
+            db.mark_sent(&todo.id, BsonDateTime::now())?;
+            print_info(&format!(
+                "Escalated ({}/{}): {}",
+                todo.escalation_step + 1,
+                todo.escalation.len(),
+                todo.title
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// `todo reminders [24h]`: what `notify` will send within the window, including missed ones
+pub fn cmd_reminders(db: &TodoDb, args: Vec<String>) -> Result<()> {
+    let window = args.first().cloned().unwrap_or_else(|| "24h".to_string());
+    let minutes = parse_minutes(&window.to_lowercase())
+        .ok_or_else(|| anyhow!("Invalid window: {}. Examples: 2h, 24h, 7d", window))?;
+    let until = BsonDateTime::now().timestamp_millis() + minutes * 60_000;
+
+    let todos = db.list_all()?;
+    let mut rows: Vec<usize> = (0..todos.len())
+        .filter(|&i| {
+            let t = &todos[i];
+            !t.done
+                && !t.notified
+                && t.remind_at.is_some_and(|r| r.timestamp_millis() <= until)
+        })
+        .collect();
+    rows.sort_by_key(|&i| todos[i].remind_at.map(|r| r.timestamp_millis()));
+
+    print_reminder_preview(&todos, &rows, &window);
+    Ok(())
+}
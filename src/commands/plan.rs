@@ -0,0 +1,89 @@
+use anyhow::{anyhow, Result};
+use chrono::{Duration, Local, NaiveDate};
+use polodb_core::bson::DateTime as BsonDateTime;
+
+use super::extract_option;
+use crate::config::Config;
+use crate::db::TodoDb;
+use crate::display::print_plan;
+use crate::models::Todo;
+use crate::remind::{parse_minutes, to_local};
+
+fn parse_day(input: &str) -> Result<NaiveDate> {
+    let today = Local::now().date_naive();
+    match input.to_lowercase().as_str() {
+        "today" => Ok(today),
+        "tomorrow" => Ok(today + Duration::days(1)),
+        other => NaiveDate::parse_from_str(other, "%Y-%m-%d")
+            .map_err(|_| anyhow!("Invalid day: {}. Use YYYY-MM-DD, today or tomorrow", input)),
+    }
+}
+
+fn on_day(dt: &Option<BsonDateTime>, day: NaiveDate) -> bool {
+    dt.as_ref()
+        .and_then(to_local)
+        .is_some_and(|t| t.date_naive() == day)
+}
+
+/// Least important work to push to another day until the rest fits: todos merely
+/// scheduled (started) that day go before ones due that day, then the least urgent
+fn defer_candidates(
+    todos: &[Todo],
+    rows: &[usize],
+    day: NaiveDate,
+    over: i64,
+    config: &Config,
+) -> Vec<usize> {
+    let now = BsonDateTime::now();
+    let mut candidates: Vec<usize> = rows
+        .iter()
+        .copied()
+        .filter(|&i| todos[i].estimate_minutes.is_some())
+        .collect();
+    candidates.sort_by(|&a, &b| {
+        let due_a = on_day(&todos[a].due_at, day);
+        let due_b = on_day(&todos[b].due_at, day);
+        due_a.cmp(&due_b).then_with(|| {
+            todos[a]
+                .urgency(now, config.effort_weight)
+                .total_cmp(&todos[b].urgency(now, config.effort_weight))
+        })
+    });
+
+    let mut freed = 0;
+    candidates
+        .into_iter()
+        .take_while(|&i| {
+            let keep_going = freed < over;
+            freed += todos[i].estimate_minutes.unwrap_or(0);
+            keep_going
+        })
+        .collect()
+}
+
+/// `todo plan [--day YYYY-MM-DD]`: estimated work due or scheduled on a day vs capacity
+pub fn cmd_plan(db: &TodoDb, config: &Config, args: Vec<String>) -> Result<()> {
+    let (_, day_str) = extract_option(&args, "--day", None);
+    let day = parse_day(day_str.as_deref().unwrap_or("today"))?;
+    let capacity = parse_minutes(&config.daily_capacity.to_lowercase()).ok_or_else(|| {
+        anyhow!("Invalid daily_capacity: {}. Examples: 6h, 360m", config.daily_capacity)
+    })?;
+
+    let todos = db.list_all()?;
+    let rows: Vec<usize> = todos
+        .iter()
+        .enumerate()
+        .filter(|(_, t)| !t.done && (on_day(&t.due_at, day) || on_day(&t.start_at, day)))
+        .map(|(i, _)| i)
+        .collect();
+
+    let booked: i64 = rows.iter().filter_map(|&i| todos[i].estimate_minutes).sum();
+    let defer = if booked > capacity {
+        defer_candidates(&todos, &rows, day, booked - capacity, config)
+    } else {
+        Vec::new()
+    };
+
+    print_plan(&todos, &rows, &day.format("%a %Y-%m-%d").to_string(), booked, capacity, &defer);
+    Ok(())
+}
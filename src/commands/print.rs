@@ -0,0 +1,98 @@
+use anyhow::Result;
+use chrono::Local;
+
+use super::extract_option;
+use crate::db::TodoDb;
+use crate::filter::Filter;
+use crate::models::Todo;
+use crate::remind::to_local;
+
+/// Characters per line; fits an A4 or Letter page in a monospace font
+const PAGE_WIDTH: usize = 72;
+
+/// Right-hand details for a printed line: project, priority, due date, tags
+fn details(todo: &Todo) -> String {
+    let mut parts = Vec::new();
+    if let Some(ref project) = todo.project {
+        parts.push(format!("@{}", project));
+    }
+    if let Some(priority) = todo.priority {
+        parts.push(format!("!{}", priority.as_str()));
+    }
+    if let Some(due) = todo.due_at.as_ref().and_then(to_local) {
+        parts.push(format!("due {}", due.format("%a %b %-d")));
+    }
+    parts.extend(todo.tags.iter().map(|t| format!("+{}", t)));
+    parts.join("  ")
+}
+
+/// A checkbox line, with details pushed to the right margin (or onto the next line when
+/// the title is too long to share it)
+fn push_line(todo: &Todo, depth: usize, lines: &mut Vec<String>) {
+    let checkbox = if todo.done { "[x]" } else { "[ ]" };
+    let left = format!("{}{} {}", "    ".repeat(depth), checkbox, todo.title);
+    let right = details(todo);
+    let gap = PAGE_WIDTH.saturating_sub(left.chars().count() + right.chars().count());
+    if right.is_empty() {
+        lines.push(left);
+    } else if gap >= 2 {
+        lines.push(format!("{}{}{}", left, " ".repeat(gap), right));
+    } else {
+        lines.push(left);
+        lines.push(format!("{:>PAGE_WIDTH$}", right));
+    }
+}
+
+/// A todo followed by its subtasks that are on the page, indented
+fn push_tree(todos: &[Todo], rows: &[usize], i: usize, depth: usize, lines: &mut Vec<String>) {
+    push_line(&todos[i], depth, lines);
+    for &child in rows.iter().filter(|&&c| todos[c].parent_id == Some(todos[i].id)) {
+        push_tree(todos, rows, child, depth + 1, lines);
+    }
+}
+
+/// One todo per line with a checkbox; subtasks sit under their parent when it is on the
+/// page too
+fn render_page(todos: &[Todo], rows: &[usize], heading: &str) -> String {
+    let mut lines = vec![
+        heading.to_string(),
+        Local::now().format("%A, %B %-d, %Y").to_string(),
+        "=".repeat(PAGE_WIDTH),
+        String::new(),
+    ];
+
+    let shown = |id| rows.iter().any(|&i| todos[i].id == id);
+    for &i in rows {
+        if !todos[i].parent_id.is_some_and(shown) {
+            push_tree(todos, rows, i, 0, &mut lines);
+        }
+    }
+
+    lines.push(String::new());
+    lines.push(format!("{} todo(s)", rows.len()));
+    lines.join("\n")
+}
+
+/// `todo print [--filter "terms"] [--title text] [terms...]`: a plain-text page of todos
+/// with checkboxes, for a paper copy
+pub fn cmd_print(db: &TodoDb, args: Vec<String>) -> Result<()> {
+    let (args, filter_str) = extract_option(&args, "--filter", None);
+    let (args, heading) = extract_option(&args, "--title", None);
+
+    let mut terms: Vec<String> = args;
+    if let Some(ref filter_str) = filter_str {
+        terms.extend(filter_str.split_whitespace().map(String::from));
+    }
+    let mut filter = Filter::parse(&terms)?;
+    if filter.done.is_none() {
+        filter.done = Some(false);
+    }
+
+    let todos = db.list_all()?;
+    let rows: Vec<usize> = (0..todos.len())
+        .filter(|&i| filter.matches(&todos[i]))
+        .collect();
+
+    println!("{}", render_page(&todos, &rows, heading.as_deref().unwrap_or("TODO")));
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use anyhow::{anyhow, Result};
+
+use super::cmd_add;
+use crate::config::Config;
+use crate::db::TodoDb;
+use crate::models::Priority;
+use crate::remind::parse_reminder;
+
+/// The most words a due date at the end of the text can take ("january 15th 2026 at 3pm")
+const MAX_DATE_WORDS: usize = 5;
+
+/// Words that tie a due date to the title ("by friday", "due jan 15") and go with it
+const DATE_LEADS: &[&str] = &["by", "due"];
+
+/// Where the longest run of words that reads as a date at the end of `words` starts; the
+/// first word always stays, so there's a title left
+fn trailing_date(words: &[String]) -> Option<usize> {
+    let first = words.len().saturating_sub(MAX_DATE_WORDS).max(1);
+    (first..words.len()).find(|&start| parse_reminder(&words[start..].join(" ")).is_ok())
+}
+
+/// `todo quick "submit report friday 3pm +work !high"`: add a todo from one line of text,
+/// taking `+tags`, a `!priority`, an `@project` and a due date at the end out of the title
+pub fn cmd_quick(db: &TodoDb, config: &Config, args: Vec<String>) -> Result<()> {
+    let usage = "Usage: todo quick \"submit report friday 3pm +work !high @acme\"";
+    let text = args.join(" ");
+
+    let mut words: Vec<String> = Vec::new();
+    let mut add_args = Vec::new();
+    let (mut priority, mut project) = (None, None);
+    for word in text.split_whitespace() {
+        if let Some(tag) = word.strip_prefix('+').filter(|t| !t.is_empty()) {
+            add_args.extend(["--tag".to_string(), tag.to_string()]);
+        } else if let Some(found) = word.strip_prefix('!').and_then(Priority::parse) {
+            priority = Some(found);
+        } else if let Some(name) = word.strip_prefix('@').filter(|p| !p.is_empty()) {
+            project = Some(name.to_string());
+        } else {
+            words.push(word.to_string());
+        }
+    }
+
+    if let Some(start) = trailing_date(&words) {
+        add_args.extend(["--due".to_string(), words[start..].join(" ")]);
+        words.truncate(start);
+        let lead = words.last().is_some_and(|w| DATE_LEADS.contains(&w.to_lowercase().as_str()));
+        if lead && words.len() > 1 {
+            words.pop();
+        }
+    }
+    if words.is_empty() {
+        return Err(anyhow!(usage));
+    }
+    if let Some(priority) = priority {
+        add_args.extend(["--priority".to_string(), priority.as_str().to_string()]);
+    }
+    if let Some(project) = project {
+        add_args.extend(["--project".to_string(), project]);
+    }
+
+    let mut args = vec![words.join(" ")];
+    args.extend(add_args);
+    cmd_add(db, config, args)
+}
@@ -0,0 +1,154 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Local};
+use polodb_core::bson::DateTime as BsonDateTime;
+
+use super::get_todo_by_index;
+use crate::db::TodoDb;
+use crate::display::{print_info, print_occurrences, print_success};
+use crate::models::{HistoryEntry, HistoryKind, Todo};
+use crate::remind::{format_remind_at, to_local};
+use crate::schedule::Schedule;
+
+/// The occurrence after the todo's current one (or after now, if it has no due date yet)
+pub(super) fn next_occurrence(todo: &Todo) -> Result<BsonDateTime> {
+    let rule = todo
+        .recur
+        .as_deref()
+        .ok_or_else(|| anyhow!("\"{}\" is not a recurring todo", todo.title))?;
+    let schedule = Schedule::parse(rule)?;
+
+    let after = todo
+        .due_at
+        .as_ref()
+        .and_then(to_local)
+        .unwrap_or_else(Local::now);
+    let next = schedule
+        .next_after(after)
+        .ok_or_else(|| anyhow!("Schedule '{}' has no next occurrence", rule))?;
+    Ok(BsonDateTime::from_millis(next.timestamp_millis()))
+}
+
+/// Record the current occurrence as `kind` and move the todo on to the next one
+pub(super) fn advance(db: &TodoDb, todo: &Todo, kind: HistoryKind) -> Result<BsonDateTime> {
+    advance_by(db, todo, kind, None)
+}
+
+/// `advance`, on behalf of a `todo serve` user
+pub(super) fn advance_by(
+    db: &TodoDb,
+    todo: &Todo,
+    kind: HistoryKind,
+    by: Option<&str>,
+) -> Result<BsonDateTime> {
+    let next = next_occurrence(todo)?;
+
+    let mut history = todo.history.clone();
+    history.push(HistoryEntry {
+        kind,
+        occurrence: todo.due_at.unwrap_or_else(BsonDateTime::now),
+        at: BsonDateTime::now(),
+        by: by.map(String::from),
+    });
+
+    db.advance_occurrence(&todo.id, next, &history)?;
+    if !todo.notify_before.is_empty() {
+        let pending = Todo::lead_notifications(next, &todo.notify_before, BsonDateTime::now());
+        db.set_notifications(&todo.id, &todo.notify_before, &pending)?;
+    }
+    Ok(next)
+}
+
+/// `todo skip <#>`: move a recurring todo past its current occurrence without completing it
+pub fn cmd_skip(db: &TodoDb, args: Vec<String>) -> Result<()> {
+    let index_str = args
+        .first()
+        .ok_or_else(|| anyhow!("Missing todo number. Usage: todo skip <#>"))?;
+    let (index, todo) = get_todo_by_index(db, index_str)?;
+
+    let next = advance(db, &todo, HistoryKind::Skipped)?;
+    print_success(&format!("Skipped #{}: {}", index, todo.title));
+    print_info(&format!("  Next due: {}", format_remind_at(&next)));
+    Ok(())
+}
+
+/// `todo recur <#> <rule>`: make a todo recurring (or `clear` to stop)
+pub fn cmd_recur(db: &TodoDb, args: Vec<String>) -> Result<()> {
+    if args.len() < 2 {
+        return Err(anyhow!(
+            "Usage: todo recur <#> \"every monday 9am\"  or  todo recur <#> clear"
+        ));
+    }
+
+    let (index, todo) = get_todo_by_index(db, &args[0])?;
+    let rule = args[1..].join(" ").trim().to_lowercase();
+
+    if rule == "clear" || rule == "off" || rule == "none" {
+        db.set_recurrence(&todo.id, None)?;
+        print_success(&format!("#{} no longer recurs: {}", index, todo.title));
+        return Ok(());
+    }
+
+    let schedule = Schedule::parse(&rule)?;
+    db.set_recurrence(&todo.id, Some(&rule))?;
+
+    if todo.due_at.is_none()
+        && let Some(first) = schedule.next_after(Local::now())
+    {
+        db.set_due(&todo.id, Some(BsonDateTime::from_millis(first.timestamp_millis())))?;
+    }
+
+    print_success(&format!("#{} now recurs {}: {}", index, rule, todo.title));
+    Ok(())
+}
+
+/// The current firing followed by the next `count - 1`, as local times
+fn upcoming(rule: &str, current: Option<&BsonDateTime>, count: usize) -> Result<Vec<DateTime<Local>>> {
+    let schedule = Schedule::parse(rule)?;
+    let mut dates = Vec::with_capacity(count);
+
+    let mut next = match current.and_then(to_local) {
+        Some(dt) => Some(dt),
+        None => schedule.next_after(Local::now()),
+    };
+    while let Some(dt) = next {
+        if dates.len() == count {
+            break;
+        }
+        dates.push(dt);
+        next = schedule.next_after(dt);
+    }
+    Ok(dates)
+}
+
+/// `todo occurrences <#> [n]`: preview the next n firings of a recurring todo or reminder
+pub fn cmd_occurrences(db: &TodoDb, args: Vec<String>) -> Result<()> {
+    let index_str = args
+        .first()
+        .ok_or_else(|| anyhow!("Missing todo number. Usage: todo occurrences <#> [count]"))?;
+    let (index, todo) = get_todo_by_index(db, index_str)?;
+
+    let count: usize = match args.get(1) {
+        Some(n) => n
+            .parse()
+            .map_err(|_| anyhow!("Invalid count: {}. Use a number like 5", n))?,
+        None => 5,
+    };
+
+    if todo.recur.is_none() && todo.remind_every.is_none() {
+        return Err(anyhow!(
+            "Todo #{} has no recurrence or repeating reminder",
+            index
+        ));
+    }
+
+    let due = match todo.recur {
+        Some(ref rule) => Some((rule.as_str(), upcoming(rule, todo.due_at.as_ref(), count)?)),
+        None => None,
+    };
+    let reminder = match todo.remind_every {
+        Some(ref rule) => Some((rule.as_str(), upcoming(rule, todo.remind_at.as_ref(), count)?)),
+        None => None,
+    };
+    print_occurrences(index, &todo.title, due, reminder);
+    Ok(())
+}
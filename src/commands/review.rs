@@ -0,0 +1,107 @@
+use anyhow::Result;
+use chrono::Duration;
+use polodb_core::bson::DateTime as BsonDateTime;
+
+use crate::config::Config;
+use crate::db::TodoDb;
+use crate::display::{print_info, print_success, print_warning, prompt};
+use crate::remind::{format_remind_at, parse_reminder};
+
+#[derive(Default)]
+struct ReviewSummary {
+    kept: usize,
+    rescheduled: usize,
+    delegated: usize,
+    deleted: usize,
+}
+
+/// GTD-style walk through every pending todo older than `review_age_days`
+pub fn cmd_review(db: &TodoDb, config: &Config) -> Result<()> {
+    let cutoff = BsonDateTime::now().timestamp_millis()
+        - Duration::days(config.review_age_days).num_milliseconds();
+
+    let todos = db.list_all()?;
+    let stale: Vec<(usize, _)> = todos
+        .iter()
+        .enumerate()
+        .filter(|(_, t)| !t.done && t.created_at.timestamp_millis() <= cutoff)
+        .map(|(i, t)| (i + 1, t.clone()))
+        .collect();
+
+    if stale.is_empty() {
+        print_success(&format!(
+            "Nothing to review: no pending todos older than {} day(s)",
+            config.review_age_days
+        ));
+        return Ok(());
+    }
+
+    print_info(&format!("Reviewing {} stale todo(s)", stale.len()));
+    let mut summary = ReviewSummary::default();
+
+    'todos: for (index, todo) in &stale {
+        let age_days =
+            (BsonDateTime::now().timestamp_millis() - todo.created_at.timestamp_millis())
+                / 86_400_000;
+        println!();
+        print_info(&format!("#{} {} ({}d old)", index, todo.title, age_days));
+
+        loop {
+            let Some(answer) = prompt("[k]eep  [r]eschedule  [d]elegate  [x] delete  [q]uit >")?
+            else {
+                break 'todos;
+            };
+
+            match answer.to_lowercase().as_str() {
+                "" | "k" | "keep" => {
+                    summary.kept += 1;
+                }
+                "r" | "reschedule" => {
+                    let Some(when) = prompt("Remind when? (e.g. 1d, 14:30, tomorrow)")? else {
+                        break 'todos;
+                    };
+                    match parse_reminder(&when) {
+                        Ok(remind_at) => {
+                            db.set_reminder(&todo.id, Some(remind_at))?;
+                            print_success(&format!("Rescheduled ({})", format_remind_at(&remind_at)));
+                            summary.rescheduled += 1;
+                        }
+                        Err(e) => {
+                            print_warning(&format!("{:#}", e));
+                            continue;
+                        }
+                    }
+                }
+                "d" | "delegate" => {
+                    let Some(name) = prompt("Delegate to:")? else {
+                        break 'todos;
+                    };
+                    if name.is_empty() {
+                        continue;
+                    }
+                    db.set_waiting(&todo.id, Some(&name))?;
+                    print_success(&format!("Waiting on {}", name));
+                    summary.delegated += 1;
+                }
+                "x" | "delete" => {
+                    db.trash_many(std::slice::from_ref(todo))?;
+                    print_success("Moved to the trash");
+                    summary.deleted += 1;
+                }
+                "q" | "quit" => break 'todos,
+                _ => {
+                    print_warning("Please answer k, r, d, x or q");
+                    continue;
+                }
+            }
+            break;
+        }
+    }
+
+    println!();
+    print_info(&format!(
+        "Review done: {} kept · {} rescheduled · {} delegated · {} deleted",
+        summary.kept, summary.rescheduled, summary.delegated, summary.deleted
+    ));
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use anyhow::{anyhow, Result};
+
+use crate::config::Config;
+use crate::display::{json_output, print_info, print_rules};
+
+/// `todo rules [test <title>]`: list title rules, or show which would fire for a title
+pub fn cmd_rules(config: &Config, args: Vec<String>) -> Result<()> {
+    match args.first().map(String::as_str) {
+        None | Some("list") => {
+            if config.rules.is_empty() && !json_output() {
+                print_info("No rules configured. Add a [[rules]] section to config.toml");
+            } else {
+                print_rules(&config.rules.iter().collect::<Vec<_>>());
+            }
+            Ok(())
+        }
+        Some("test") => {
+            let title = args[1..].join(" ").trim().to_string();
+            if title.is_empty() {
+                return Err(anyhow!("Usage: todo rules test \"call mom\""));
+            }
+
+            let matched = config.rules_matching(&title)?;
+            if json_output() {
+                print_rules(&matched);
+            } else if matched.is_empty() {
+                print_info(&format!("No rules match \"{}\"", title));
+            } else {
+                print_info(&format!("{} rule(s) fire for \"{}\":", matched.len(), title));
+                print_rules(&matched);
+            }
+            Ok(())
+        }
+        Some(other) => Err(anyhow!(
+            "Unknown rules command '{}'. Use: todo rules [test <title>]",
+            other
+        )),
+    }
+}
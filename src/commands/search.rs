@@ -0,0 +1,70 @@
+use std::collections::HashSet;
+
+use anyhow::{anyhow, Result};
+use polodb_core::bson::oid::ObjectId;
+
+use crate::db::TodoDb;
+use crate::display::{print_search_results, print_todo_rows};
+use crate::filter::Filter;
+use crate::models::Todo;
+
+/// Todos that are gone from the list, the archive and the trash but still remembered by
+/// the undo journal, latest version first
+fn deleted_todos(db: &TodoDb, present: &HashSet<ObjectId>) -> Result<Vec<Todo>> {
+    let mut seen = present.clone();
+    let mut deleted = Vec::new();
+    for entry in db.list_journal()?.into_iter().rev() {
+        for todo in entry.after.into_iter().chain(entry.before) {
+            if seen.insert(todo.id) {
+                deleted.push(todo);
+            }
+        }
+    }
+    Ok(deleted)
+}
+
+/// `todo search <filter>... [--everywhere]`: find todos by words, tags or status. With
+/// `--everywhere`, archived todos, the trash and deleted ones `todo undo` can still bring
+/// back are searched too, with a column saying where each was found.
+pub fn cmd_search(db: &TodoDb, args: Vec<String>) -> Result<()> {
+    let everywhere = args.iter().any(|a| a == "--everywhere" || a == "-e");
+    let terms: Vec<String> = args.into_iter().filter(|a| !a.starts_with('-')).collect();
+    if terms.is_empty() {
+        return Err(anyhow!("Usage: todo search <words or filter terms> [--everywhere]"));
+    }
+    let filter = Filter::parse(&terms)?;
+
+    let todos = db.list_all()?;
+    let rows: Vec<usize> = (0..todos.len()).filter(|&i| filter.matches(&todos[i])).collect();
+    if !everywhere {
+        print_todo_rows(&todos, &rows);
+        return Ok(());
+    }
+
+    let archived = db.list_archived()?;
+    let trashed: Vec<Todo> = db.list_trash()?.into_iter().map(|e| e.todo).collect();
+    let present = todos.iter().chain(&archived).chain(&trashed).map(|t| t.id).collect();
+    let deleted = deleted_todos(db, &present)?;
+
+    // (source, number in that source, todo)
+    let mut hits: Vec<(&str, Option<usize>, &Todo)> =
+        rows.iter().map(|&i| ("list", Some(i + 1), &todos[i])).collect();
+    hits.extend(
+        archived
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| filter.matches(t))
+            .map(|(i, t)| ("archive", Some(i + 1), t)),
+    );
+    hits.extend(
+        trashed
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| filter.matches(t))
+            .map(|(i, t)| ("trash", Some(i + 1), t)),
+    );
+    hits.extend(deleted.iter().filter(|t| filter.matches(t)).map(|t| ("deleted", None, t)));
+
+    print_search_results(&hits);
+    Ok(())
+}
@@ -0,0 +1,763 @@
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{IpAddr, TcpListener, TcpStream};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use polodb_core::bson::{self, oid::ObjectId, DateTime as BsonDateTime, Document};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use super::trash::expire_trash;
+use super::{complete_finished_parents, extract_option, extract_option_all, find_short_id, recur};
+use crate::config::Config;
+use crate::db::TodoDb;
+use crate::display::{
+    print_api_tokens, print_info, print_serve_users, print_success, print_warning,
+};
+use crate::filter::{sort_rows, Filter};
+use crate::ics::render_calendar;
+use crate::json::{patch_todo, todo_from_json, todo_json};
+use crate::models::{ApiToken, HistoryKind, Role, ServeUser, Todo};
+
+/// Request bodies bigger than this are turned away
+const MAX_BODY: usize = 64 * 1024;
+
+/// A client that hasn't sent its request by then is dropped, so it can't hold up others
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// `/events` streams get a comment line this often, so proxies keep them open and clients
+/// that went away are noticed
+const KEEPALIVE: Duration = Duration::from_secs(15);
+
+struct Request {
+    method: String,
+    path: String,
+    /// Query string parameters, decoded
+    query: Vec<(String, String)>,
+    /// From `Authorization: Bearer <token>`
+    token: Option<String>,
+    /// `None` if it was bigger than `MAX_BODY`
+    body: Option<String>,
+}
+
+/// What every request is served against
+struct Server<'a> {
+    config: &'a Config,
+    /// The list `todo serve` was started on; none for a `--db` file
+    list: Option<String>,
+    ics_only: bool,
+}
+
+struct Response {
+    status: &'static str,
+    content_type: &'static str,
+    body: String,
+    /// How many todos matched before `limit` and `offset`, sent as `X-Total-Count`
+    total: Option<usize>,
+    /// Keep the connection open for `/events`
+    events: bool,
+}
+
+impl Response {
+    fn new(status: &'static str, content_type: &'static str, body: String) -> Self {
+        Self { status, content_type, body, total: None, events: false }
+    }
+}
+
+/// What became of a request
+enum Outcome {
+    Answered,
+    /// The list may have changed
+    Wrote,
+    /// The client follows `/events`
+    Subscribed,
+}
+
+/// Clients following `/events`, and the list as they last heard of it
+struct Events {
+    last: HashMap<ObjectId, Document>,
+    subscribers: Vec<TcpStream>,
+}
+
+impl Events {
+    /// Send to every subscriber, dropping those that have gone
+    fn send(&mut self, message: &str) {
+        self.subscribers
+            .retain_mut(|s| s.write_all(message.as_bytes()).and_then(|_| s.flush()).is_ok());
+    }
+
+    /// Tell subscribers about every todo created, updated or deleted since the last call
+    fn publish(&mut self, db: &TodoDb) -> Result<()> {
+        let todos = db.list_all()?;
+        let now = documents(&todos)?;
+        let mut message = String::new();
+        for (i, todo) in todos.iter().enumerate() {
+            let kind = match self.last.get(&todo.id) {
+                None => "created",
+                Some(old) if Some(old) != now.get(&todo.id) => "updated",
+                Some(_) => continue,
+            };
+            message.push_str(&format!("event: {}\ndata: {}\n\n", kind, todo_json(i + 1, todo)));
+        }
+        for id in self.last.keys().filter(|id| !now.contains_key(id)) {
+            let data = json!({ "id": id.to_hex() });
+            message.push_str(&format!("event: deleted\ndata: {}\n\n", data));
+        }
+        self.last = now;
+        if !message.is_empty() {
+            self.send(&message);
+        }
+        Ok(())
+    }
+}
+
+fn documents(todos: &[Todo]) -> Result<HashMap<ObjectId, Document>> {
+    todos
+        .iter()
+        .map(|t| Ok((t.id, bson::to_document(t).context("failed to encode todo")?)))
+        .collect()
+}
+
+fn respond(stream: &mut TcpStream, response: &Response, head_only: bool) -> Result<()> {
+    if response.events {
+        write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\n\r\n\
+             : connected\n\n"
+        )?;
+        stream.flush()?;
+        return Ok(());
+    }
+    let total = match response.total {
+        Some(total) => format!("X-Total-Count: {}\r\n", total),
+        None => String::new(),
+    };
+    let body = if head_only { "" } else { response.body.as_str() };
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\n{}Connection: close\r\n\r\n\
+         {}",
+        response.status,
+        response.content_type,
+        body.len(),
+        total,
+        body
+    )?;
+    stream.flush()?;
+    Ok(())
+}
+
+fn text(status: &'static str, message: &str) -> Result<Response> {
+    Ok(Response::new(status, "text/plain", format!("{}\n", message)))
+}
+
+/// Undo URL encoding: `%2B` and friends, and `+` for a space
+fn decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|h| std::str::from_utf8(h).ok());
+        match (bytes[i], hex.and_then(|h| u8::from_str_radix(h, 16).ok())) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+                continue;
+            }
+            (b'+', _) => out.push(b' '),
+            (byte, _) => out.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+fn read_request(stream: &TcpStream) -> Result<Request> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+
+    let mut token = None;
+    let mut length = 0;
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        if let Some((name, value)) = header.split_once(':') {
+            let value = value.trim();
+            match name.trim().to_ascii_lowercase().as_str() {
+                "authorization" => token = value.strip_prefix("Bearer ").map(|t| t.trim().into()),
+                "content-length" => length = value.parse().unwrap_or(0),
+                _ => {}
+            }
+        }
+        header.clear();
+    }
+
+    let body = if length > MAX_BODY {
+        None
+    } else {
+        let mut bytes = vec![0; length];
+        reader.read_exact(&mut bytes)?;
+        Some(String::from_utf8_lossy(&bytes).to_string())
+    };
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let target = parts.next().unwrap_or_default();
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let query = query
+        .split('&')
+        .filter(|p| !p.is_empty())
+        .map(|p| {
+            let (key, value) = p.split_once('=').unwrap_or((p, ""));
+            (decode(key), decode(value))
+        })
+        .collect();
+    Ok(Request { method, path: decode(path), query, token, body })
+}
+
+fn hash(token: &str) -> String {
+    Sha256::digest(token.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Check a request's token against the stored ones, and the user it signs in as, if any,
+/// giving back that user's name. Without any tokens the list can be read but not changed,
+/// as before tokens existed.
+fn authorize(
+    tokens: &[ApiToken],
+    users: &[ServeUser],
+    token: Option<&str>,
+    list: Option<&str>,
+    write: bool,
+) -> Result<Option<String>, (&'static str, &'static str)> {
+    if tokens.is_empty() {
+        let message = "Changing todos needs a token: todo serve token add --write";
+        return if write { Err(("403 Forbidden", message)) } else { Ok(None) };
+    }
+    let Some(token) = token else {
+        return Err(("401 Unauthorized", "Send an API token: Authorization: Bearer <token>"));
+    };
+    let hash = hash(token);
+    let Some(found) = tokens.iter().find(|t| t.hash == hash) else {
+        return Err(("401 Unauthorized", "Unknown API token"));
+    };
+    if write && !found.write {
+        return Err(("403 Forbidden", "This token is read-only"));
+    }
+    let reaches = found.lists.is_empty() || list.is_some_and(|l| found.lists.contains(&l.into()));
+    if !reaches {
+        return Err(("403 Forbidden", "This token doesn't reach this list"));
+    }
+
+    let Some(ref name) = found.user else {
+        return Ok(None);
+    };
+    let Some(user) = users.iter().find(|u| &u.name == name) else {
+        return Err(("403 Forbidden", "This token's user has been removed"));
+    };
+    if !user.reaches(list, write) {
+        let message = match write {
+            true => "This user can't change this list",
+            false => "This user can't read this list",
+        };
+        return Err(("403 Forbidden", message));
+    }
+    Ok(Some(user.name.clone()))
+}
+
+/// `POST /todos`: a todo object as `--json` prints them, or a plain-text title. Who owns
+/// and completed it come from the signed-in user, never from the body.
+fn add(db: &TodoDb, body: &str, user: Option<&str>) -> Result<Response> {
+    let mut todo = match serde_json::from_str::<Value>(body) {
+        Ok(value @ Value::Object(_)) => match todo_from_json(&value) {
+            Ok(todo) => todo,
+            Err(e) => return text("400 Bad Request", &format!("{:#}", e)),
+        },
+        _ => Todo::new(body.trim().to_string()),
+    };
+    if todo.title.trim().is_empty() {
+        return text("400 Bad Request", "Title cannot be empty");
+    }
+    todo.owner = user.map(String::from);
+    todo.completed_by = user.filter(|_| todo.done).map(String::from);
+    db.insert(&todo)?;
+    let number = db.list_all()?.iter().position(|t| t.id == todo.id).map_or(0, |i| i + 1);
+    let body = todo_json(number, &todo).to_string();
+    Ok(Response::new("201 Created", "application/json", body))
+}
+
+/// Where the todo a URL names (by its id, short id or list number) is in `todos`, or the
+/// status and message saying why there isn't one
+fn find(todos: &[Todo], reference: &str) -> Result<usize, (&'static str, String)> {
+    let found = match (ObjectId::parse_str(reference), reference.parse::<usize>()) {
+        (Ok(id), _) => Ok(todos.iter().position(|t| t.id == id)),
+        (_, Ok(number)) => Ok(number.checked_sub(1).filter(|&i| i < todos.len())),
+        _ => find_short_id(todos, reference),
+    };
+    match found {
+        Ok(Some(i)) => Ok(i),
+        Ok(None) => Err(("404 Not Found", "No such todo".to_string())),
+        Err(e) => Err(("400 Bad Request", format!("{:#}", e))),
+    }
+}
+
+/// A todo as `/todos` gives it, numbered as it is in the list now
+fn todo_response(db: &TodoDb, status: &'static str, id: &ObjectId) -> Result<Response> {
+    let todos = db.list_all()?;
+    let Some(i) = todos.iter().position(|t| &t.id == id) else {
+        return text("404 Not Found", "No such todo");
+    };
+    Ok(Response::new(status, "application/json", todo_json(i + 1, &todos[i]).to_string()))
+}
+
+/// `PATCH /todos/<id>`: change the fields in a JSON object, as `--json` prints them.
+/// Marking a todo done or pending through `done` keeps `done_at` and who completed it in
+/// step; `owner` and `completed_by` are the server's to set.
+fn update(db: &TodoDb, reference: &str, body: &str, user: Option<&str>) -> Result<Response> {
+    let todos = db.list_all()?;
+    let todo = match find(&todos, reference) {
+        Ok(i) => &todos[i],
+        Err((status, message)) => return text(status, &message),
+    };
+    let Ok(Value::Object(patch)) = serde_json::from_str::<Value>(body) else {
+        return text("400 Bad Request", "Send a JSON object with the fields to change");
+    };
+    if let Some(key) = ["owner", "completed_by"].iter().find(|k| patch.contains_key(**k)) {
+        return text("403 Forbidden", &format!("{} is set by the server", key));
+    }
+    let (mut patched, changed) = match patch_todo(todo, &patch) {
+        Ok(result) => result,
+        Err(e) => return text("400 Bad Request", &format!("{:#}", e)),
+    };
+    if patched.title.trim().is_empty() {
+        return text("400 Bad Request", "Title cannot be empty");
+    }
+    let has = |key: &str| changed.iter().any(|c| c == key);
+    if has("done") {
+        if !has("done_at") {
+            patched.done_at = patched.done.then(BsonDateTime::now);
+        }
+        patched.completed_by = user.filter(|_| patched.done).map(String::from);
+    }
+    if !changed.is_empty() {
+        db.replace(&patched)?;
+    }
+    todo_response(db, "200 OK", &todo.id)
+}
+
+/// `DELETE /todos/<id>`: move a todo to the trash, as `todo rm` does
+fn remove(db: &TodoDb, config: &Config, reference: &str) -> Result<Response> {
+    let todos = db.list_all()?;
+    let todo = match find(&todos, reference) {
+        Ok(i) => &todos[i],
+        Err((status, message)) => return text(status, &message),
+    };
+    expire_trash(db, config)?;
+    db.trash_many(std::slice::from_ref(todo))?;
+    Ok(Response::new("204 No Content", "text/plain", String::new()))
+}
+
+/// `POST /todos/<id>/done`: complete a todo (or the current occurrence of a recurring one),
+/// crediting the signed-in user
+fn complete(
+    db: &TodoDb,
+    config: &Config,
+    reference: &str,
+    user: Option<&str>,
+) -> Result<Response> {
+    let todos = db.list_all()?;
+    let todo = match find(&todos, reference) {
+        Ok(i) => &todos[i],
+        Err((status, message)) => return text(status, &message),
+    };
+    if todo.done {
+        return text("409 Conflict", "Already done");
+    }
+    if todo.descendants(&todos).iter().any(|t| !t.done) {
+        return text("409 Conflict", "It has pending subtasks; finish them first");
+    }
+
+    if todo.recur.is_some() {
+        recur::advance_by(db, todo, HistoryKind::Completed, user)?;
+    } else {
+        db.mark_done_by(&todo.id, user)?;
+        if config.auto_complete_parents {
+            complete_finished_parents(db, todo)?;
+        }
+    }
+    todo_response(db, "200 OK", &todo.id)
+}
+
+/// `GET /todos?filter=tag:work status:pending&sort=urgency&limit=50&offset=50`: the
+/// todos matching a `todo list` filter, in the order `--sort` gives, a page at a time.
+/// List numbers stay attached, as in the CLI.
+fn query(db: &TodoDb, config: &Config, request: &Request) -> Result<Response> {
+    let param = |key: &str| {
+        let found = request.query.iter().rev().find(|(k, _)| k == key);
+        found.map(|(_, v)| v.as_str())
+    };
+    let count = |key: &str| match param(key) {
+        Some(n) => n.parse::<usize>().map(Some).map_err(|_| format!("{} takes a number", key)),
+        None => Ok(None),
+    };
+    let (limit, offset) = match (count("limit"), count("offset")) {
+        (Ok(limit), Ok(offset)) => (limit, offset.unwrap_or(0)),
+        (Err(e), _) | (_, Err(e)) => return text("400 Bad Request", &e),
+    };
+    let terms: Vec<String> =
+        param("filter").unwrap_or_default().split_whitespace().map(String::from).collect();
+    let filter = match Filter::parse(&terms) {
+        Ok(filter) => filter,
+        Err(e) => return text("400 Bad Request", &format!("{:#}", e)),
+    };
+
+    let todos = db.list_all()?;
+    let mut rows: Vec<usize> = (0..todos.len()).filter(|&i| filter.matches(&todos[i])).collect();
+    let sort = param("sort").unwrap_or(&config.sort);
+    if let Err(e) = sort_rows(&todos, &mut rows, sort, config) {
+        return text("400 Bad Request", &format!("{:#}", e));
+    }
+
+    let total = rows.len();
+    let page = rows.iter().skip(offset).take(limit.unwrap_or(usize::MAX));
+    let items: Vec<Value> = page.map(|&i| todo_json(i + 1, &todos[i])).collect();
+    let body = Value::Array(items).to_string();
+    Ok(Response { total: Some(total), ..Response::new("200 OK", "application/json", body) })
+}
+
+fn route(db: &TodoDb, server: &Server, request: &Request) -> Result<Response> {
+    // `/lists/<name>/...` reaches another list
+    let (list, path) = match request.path.strip_prefix("/lists/").and_then(|p| p.split_once('/')) {
+        Some((name, rest)) => (Some(name.to_string()), format!("/{}", rest)),
+        None => (server.list.clone(), request.path.clone()),
+    };
+    let method = request.method.as_str();
+    // `/todos/<id>` names one todo, and `/todos/<id>/done` completes it
+    let (item, done) = match path.strip_prefix("/todos/").filter(|p| !p.is_empty()) {
+        Some(rest) => match rest.strip_suffix("/done") {
+            Some(reference) => (Some(reference), true),
+            None => (Some(rest), false),
+        },
+        None => (None, false),
+    };
+    let known = match (method, path.as_str()) {
+        ("GET" | "HEAD", "/todos.ics") => true,
+        ("GET" | "HEAD", "/todos" | "/todos.json") | ("POST", "/todos") => !server.ics_only,
+        ("GET" | "HEAD" | "PATCH" | "DELETE", _) if item.is_some() && !done => !server.ics_only,
+        ("POST", _) => !server.ics_only && done && item.is_some_and(|i| !i.is_empty()),
+        // Only the list the server was started on is followed
+        ("GET", "/events") => !server.ics_only && list == server.list,
+        _ => false,
+    };
+    if !known {
+        return match method {
+            "GET" | "HEAD" => text("404 Not Found", "Not found"),
+            _ => text("405 Method Not Allowed", "Not a method this path takes"),
+        };
+    }
+
+    let write = matches!(method, "POST" | "PATCH" | "DELETE");
+    let tokens = db.get_api_tokens()?;
+    let users = db.get_serve_users()?;
+    // Browsers' EventSource can't set headers, so `/events` also takes the token in the URL
+    let token = match request.token {
+        Some(ref token) => Some(token.as_str()),
+        None if path == "/events" => {
+            let found = request.query.iter().find(|(k, _)| k == "access_token");
+            found.map(|(_, v)| v.as_str())
+        }
+        None => None,
+    };
+    let user = match authorize(&tokens, &users, token, list.as_deref(), write) {
+        Ok(user) => user,
+        Err((status, message)) => return text(status, message),
+    };
+
+    let opened;
+    let db = match list {
+        Some(ref name) if list != server.list => {
+            let Some(path) = server.config.list_path(name).ok().filter(|p| p.exists()) else {
+                return text("404 Not Found", "No such list");
+            };
+            opened = TodoDb::open_path(&path, server.config.read_only || !write)?;
+            &opened
+        }
+        _ => db,
+    };
+
+    match (write, path.as_str()) {
+        (false, "/todos.ics") => {
+            let body = render_calendar(&db.list_all()?);
+            Ok(Response::new("200 OK", "text/calendar; charset=utf-8", body))
+        }
+        (false, "/events") => Ok(Response {
+            events: true,
+            ..Response::new("200 OK", "text/event-stream", String::new())
+        }),
+        (false, _) => match item {
+            Some(reference) => {
+                let todos = db.list_all()?;
+                match find(&todos, reference) {
+                    Ok(i) => {
+                        let body = todo_json(i + 1, &todos[i]).to_string();
+                        Ok(Response::new("200 OK", "application/json", body))
+                    }
+                    Err((status, message)) => text(status, &message),
+                }
+            }
+            None => query(db, server.config, request),
+        },
+        (true, _) => {
+            let user = user.as_deref();
+            let reference = item.unwrap_or_default();
+            match (method, &request.body) {
+                ("DELETE", _) => remove(db, server.config, reference),
+                (_, _) if done => complete(db, server.config, reference, user),
+                (_, None) => text("413 Payload Too Large", "Request body too large"),
+                ("PATCH", Some(body)) => update(db, reference, body, user),
+                (_, Some(body)) => add(db, body, user),
+            }
+        }
+    }
+}
+
+/// Answer a single request
+fn handle(db: &TodoDb, server: &Server, stream: &mut TcpStream) -> Result<Outcome> {
+    let request = read_request(stream)?;
+    let response = route(db, server, &request).unwrap_or_else(|e| {
+        Response::new("500 Internal Server Error", "text/plain", format!("{:#}\n", e))
+    });
+    respond(stream, &response, request.method == "HEAD")?;
+    Ok(if response.events {
+        Outcome::Subscribed
+    } else if matches!(request.method.as_str(), "POST" | "PATCH" | "DELETE") {
+        Outcome::Wrote
+    } else {
+        Outcome::Answered
+    })
+}
+
+/// `todo serve token add [--write] [--lists work,home] [--name phone] [--user sam]`,
+/// `token list` and `token rm <id>`: the keys clients send to `todo serve`
+fn cmd_token(db: &TodoDb, config: &Config, args: &[String]) -> Result<()> {
+    let usage = "Usage: todo serve token add [--write] [--lists work,home] [--name label] \
+                 [--user name] | list | rm <id>";
+    let mut tokens = db.get_api_tokens()?;
+    match args.first().map(String::as_str) {
+        Some("add") => {
+            let (args, name) = extract_option(&args[1..], "--name", None);
+            let (args, user) = extract_option(&args, "--user", None);
+            let (args, lists) = extract_option_all(&args, "--lists", None);
+            for list in &lists {
+                config.list_path(list)?;
+            }
+            if let Some(ref user) = user
+                && !db.get_serve_users()?.iter().any(|u| &u.name == user)
+            {
+                return Err(anyhow!("No user {}; todo serve user add makes one", user));
+            }
+            let write = args.iter().any(|a| a == "--write");
+
+            let mut bytes = [0u8; 20];
+            getrandom::getrandom(&mut bytes)
+                .map_err(|e| anyhow!("failed to generate a token: {}", e))?;
+            let token: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+            let token = format!("todo_{}", token);
+            let hash = hash(&token);
+            let id = hash[..8].to_string();
+            tokens.push(ApiToken {
+                id: id.clone(),
+                hash,
+                name,
+                write,
+                lists: lists.clone(),
+                user: user.clone(),
+                created_at: BsonDateTime::now(),
+            });
+            db.set_api_tokens(&tokens)?;
+
+            let access = if write { "read-write" } else { "read-only" };
+            let reach = match lists.is_empty() {
+                true => "every list".to_string(),
+                false => lists.join(", "),
+            };
+            let user = user.map(|u| format!(", signing in as {}", u)).unwrap_or_default();
+            print_success(&format!("Created {} token {} for {}{}", access, id, reach, user));
+            print_info(&format!("Token: {}", token));
+            print_info("It isn't shown again; clients send it as Authorization: Bearer <token>");
+        }
+        Some("list") | None => print_api_tokens(&tokens),
+        Some("rm") => {
+            let id = args.get(1).ok_or_else(|| anyhow!(usage))?;
+            let count = tokens.len();
+            tokens.retain(|t| &t.id != id);
+            if tokens.len() == count {
+                return Err(anyhow!("No token {}; todo serve token list shows them", id));
+            }
+            db.set_api_tokens(&tokens)?;
+            print_success(&format!("Revoked token {}", id));
+        }
+        Some(_) => return Err(anyhow!(usage)),
+    }
+    Ok(())
+}
+
+/// `todo serve user add <name> [--admin] [--lists chores] [--read family]`, `user list`
+/// and `user rm <name>`: the people sharing `todo serve`, each signing in with tokens made
+/// with `--user`
+fn cmd_user(db: &TodoDb, config: &Config, args: &[String]) -> Result<()> {
+    let usage = "Usage: todo serve user add <name> [--admin] [--lists a,b] [--read c,d] | list \
+                 | rm <name>";
+    let mut users = db.get_serve_users()?;
+    match args.first().map(String::as_str) {
+        Some("add") => {
+            let (args, lists) = extract_option_all(&args[1..], "--lists", None);
+            let (args, read) = extract_option_all(&args, "--read", None);
+            let admin = args.iter().any(|a| a == "--admin");
+            let name = args.iter().find(|a| !a.starts_with('-')).ok_or_else(|| anyhow!(usage))?;
+            if users.iter().any(|u| &u.name == name) {
+                return Err(anyhow!("There's already a user {}", name));
+            }
+            for list in lists.iter().chain(&read) {
+                config.list_path(list)?;
+            }
+            let role = match (admin, lists.is_empty() && read.is_empty()) {
+                (true, true) => Role::Admin,
+                (false, false) => Role::Member,
+                (true, false) => {
+                    return Err(anyhow!("Admins reach every list; drop --lists and --read"));
+                }
+                (false, true) => {
+                    return Err(anyhow!(
+                        "Give a member lists to change (--lists) or read (--read), or make \
+                         them --admin"
+                    ));
+                }
+            };
+            users.push(ServeUser {
+                name: name.clone(),
+                role,
+                lists,
+                read,
+                created_at: BsonDateTime::now(),
+            });
+            db.set_serve_users(&users)?;
+            print_success(&format!("Added {} {}", role.as_str(), name));
+            print_info(&format!(
+                "Give them a token with: todo serve token add --write --user {}",
+                name
+            ));
+        }
+        Some("list") | None => print_serve_users(&users),
+        Some("rm") => {
+            let name = args.get(1).ok_or_else(|| anyhow!(usage))?;
+            let count = users.len();
+            users.retain(|u| &u.name != name);
+            if users.len() == count {
+                return Err(anyhow!("No user {}; todo serve user list shows them", name));
+            }
+            db.set_serve_users(&users)?;
+            let mut tokens = db.get_api_tokens()?;
+            let count = tokens.len();
+            tokens.retain(|t| t.user.as_ref() != Some(name));
+            db.set_api_tokens(&tokens)?;
+            print_success(&format!(
+                "Removed {} and revoked their {} token(s)",
+                name,
+                count - tokens.len()
+            ));
+        }
+        Some(_) => return Err(anyhow!(usage)),
+    }
+    Ok(())
+}
+
+/// `todo serve [--ics-only] [--port 8080] [--bind 127.0.0.1]`: serve a calendar feed of due
+/// todos at /todos.ics, the list as JSON at /todos (`POST` to add one, and `GET`, `PATCH`
+/// and `DELETE` on /todos/<id>), `POST /todos/<id>/done` to complete one and a stream of
+/// changes at /events. Once `todo serve token` has made a token, every request needs one.
+pub fn cmd_serve(db: &TodoDb, config: &Config, args: Vec<String>) -> Result<()> {
+    match args.first().map(String::as_str) {
+        Some("token") => return cmd_token(db, config, &args[1..]),
+        Some("user") => return cmd_user(db, config, &args[1..]),
+        _ => {}
+    }
+    let (args, port) = extract_option(&args, "--port", Some("-p"));
+    let (args, bind) = extract_option(&args, "--bind", None);
+    let bind = bind.unwrap_or_else(|| "127.0.0.1".to_string());
+    let addr = format!("{}:{}", bind, port.as_deref().unwrap_or("8080"));
+
+    let list = match (&config.db_path, &config.list) {
+        (Some(_), _) => None,
+        (None, Some(list)) => Some(list.clone()),
+        (None, None) => Some(config.current_list()?),
+    };
+    let server = Server {
+        config,
+        list,
+        ics_only: args.iter().any(|a| a == "--ics-only"),
+    };
+
+    let listener =
+        TcpListener::bind(&addr).with_context(|| format!("failed to listen on {}", addr))?;
+    if server.ics_only {
+        print_info(&format!("Serving calendar feed at http://{}/todos.ics (Ctrl-C to stop)", addr));
+    } else {
+        print_info(&format!(
+            "Serving http://{}/todos.ics, /todos, /todos/<id>, /todos/<id>/done and /events \
+             (Ctrl-C to stop)",
+            addr
+        ));
+    }
+    let local = bind == "localhost" || bind.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback());
+    if db.get_api_tokens()?.is_empty() {
+        let message = "No API tokens yet: anyone who can reach the server can read the list, \
+                       and no one can change it. todo serve token add makes one";
+        if local {
+            print_info(message);
+        } else {
+            print_warning(message);
+        }
+    }
+
+    // Connections are accepted on their own thread so the loop below can wake up for
+    // keepalives; requests are still answered one at a time, against the one open database
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            if tx.send(stream).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut events = Events { last: documents(&db.list_all()?)?, subscribers: Vec::new() };
+    loop {
+        let mut stream = match rx.recv_timeout(KEEPALIVE) {
+            Ok(Ok(stream)) => stream,
+            Ok(Err(e)) => {
+                print_warning(&format!("Connection failed: {}", e));
+                continue;
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                events.send(": keepalive\n\n");
+                continue;
+            }
+            Err(RecvTimeoutError::Disconnected) => return Ok(()),
+        };
+        let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+        let _ = stream.set_write_timeout(Some(READ_TIMEOUT));
+        match handle(db, &server, &mut stream) {
+            Ok(Outcome::Answered) => {}
+            Ok(Outcome::Wrote) => {
+                if let Err(e) = events.publish(db) {
+                    print_warning(&format!("Failed to send events: {:#}", e));
+                }
+            }
+            Ok(Outcome::Subscribed) => events.subscribers.push(stream),
+            Err(e) => print_warning(&format!("Request failed: {:#}", e)),
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use std::fs;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::config::{config_path, Config};
+use crate::display::{print_info, print_success};
+use crate::tar;
+
+/// The config file's name inside a bundle
+const CONFIG_ENTRY: &str = "config.toml";
+
+/// `todo settings export <bundle.tar>`: the config file (rules, snippets, tag defaults and
+/// everything else in it) packed up for another machine, without any todos
+fn export(path: &str) -> Result<()> {
+    let config = config_path()?;
+    let contents = fs::read(&config).map_err(|_| {
+        anyhow!("No settings to export yet: {} doesn't exist", config.display())
+    })?;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    let bytes = tar::write(&[(CONFIG_ENTRY, contents)], now)?;
+    fs::write(path, bytes).with_context(|| format!("failed to write {}", path))?;
+    print_success(&format!("Saved your settings to {}", path));
+    print_info("  Your todos aren't in it; todo export or todo sync carries those");
+    Ok(())
+}
+
+/// `todo settings import <bundle.tar>`: put a bundle's config in place, keeping the old one
+/// as config.toml.bak
+fn import(path: &str) -> Result<()> {
+    let bytes = fs::read(path).with_context(|| format!("failed to read {}", path))?;
+    let files = tar::read(&bytes).with_context(|| format!("failed to read {}", path))?;
+    let contents = files
+        .into_iter()
+        .find(|(name, _)| name.trim_start_matches("./") == CONFIG_ENTRY)
+        .map(|(_, contents)| contents)
+        .ok_or_else(|| anyhow!("{} has no config.toml; is it a todo settings bundle?", path))?;
+    let text = String::from_utf8(contents).context("the bundled config.toml isn't text")?;
+    let config: Config =
+        toml::from_str(&text).context("the bundled config.toml isn't a valid todo config")?;
+
+    let target = config_path()?;
+    if let Some(dir) = target.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    }
+    if target.exists() {
+        let backup = target.with_extension("toml.bak");
+        fs::copy(&target, &backup)
+            .with_context(|| format!("failed to back up {}", target.display()))?;
+        print_info(&format!("Your previous settings are in {}", backup.display()));
+    }
+    fs::write(&target, text).with_context(|| format!("failed to write {}", target.display()))?;
+    print_success(&format!("Imported settings into {}", target.display()));
+
+    // Paths from the other machine may not exist here
+    let paths: Vec<&str> = [
+        ("data_dir", config.data_dir.is_some()),
+        ("db_path", config.db_path.is_some()),
+        ("sync_dir", config.sync_dir.is_some()),
+        ("dir_tags", !config.dir_tags.is_empty()),
+    ]
+    .into_iter()
+    .filter_map(|(key, set)| set.then_some(key))
+    .collect();
+    if !paths.is_empty() {
+        print_info(&format!("  Check that the paths in {} suit this machine", paths.join(", ")));
+    }
+    Ok(())
+}
+
+/// Dispatch `todo settings export|import <bundle.tar>`
+pub fn cmd_settings(args: Vec<String>) -> Result<()> {
+    let usage = "Usage: todo settings export <bundle.tar>  or  todo settings import <bundle.tar>";
+    match (args.first().map(String::as_str), args.get(1)) {
+        (Some("export"), Some(path)) => export(path),
+        (Some("import"), Some(path)) => import(path),
+        _ => Err(anyhow!(usage)),
+    }
+}
@@ -0,0 +1,89 @@
+use anyhow::{anyhow, Context, Result};
+use qrcode::render::unicode::Dense1x2;
+use qrcode::QrCode;
+use serde_json::{json, Map, Value};
+
+use super::get_todo_by_index;
+use crate::db::TodoDb;
+use crate::display::{json_output, print_info};
+use crate::models::Todo;
+use crate::remind::to_local;
+
+/// Readable summary any phone camera app can show as-is
+fn share_text(todo: &Todo) -> String {
+    let mut lines = vec![todo.title.clone()];
+    if let Some(due) = todo.due_at.as_ref().and_then(to_local) {
+        lines.push(format!("Due: {}", due.format("%Y-%m-%d %H:%M")));
+    }
+    if let Some(ref location) = todo.location {
+        lines.push(format!("Where: {}", location));
+    }
+    if !todo.tags.is_empty() {
+        let tags: Vec<String> = todo.tags.iter().map(|t| format!("+{}", t)).collect();
+        lines.push(format!("Tags: {}", tags.join(" ")));
+    }
+    // Notes set off by blank lines, with the comments after them
+    if let Some(ref notes) = todo.notes {
+        lines.push(String::new());
+        lines.push(notes.trim_end().to_string());
+        if !todo.comments.is_empty() {
+            lines.push(String::new());
+        }
+    }
+    for comment in &todo.comments {
+        lines.push(format!("- {}", comment.text));
+    }
+    lines.join("\n")
+}
+
+/// Compact JSON blob for apps that want structured fields
+fn share_json(todo: &Todo) -> String {
+    let mut fields = Map::new();
+    fields.insert("title".to_string(), json!(todo.title));
+    if let Some(due) = todo.due_at.as_ref().and_then(to_local) {
+        fields.insert("due".to_string(), json!(due.to_rfc3339()));
+    }
+    if let Some(ref location) = todo.location {
+        fields.insert("location".to_string(), json!(location));
+    }
+    fields.insert("tags".to_string(), json!(todo.tags));
+    if let Some(ref notes) = todo.notes {
+        fields.insert("notes".to_string(), json!(notes));
+    }
+    let comments: Vec<&str> = todo.comments.iter().map(|c| c.text.as_str()).collect();
+    fields.insert("comments".to_string(), json!(comments));
+    fields.insert("done".to_string(), json!(todo.done));
+    Value::Object(fields).to_string()
+}
+
+/// `todo share <#> [--qr] [--json]`: print a todo as text or JSON, or as a QR code to scan.
+/// `--json` is the global flag, read through `json_output`.
+pub fn cmd_share(db: &TodoDb, args: Vec<String>) -> Result<()> {
+    let qr = args.iter().any(|a| a == "--qr");
+    let json = json_output();
+    let index_str = args
+        .iter()
+        .find(|a| !a.starts_with("--"))
+        .ok_or_else(|| anyhow!("Usage: todo share <#> [--qr] [--json]"))?;
+    let (index, todo) = get_todo_by_index(db, index_str)?;
+
+    let payload = if json { share_json(&todo) } else { share_text(&todo) };
+    if !qr {
+        println!("{}", payload);
+        return Ok(());
+    }
+
+    let code = QrCode::new(payload.as_bytes())
+        .with_context(|| format!("failed to encode #{} as a QR code", index))?;
+    // Light modules drawn as blocks, so the code scans on a dark terminal background
+    let image = code
+        .render::<Dense1x2>()
+        .dark_color(Dense1x2::Light)
+        .light_color(Dense1x2::Dark)
+        .quiet_zone(true)
+        .build();
+
+    println!("{}", image);
+    print_info(&format!("Scan to copy #{}: {}", index, todo.title));
+    Ok(())
+}
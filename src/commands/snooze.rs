@@ -0,0 +1,92 @@
+use anyhow::{anyhow, Result};
+use polodb_core::bson::DateTime as BsonDateTime;
+
+use super::get_todo_by_index;
+use crate::db::TodoDb;
+use crate::display::{print_info, print_success};
+use crate::models::Todo;
+use crate::remind::{format_remind_at, parse_minutes};
+
+/// How far a snooze with no duration pushes the reminder back
+pub(super) const DEFAULT_SNOOZE: &str = "10m";
+
+/// Minutes in a snooze duration like "10m" or "1h"
+pub(super) fn parse_snooze(duration: &str) -> Result<i64> {
+    parse_minutes(&duration.to_lowercase())
+        .filter(|&m| m > 0)
+        .ok_or_else(|| anyhow!("Invalid snooze: {}. Examples: 10m, 1h", duration))
+}
+
+/// Fire the reminder again `minutes` from now, returning when
+pub(super) fn snooze(db: &TodoDb, todo: &Todo, minutes: i64) -> Result<BsonDateTime> {
+    let until = BsonDateTime::from_millis(BsonDateTime::now().timestamp_millis() + minutes * 60_000);
+    // A repeating reminder keeps its schedule; only this firing moves
+    if todo.remind_every.is_some() {
+        db.advance_reminder(&todo.id, until)?;
+    } else {
+        db.set_reminder(&todo.id, Some(until))?;
+    }
+    Ok(until)
+}
+
+/// `todo snooze <#> [10m]` / `todo snooze --snooze-all [10m]`: push a reminder (or every
+/// overdue one) back without typing the time again
+pub fn cmd_snooze(db: &TodoDb, args: Vec<String>) -> Result<()> {
+    let all = args.iter().any(|a| a == "--snooze-all" || a == "--all");
+    let args: Vec<String> = args
+        .into_iter()
+        .filter(|a| a != "--snooze-all" && a != "--all")
+        .collect();
+
+    if all {
+        let minutes = parse_snooze(args.first().map_or(DEFAULT_SNOOZE, String::as_str))?;
+        let now = BsonDateTime::now().timestamp_millis();
+        let overdue: Vec<Todo> = db
+            .list_all()?
+            .into_iter()
+            .filter(|t| !t.done && t.remind_at.is_some_and(|r| r.timestamp_millis() <= now))
+            .collect();
+        if overdue.is_empty() {
+            print_info("No overdue reminders to snooze");
+            return Ok(());
+        }
+
+        let mut until = None;
+        for todo in &overdue {
+            until = Some(snooze(db, todo, minutes)?);
+        }
+        if let Some(until) = until {
+            print_success(&format!(
+                "Snoozed {} reminder(s) until {}",
+                overdue.len(),
+                format_remind_at(&until)
+            ));
+        }
+        return Ok(());
+    }
+
+    let Some(index_str) = args.first() else {
+        return Err(anyhow!("Usage: todo snooze <#> [10m]  or  todo snooze --snooze-all [10m]"));
+    };
+    let (index, todo) = get_todo_by_index(db, index_str)?;
+    if todo.done {
+        return Err(anyhow!("Todo #{} is already done", index));
+    }
+    if todo.remind_at.is_none() {
+        return Err(anyhow!(
+            "Todo #{} has no reminder. Set one with: todo remind {} 10m",
+            index,
+            index
+        ));
+    }
+
+    let minutes = parse_snooze(args.get(1).map_or(DEFAULT_SNOOZE, String::as_str))?;
+    let until = snooze(db, &todo, minutes)?;
+    print_success(&format!(
+        "Snoozed #{}: {} ({})",
+        index,
+        todo.title,
+        format_remind_at(&until)
+    ));
+    Ok(())
+}
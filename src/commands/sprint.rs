@@ -0,0 +1,117 @@
+use anyhow::{anyhow, Result};
+use chrono::{Datelike, Duration, Local, NaiveDate, Weekday};
+
+use super::get_todo_by_index;
+use crate::db::TodoDb;
+use crate::display::{print_info, print_success, print_todo_rows, print_warning};
+
+/// Monday of an ISO week written like "2025-W29", or `this` / `next` / `last`
+fn parse_week(input: &str) -> Result<NaiveDate> {
+    let monday = |d: NaiveDate| d - Duration::days(d.weekday().num_days_from_monday() as i64);
+    let today = Local::now().date_naive();
+    match input.to_lowercase().as_str() {
+        "this" => Ok(monday(today)),
+        "next" => Ok(monday(today) + Duration::weeks(1)),
+        "last" => Ok(monday(today) - Duration::weeks(1)),
+        other => other
+            .split_once("-w")
+            .and_then(|(year, week)| {
+                NaiveDate::from_isoywd_opt(year.parse().ok()?, week.parse().ok()?, Weekday::Mon)
+            })
+            .ok_or_else(|| {
+                anyhow!("Invalid week: {}. Use YYYY-Www (e.g. 2025-W29), this, next or last", input)
+            }),
+    }
+}
+
+/// "2025-W29" for the ISO week containing `day`
+fn week_label(day: NaiveDate) -> String {
+    let week = day.iso_week();
+    format!("{}-W{:02}", week.year(), week.week())
+}
+
+/// `todo sprint assign <#> <week>` / `show [week]` / `carry [week]`: plan todos into
+/// ISO-week sprints and catch what slipped
+pub fn cmd_sprint(db: &TodoDb, args: Vec<String>) -> Result<()> {
+    match args.first().map(String::as_str) {
+        Some("assign") => {
+            if args.len() < 3 {
+                return Err(anyhow!("Usage: todo sprint assign <#> <YYYY-Www|this|next|clear>"));
+            }
+            let (index, todo) = get_todo_by_index(db, &args[1])?;
+            if matches!(args[2].as_str(), "clear" | "none") {
+                db.set_sprint(&todo.id, None)?;
+                print_success(&format!("Took #{} out of its sprint: {}", index, todo.title));
+                return Ok(());
+            }
+            let week = week_label(parse_week(&args[2])?);
+            db.set_sprint(&todo.id, Some(&week))?;
+            print_success(&format!("Planned #{} for {}: {}", index, week, todo.title));
+            Ok(())
+        }
+        Some("show") | None => {
+            let monday = parse_week(args.get(1).map(String::as_str).unwrap_or("this"))?;
+            show_sprint(db, monday)
+        }
+        Some("carry") => {
+            let monday = parse_week(args.get(1).map(String::as_str).unwrap_or("this"))?;
+            let (week, previous) = (week_label(monday), week_label(monday - Duration::weeks(1)));
+            let todos = db.list_all()?;
+            let slipped: Vec<_> = todos
+                .iter()
+                .filter(|t| !t.done && t.sprint.as_deref() == Some(previous.as_str()))
+                .collect();
+            for todo in &slipped {
+                db.set_sprint(&todo.id, Some(&week))?;
+            }
+            print_success(&format!(
+                "Carried {} item(s) from {} into {}",
+                slipped.len(),
+                previous,
+                week
+            ));
+            Ok(())
+        }
+        Some(other) => Err(anyhow!(
+            "Unknown sprint command '{}'. Use: todo sprint assign|show|carry",
+            other
+        )),
+    }
+}
+
+/// A week's sprint with progress, plus a warning for pending work left in the week before
+fn show_sprint(db: &TodoDb, monday: NaiveDate) -> Result<()> {
+    let week = week_label(monday);
+    let previous = week_label(monday - Duration::weeks(1));
+    let todos = db.list_all()?;
+
+    let rows: Vec<usize> = (0..todos.len())
+        .filter(|&i| todos[i].sprint.as_deref() == Some(week.as_str()))
+        .collect();
+    let slipped = todos
+        .iter()
+        .filter(|t| !t.done && t.sprint.as_deref() == Some(previous.as_str()))
+        .count();
+
+    let sunday = monday + Duration::days(6);
+    print_info(&format!(
+        "Sprint {} ({} – {})",
+        week,
+        monday.format("%b %-d"),
+        sunday.format("%b %-d")
+    ));
+    if rows.is_empty() {
+        print_info(&format!("Nothing planned. Add todos with: todo sprint assign <#> {}", week));
+    } else {
+        print_todo_rows(&todos, &rows);
+        let done = rows.iter().filter(|&&i| todos[i].done).count();
+        print_info(&format!("{}/{} done", done, rows.len()));
+    }
+    if slipped > 0 {
+        print_warning(&format!(
+            "{} item(s) slipped from last week ({}). Bring them along with: todo sprint carry",
+            slipped, previous
+        ));
+    }
+    Ok(())
+}
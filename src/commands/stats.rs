@@ -0,0 +1,167 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use anyhow::Result;
+use chrono::{Datelike, Duration, Local, NaiveDate};
+
+use crate::db::TodoDb;
+use crate::display::{print_cycle_times, print_info, print_productivity};
+use crate::models::{HistoryKind, Todo};
+use crate::remind::to_local;
+
+/// Days and weeks charted by `todo stats`
+const CHART_DAYS: i64 = 14;
+const CHART_WEEKS: i64 = 8;
+/// Tags and projects listed as the busiest
+const TOP_GROUPS: usize = 5;
+
+/// Median and p90 (nearest-rank) of a sorted, non-empty list
+fn median_p90(sorted: &[i64]) -> (i64, i64) {
+    let n = sorted.len();
+    let median = if n.is_multiple_of(2) {
+        (sorted[n / 2 - 1] + sorted[n / 2]) / 2
+    } else {
+        sorted[n / 2]
+    };
+    let p90 = sorted[(n * 9).div_ceil(10) - 1];
+    (median, p90)
+}
+
+/// Minutes from creation to completion, for todos completed since `done_at` was recorded
+fn cycle_minutes(todo: &Todo) -> Option<i64> {
+    let done_at = todo.done_at.filter(|_| todo.done)?;
+    Some(((done_at.timestamp_millis() - todo.created_at.timestamp_millis()) / 60_000).max(0))
+}
+
+/// Local days on which `todo` was completed: its `done_at`, plus each finished occurrence
+/// if it recurs
+pub(super) fn completion_days(todo: &Todo) -> Vec<NaiveDate> {
+    let done = todo.done_at.filter(|_| todo.done);
+    let occurrences = todo
+        .history
+        .iter()
+        .filter(|h| matches!(h.kind, HistoryKind::Completed))
+        .map(|h| h.at);
+    done.into_iter()
+        .chain(occurrences)
+        .filter_map(|at| Some(to_local(&at)?.date_naive()))
+        .collect()
+}
+
+/// Counts of the `n` most common names, most first
+fn busiest(counts: HashMap<String, usize>, n: usize) -> Vec<(String, usize)> {
+    let mut counts: Vec<(String, usize)> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts.truncate(n);
+    counts
+}
+
+/// `todo stats`: completions per day and week as a chart, average completion time, the
+/// current streak and the busiest tags and projects
+fn productivity(todos: &[Todo]) {
+    let today = Local::now().date_naive();
+    let mut per_day: HashMap<NaiveDate, usize> = HashMap::new();
+    let mut tags: HashMap<String, usize> = HashMap::new();
+    let mut projects: HashMap<String, usize> = HashMap::new();
+    for todo in todos {
+        let days = completion_days(todo);
+        for &day in &days {
+            *per_day.entry(day).or_default() += 1;
+        }
+        for tag in &todo.tags {
+            *tags.entry(tag.clone()).or_default() += days.len();
+        }
+        if let Some(ref project) = todo.project {
+            *projects.entry(project.clone()).or_default() += days.len();
+        }
+    }
+    tags.retain(|_, n| *n > 0);
+    projects.retain(|_, n| *n > 0);
+
+    let count_on = |day: NaiveDate| per_day.get(&day).copied().unwrap_or(0);
+    let days: Vec<(NaiveDate, usize)> = (0..CHART_DAYS)
+        .rev()
+        .map(|back| today - Duration::days(back))
+        .map(|day| (day, count_on(day)))
+        .collect();
+    let this_monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+    let weeks: Vec<(NaiveDate, usize)> = (0..CHART_WEEKS)
+        .rev()
+        .map(|back| this_monday - Duration::weeks(back))
+        .map(|monday| (monday, (0..7).map(|d| count_on(monday + Duration::days(d))).sum()))
+        .collect();
+
+    // A streak still counts if nothing's been finished yet today
+    let mut day = if count_on(today) > 0 { today } else { today - Duration::days(1) };
+    let mut streak = 0;
+    while count_on(day) > 0 {
+        streak += 1;
+        day -= Duration::days(1);
+    }
+
+    let cycles: Vec<i64> = todos.iter().filter_map(cycle_minutes).collect();
+    let average = (!cycles.is_empty()).then(|| cycles.iter().sum::<i64>() / cycles.len() as i64);
+
+    print_productivity(
+        &days,
+        &weeks,
+        average,
+        streak,
+        &busiest(tags, TOP_GROUPS),
+        &busiest(projects, TOP_GROUPS),
+    );
+}
+
+/// `todo stats`: a productivity report. `todo stats --cycle-time`: how long todos take
+/// from creation to completion, overall and per tag / linked repository
+pub fn cmd_stats(db: &TodoDb, args: Vec<String>) -> Result<()> {
+    let todos = db.list_all()?;
+    if !args.iter().any(|a| a == "--cycle-time") {
+        productivity(&todos);
+        return Ok(());
+    }
+
+    let mut groups: BTreeMap<String, Vec<i64>> = BTreeMap::new();
+    let mut overall = Vec::new();
+
+    for todo in &todos {
+        let Some(minutes) = cycle_minutes(todo) else {
+            continue;
+        };
+        overall.push(minutes);
+        for tag in &todo.tags {
+            groups.entry(format!("tag:{}", tag)).or_default().push(minutes);
+        }
+        if let Some(ref link) = todo.git {
+            let name = Path::new(&link.repo)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| link.repo.clone());
+            groups.entry(format!("repo:{}", name)).or_default().push(minutes);
+        }
+    }
+
+    if overall.is_empty() {
+        print_info("No completed todos with a completion time yet.");
+        return Ok(());
+    }
+
+    overall.sort_unstable();
+    let (median, p90) = median_p90(&overall);
+    let mut rows = vec![("all".to_string(), overall.len(), median, p90)];
+
+    let mut grouped: Vec<_> = groups
+        .into_iter()
+        .map(|(label, mut minutes)| {
+            minutes.sort_unstable();
+            let (median, p90) = median_p90(&minutes);
+            (label, minutes.len(), median, p90)
+        })
+        .collect();
+    // Slowest kinds of work first
+    grouped.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(&b.0)));
+    rows.extend(grouped);
+
+    print_cycle_times(&rows);
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use anyhow::{anyhow, Result};
+use polodb_core::bson::DateTime as BsonDateTime;
+
+use super::archive::move_selected;
+use crate::config::Config;
+use crate::db::TodoDb;
+use crate::display::{print_info, print_success, print_trash, prompt};
+use crate::models::Todo;
+
+/// Delete for good whatever has been in the trash longer than `trash_days`
+pub(super) fn expire_trash(db: &TodoDb, config: &Config) -> Result<()> {
+    if config.trash_days == 0 || config.read_only {
+        return Ok(());
+    }
+    let cutoff = BsonDateTime::now().timestamp_millis() - config.trash_days as i64 * 86_400_000;
+    let expired: Vec<_> = db
+        .list_trash()?
+        .iter()
+        .filter(|e| e.deleted_at.timestamp_millis() < cutoff)
+        .map(|e| e.id)
+        .collect();
+    if !expired.is_empty() {
+        db.remove_from_trash(&expired)?;
+    }
+    Ok(())
+}
+
+/// `todo trash [empty [--yes]]`: the todos `rm`, `clear` and friends deleted, newest first
+/// and numbered for `todo restore --trash`; `empty` deletes them for good
+pub fn cmd_trash(db: &TodoDb, config: &Config, args: Vec<String>) -> Result<()> {
+    expire_trash(db, config)?;
+    let entries = db.list_trash()?;
+    match args.first().map(String::as_str) {
+        None => print_trash(&entries, (config.trash_days > 0).then_some(config.trash_days)),
+        Some("empty") => {
+            if entries.is_empty() {
+                print_info("The trash is empty");
+                return Ok(());
+            }
+            let skip_confirm = args.iter().any(|a| a == "--yes" || a == "-y");
+            if !skip_confirm {
+                let question =
+                    format!("Delete the {} todo(s) in the trash for good? [y/N]", entries.len());
+                let answer = prompt(&question)?;
+                if !matches!(answer.as_deref(), Some("y" | "yes")) {
+                    print_info("Aborted, the trash is as it was");
+                    return Ok(());
+                }
+            }
+            let ids: Vec<_> = entries.iter().map(|e| e.id).collect();
+            let count = db.remove_from_trash(&ids)?;
+            print_success(&format!("Emptied the trash ({} todo(s) deleted for good)", count));
+        }
+        Some(_) => return Err(anyhow!("Usage: todo trash  or  todo trash empty [--yes]")),
+    }
+    Ok(())
+}
+
+/// `todo restore --trash <#>|<range>|<filter>...`: bring deleted todos back, numbered as in
+/// `todo trash`
+pub(super) fn restore_trashed(db: &TodoDb, config: &Config, args: &[String]) -> Result<()> {
+    expire_trash(db, config)?;
+    let entries = db.list_trash()?;
+    if entries.is_empty() {
+        print_info("The trash is empty");
+        return Ok(());
+    }
+    let todos: Vec<Todo> = entries.iter().map(|e| e.todo.clone()).collect();
+    let usage = "todo restore --trash <#>  (numbers from todo trash)";
+    move_selected(&todos, args, usage, "Restored", |selected| {
+        let chosen: Vec<_> = entries
+            .iter()
+            .filter(|e| selected.iter().any(|t| t.id == e.id))
+            .cloned()
+            .collect();
+        db.untrash_many(&chosen)
+    })
+}
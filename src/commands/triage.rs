@@ -0,0 +1,128 @@
+use anyhow::Result;
+
+use crate::db::TodoDb;
+use crate::display::{print_info, print_success, print_warning, prompt};
+use crate::filter::{parse_effort, parse_priority};
+use crate::remind::{format_remind_at, parse_reminder};
+
+/// `todo triage`: step through inbox items, filing each with tags, a project, priority,
+/// a due date and effort
+pub fn cmd_triage(db: &TodoDb) -> Result<()> {
+    let todos = db.list_all()?;
+    let inbox: Vec<(usize, _)> = todos
+        .iter()
+        .enumerate()
+        .filter(|(_, t)| t.inbox && !t.done)
+        .map(|(i, t)| (i + 1, t.clone()))
+        .collect();
+
+    if inbox.is_empty() {
+        print_success("Inbox zero. Capture something with: todo in \"random thought\"");
+        return Ok(());
+    }
+
+    print_info(&format!(
+        "{} item(s) to triage. Press Enter to skip a question.",
+        inbox.len()
+    ));
+    let mut filed = 0;
+    let mut deleted = 0;
+
+    'todos: for (index, todo) in &inbox {
+        println!();
+        print_info(&format!("#{} {}", index, todo.title));
+
+        let Some(answer) = prompt("Tags (e.g. +work +home), [x] delete, [q]uit >")? else {
+            break;
+        };
+        match answer.as_str() {
+            "q" | "quit" => break,
+            "x" | "delete" => {
+                db.trash_many(std::slice::from_ref(todo))?;
+                print_success("Moved to the trash");
+                deleted += 1;
+                continue;
+            }
+            "" => {}
+            tags => {
+                let mut merged = todo.tags.clone();
+                for tag in tags.split_whitespace().map(|t| t.trim_start_matches('+')) {
+                    if !tag.is_empty() && !merged.iter().any(|t| t == tag) {
+                        merged.push(tag.to_string());
+                    }
+                }
+                db.set_tags(&todo.id, &merged)?;
+            }
+        }
+
+        let Some(project) = prompt("Project? (e.g. work) >")? else {
+            break;
+        };
+        let project = project.trim_start_matches('@');
+        if !project.is_empty() {
+            db.set_project(&todo.id, Some(project))?;
+        }
+
+        loop {
+            let Some(priority) = prompt("Priority? (low/medium/high/critical) >")? else {
+                break 'todos;
+            };
+            if priority.is_empty() {
+                break;
+            }
+            match parse_priority(&priority) {
+                Ok(priority) => {
+                    db.set_priority(&todo.id, Some(priority))?;
+                    break;
+                }
+                Err(e) => print_warning(&format!("{:#}", e)),
+            }
+        }
+
+        loop {
+            let Some(due) = prompt("Due? (e.g. friday, 2d, 2025-07-10) >")? else {
+                break 'todos;
+            };
+            if due.is_empty() {
+                break;
+            }
+            match parse_reminder(&due) {
+                Ok(due_at) => {
+                    db.set_due(&todo.id, Some(due_at))?;
+                    print_info(&format!("  Due {}", format_remind_at(&due_at)));
+                    break;
+                }
+                Err(e) => print_warning(&format!("{:#}", e)),
+            }
+        }
+
+        loop {
+            let Some(effort) = prompt("Effort? (low/medium/high) >")? else {
+                break 'todos;
+            };
+            if effort.is_empty() {
+                break;
+            }
+            match parse_effort(&effort) {
+                Ok(effort) => {
+                    db.set_effort(&todo.id, Some(effort))?;
+                    break;
+                }
+                Err(e) => print_warning(&format!("{:#}", e)),
+            }
+        }
+
+        db.set_inbox(&todo.id, false)?;
+        print_success("Filed");
+        filed += 1;
+    }
+
+    println!();
+    print_info(&format!(
+        "Triage done: {} filed · {} deleted · {} left in inbox",
+        filed,
+        deleted,
+        inbox.len() - filed - deleted
+    ));
+    Ok(())
+}
@@ -0,0 +1,326 @@
+use std::io::{self, IsTerminal};
+
+use anyhow::{anyhow, Result};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+
+use super::{complete_finished_parents, recur};
+use crate::config::Config;
+use crate::db::TodoDb;
+use crate::filter::{tree_order, Filter};
+use crate::models::{HistoryKind, Todo};
+use crate::remind::{format_remind_at, is_repeating, parse_reminder, parse_repeating};
+
+/// What the bottom line is asking for while the user types
+#[derive(Clone, Copy)]
+enum Prompt {
+    Edit,
+    Remind,
+    Filter,
+}
+
+impl Prompt {
+    fn label(self) -> &'static str {
+        match self {
+            Prompt::Edit => "Title: ",
+            Prompt::Remind => "Remind (15m, tomorrow, 2025-09-01 14:30, every day 9am, clear): ",
+            Prompt::Filter => "Filter (+work @home status:pending): ",
+        }
+    }
+}
+
+struct App<'a> {
+    db: &'a TodoDb,
+    config: &'a Config,
+    todos: Vec<Todo>,
+    /// Indexes into `todos` of the visible rows, in display order
+    rows: Vec<usize>,
+    list: ListState,
+    filter: String,
+    input: Option<(Prompt, String)>,
+    /// Result of the last action, and whether it failed
+    status: Option<(String, bool)>,
+}
+
+impl<'a> App<'a> {
+    fn new(db: &'a TodoDb, config: &'a Config) -> Result<Self> {
+        let mut app = Self {
+            db,
+            config,
+            todos: Vec::new(),
+            rows: Vec::new(),
+            list: ListState::default(),
+            filter: String::new(),
+            input: None,
+            status: None,
+        };
+        app.reload()?;
+        app.list.select(if app.rows.is_empty() { None } else { Some(0) });
+        Ok(app)
+    }
+
+    /// Re-read the list, keeping the cursor on the same row number
+    fn reload(&mut self) -> Result<()> {
+        let terms: Vec<String> = self.filter.split_whitespace().map(String::from).collect();
+        let filter = Filter::parse(&terms)?;
+        self.todos = self.db.list_all()?;
+        self.rows = (0..self.todos.len())
+            .filter(|&i| filter.matches(&self.todos[i]))
+            .collect();
+        tree_order(&self.todos, &mut self.rows);
+
+        let selected = match self.list.selected() {
+            _ if self.rows.is_empty() => None,
+            Some(row) => Some(row.min(self.rows.len() - 1)),
+            None => Some(0),
+        };
+        self.list.select(selected);
+        Ok(())
+    }
+
+    fn selected(&self) -> Option<(usize, &Todo)> {
+        let i = *self.rows.get(self.list.selected()?)?;
+        Some((i + 1, &self.todos[i]))
+    }
+
+    fn set_status(&mut self, result: Result<String>) {
+        self.status = Some(match result {
+            Ok(message) => (message, false),
+            Err(e) => (format!("{:#}", e), true),
+        });
+    }
+
+    /// Handle a key in normal mode; returns false to quit
+    fn on_key(&mut self, code: KeyCode) -> bool {
+        match code {
+            KeyCode::Char('q') => return false,
+            KeyCode::Esc if self.filter.is_empty() => return false,
+            KeyCode::Esc => {
+                self.filter.clear();
+                let result = self.reload().map(|_| "Filter cleared".to_string());
+                self.set_status(result);
+            }
+            KeyCode::Down | KeyCode::Char('j') => self.list.select_next(),
+            KeyCode::Up | KeyCode::Char('k') => self.list.select_previous(),
+            KeyCode::Home | KeyCode::Char('g') => self.list.select_first(),
+            KeyCode::End | KeyCode::Char('G') => self.list.select_last(),
+            KeyCode::Char(' ') | KeyCode::Char('x') => {
+                let result = self.toggle_done();
+                self.set_status(result);
+            }
+            KeyCode::Char('e') => {
+                if let Some((_, todo)) = self.selected() {
+                    self.input = Some((Prompt::Edit, todo.title.clone()));
+                }
+            }
+            KeyCode::Char('r') if self.selected().is_some() => {
+                self.input = Some((Prompt::Remind, String::new()));
+            }
+            KeyCode::Char('/') => self.input = Some((Prompt::Filter, self.filter.clone())),
+            _ => {}
+        }
+        true
+    }
+
+    /// Handle a key while typing into the prompt line
+    fn on_input_key(&mut self, code: KeyCode) {
+        let Some((prompt, ref mut text)) = self.input else {
+            return;
+        };
+        match code {
+            KeyCode::Esc => self.input = None,
+            KeyCode::Backspace => {
+                text.pop();
+            }
+            KeyCode::Char(c) => text.push(c),
+            KeyCode::Enter => {
+                let text = std::mem::take(text);
+                self.input = None;
+                let result = match prompt {
+                    Prompt::Edit => self.edit_title(&text),
+                    Prompt::Remind => self.set_reminder(&text),
+                    Prompt::Filter => {
+                        self.filter = text.trim().to_string();
+                        self.list.select_first();
+                        self.reload()
+                            .map(|_| format!("{} todo(s) match", self.rows.len()))
+                    }
+                };
+                self.set_status(result);
+            }
+            _ => {}
+        }
+    }
+
+    /// Same rules as `todo done` / `todo undone`, without `--cascade`
+    fn toggle_done(&mut self) -> Result<String> {
+        let (index, todo) = self.selected().ok_or_else(|| anyhow!("Nothing selected"))?;
+        let todo = todo.clone();
+
+        let message = if todo.done {
+            self.db.mark_undone(&todo.id)?;
+            format!("Marked #{} as pending", index)
+        } else if todo.descendants(&self.todos).iter().any(|t| !t.done) {
+            return Err(anyhow!(
+                "#{} has pending subtasks. Finish them first, or run: todo done {} --cascade",
+                index,
+                index
+            ));
+        } else if todo.recur.is_some() {
+            let next = recur::advance(self.db, &todo, HistoryKind::Completed)?;
+            format!("Completed this occurrence of #{}, next due {}", index, format_remind_at(&next))
+        } else {
+            self.db.mark_done(&todo.id)?;
+            let mut message = format!("Marked #{} as done", index);
+            if self.config.auto_complete_parents {
+                for title in complete_finished_parents(self.db, &todo)? {
+                    message.push_str(&format!(" · completed parent: {}", title));
+                }
+            }
+            message
+        };
+
+        self.reload()?;
+        Ok(message)
+    }
+
+    fn edit_title(&mut self, text: &str) -> Result<String> {
+        let (index, todo) = self.selected().ok_or_else(|| anyhow!("Nothing selected"))?;
+        let id = todo.id;
+        let title = text.trim();
+        if title.is_empty() {
+            return Err(anyhow!("New title cannot be empty"));
+        }
+        self.db.update_title(&id, title)?;
+        self.reload()?;
+        Ok(format!("Updated #{}", index))
+    }
+
+    /// Same inputs as `todo remind <#> ...`
+    fn set_reminder(&mut self, text: &str) -> Result<String> {
+        let (index, todo) = self.selected().ok_or_else(|| anyhow!("Nothing selected"))?;
+        let id = todo.id;
+        let text = text.trim();
+
+        let message = if matches!(text, "clear" | "off" | "none") {
+            self.db.clear_reminder(&id)?;
+            format!("Cleared reminder for #{}", index)
+        } else if is_repeating(text) {
+            let rule = text.to_lowercase();
+            let (remind_at, _) = parse_repeating(&rule)?;
+            self.db.set_repeating_reminder(&id, remind_at, &rule)?;
+            format!("Reminding #{} {} (next {})", index, rule, format_remind_at(&remind_at))
+        } else {
+            let remind_at = parse_reminder(text)?;
+            self.db.set_reminder(&id, Some(remind_at))?;
+            format!("Reminding #{} {}", index, format_remind_at(&remind_at))
+        };
+
+        self.reload()?;
+        Ok(message)
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [list_area, status_area, help_area] = Layout::vertical([
+            Constraint::Min(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        let items: Vec<ListItem> = self.rows.iter().map(|&i| self.row(i)).collect();
+        let title = if self.filter.is_empty() {
+            format!(" Todos ({}) ", self.rows.len())
+        } else {
+            format!(" Todos ({}) · {} ", self.rows.len(), self.filter)
+        };
+        let list = List::new(items)
+            .block(Block::bordered().title(title))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, list_area, &mut self.list);
+
+        let status = match (&self.input, &self.status) {
+            (Some((prompt, text)), _) => {
+                let line = Line::from(vec![Span::raw(prompt.label()).bold(), Span::raw(text.as_str())]);
+                frame.set_cursor_position((
+                    status_area.x + (prompt.label().chars().count() + text.chars().count()) as u16,
+                    status_area.y,
+                ));
+                line
+            }
+            (None, Some((message, true))) => Line::from(message.as_str()).red(),
+            (None, Some((message, false))) => Line::from(message.as_str()).green(),
+            (None, None) => Line::default(),
+        };
+        frame.render_widget(Paragraph::new(status), status_area);
+
+        let help = "j/k move · space done · e edit · r remind · / filter · esc clear · q quit";
+        frame.render_widget(Paragraph::new(help).dark_gray(), help_area);
+    }
+
+    /// One list line: number, checkbox, indented title, then tags and dates
+    fn row(&self, i: usize) -> ListItem<'static> {
+        let todo = &self.todos[i];
+        let mut depth = 0;
+        let mut parent = todo.parent_id;
+        while let Some(id) = parent {
+            depth += 1;
+            parent = self.todos.iter().find(|t| t.id == id).and_then(|t| t.parent_id);
+        }
+
+        let checkbox = if todo.done { "[x]" } else { "[ ]" };
+        let mut spans = vec![
+            Span::raw(format!("{:>3} ", i + 1)).cyan(),
+            Span::raw(format!("{} {}{}", checkbox, "  ".repeat(depth), todo.title)),
+        ];
+        for tag in &todo.tags {
+            spans.push(Span::raw(format!(" +{}", tag)).yellow());
+        }
+        if let Some(ref due) = todo.due_at {
+            spans.push(Span::raw(format!("  due {}", format_remind_at(due))).blue());
+        }
+        if let Some(ref remind_at) = todo.remind_at
+            && !todo.notified
+        {
+            spans.push(Span::raw(format!("  ⏰ {}", format_remind_at(remind_at))).magenta());
+        }
+
+        let line = Line::from(spans);
+        ListItem::new(if todo.done { line.fg(Color::DarkGray) } else { line })
+    }
+}
+
+fn run(terminal: &mut DefaultTerminal, app: &mut App) -> Result<()> {
+    loop {
+        terminal.draw(|frame| app.draw(frame))?;
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        if app.input.is_some() {
+            app.on_input_key(key.code);
+        } else if !app.on_key(key.code) {
+            return Ok(());
+        }
+    }
+}
+
+/// `todo tui`: full-screen list with keyboard navigation, done toggling, inline title
+/// editing, filtering and reminders
+pub fn cmd_tui(db: &TodoDb, config: &Config) -> Result<()> {
+    if !io::stdout().is_terminal() {
+        return Err(anyhow!("todo tui needs an interactive terminal"));
+    }
+
+    let mut app = App::new(db, config)?;
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal, &mut app);
+    ratatui::restore();
+    result
+}
@@ -0,0 +1,226 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use polodb_core::bson::{self, oid::ObjectId, DateTime as BsonDateTime, Document};
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::db::{db_path, TodoDb};
+use crate::display::{json_output, print_info, print_success, print_warning, prompt};
+use crate::models::{JournalEntry, Todo};
+use crate::remind::format_remind_at;
+
+/// What a journaled command is about to do: written before it changes anything and removed
+/// once it returns, so one cut off by a crash or power loss is noticed on the next run
+#[derive(Serialize, Deserialize)]
+struct Intent {
+    command: String,
+    started_at: BsonDateTime,
+    /// The list as it was, covering whichever todos the command ends up touching
+    before: Vec<Todo>,
+}
+
+/// Next to the database, so separate databases (`--db`) each get their own
+fn intent_path(config: &Config) -> Result<PathBuf> {
+    Ok(db_path(config)?.with_extension("interrupted.bson"))
+}
+
+/// Commands `todo undo` can take back
+pub fn is_undoable(cmd: &str) -> bool {
+    matches!(
+        cmd,
+        "add" | "a" | "in" | "quick" | "q" | "sub" | "done" | "d" | "undone" | "u" | "edit" | "e" | "note" | "rm"
+            | "remove" | "r" | "x" | "clear" | "apply"
+            | "merge" | "sync" | "dedupe"
+    )
+}
+
+fn documents(todos: &[Todo]) -> Result<HashMap<ObjectId, Document>> {
+    todos
+        .iter()
+        .map(|t| Ok((t.id, bson::to_document(t).context("failed to encode todo")?)))
+        .collect()
+}
+
+/// The todos that differ between two snapshots of the list, as they were in each
+fn changed(before: Vec<Todo>, after: Vec<Todo>) -> Result<(Vec<Todo>, Vec<Todo>)> {
+    let old = documents(&before)?;
+    let new = documents(&after)?;
+    let touched: HashSet<ObjectId> = old
+        .keys()
+        .chain(new.keys())
+        .filter(|id| old.get(id) != new.get(id))
+        .copied()
+        .collect();
+
+    let keep = |todos: Vec<Todo>| todos.into_iter().filter(|t| touched.contains(&t.id)).collect();
+    Ok((keep(before), keep(after)))
+}
+
+/// Journal what a command changed, given the list as it was beforehand. Runs whether or
+/// not the command succeeded, since a failing bulk command may have changed some todos.
+pub fn record_undo(db: &TodoDb, config: &Config, command: &str, before: Vec<Todo>) -> Result<()> {
+    let (before, after) = changed(before, db.list_all()?)?;
+    if before.is_empty() && after.is_empty() {
+        return Ok(());
+    }
+    db.record_journal(command, before, after, config.undo_levels)
+}
+
+/// Record that `command` is about to run against `before`. The file is synced and renamed
+/// into place, so it's either there in full or not at all.
+pub fn begin_intent(config: &Config, command: &str, before: &[Todo]) -> Result<()> {
+    let intent = Intent {
+        command: command.to_string(),
+        started_at: BsonDateTime::now(),
+        before: before.to_vec(),
+    };
+    let bytes = bson::to_vec(&intent).context("failed to encode the command journal")?;
+    write_synced(&intent_path(config)?, &bytes)
+}
+
+/// Write `bytes` to a side file, sync it and rename it over `path`
+pub(super) fn write_synced(path: &Path, bytes: &[u8]) -> Result<()> {
+    let partial = path.with_extension("partial");
+    let mut file = File::create(&partial)
+        .with_context(|| format!("failed to write {}", partial.display()))?;
+    file.write_all(bytes)
+        .and_then(|_| file.sync_all())
+        .with_context(|| format!("failed to write {}", partial.display()))?;
+    fs::rename(&partial, path).with_context(|| format!("failed to write {}", path.display()))
+}
+
+/// The command returned (successfully or not), so there's nothing to recover
+pub fn end_intent(config: &Config) -> Result<()> {
+    match fs::remove_file(intent_path(config)?) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => {
+            Err(e).context("failed to clear the command journal")
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Check for a command that was cut off last time. If it got as far as changing todos,
+/// offer to roll them back; kept changes go to the undo journal instead.
+pub fn recover_interrupted(db: &TodoDb, config: &Config) -> Result<()> {
+    let path = intent_path(config)?;
+    let Ok(bytes) = fs::read(&path) else {
+        return Ok(());
+    };
+    let intent: Intent = bson::from_slice(&bytes)
+        .with_context(|| format!("failed to read {}; delete it to carry on", path.display()))?;
+    let (before, after) = changed(intent.before, db.list_all()?)?;
+
+    if before.is_empty() && after.is_empty() {
+        print_info(&format!(
+            "`todo {}` was interrupted before it changed anything",
+            intent.command
+        ));
+        return end_intent(config);
+    }
+
+    print_warning(&format!(
+        "`todo {}` was interrupted ({}) after changing {} todo(s)",
+        intent.command,
+        format_remind_at(&intent.started_at),
+        before.len().max(after.len())
+    ));
+    if config.read_only || json_output() || !io::stdin().is_terminal() {
+        print_info("Run todo in a terminal without --read-only or --json to roll back or keep them");
+        return Ok(());
+    }
+
+    let answer = prompt("Roll those changes back? [y/N]")?;
+    if matches!(answer.as_deref(), Some("y" | "yes")) {
+        let ids: Vec<ObjectId> = before.iter().chain(&after).map(|t| t.id).collect();
+        db.restore_todos(&ids, &before)?;
+        print_success(&format!("Rolled back `todo {}`", intent.command));
+    } else {
+        if config.undo_levels > 0 {
+            db.record_journal(&intent.command, before, after, config.undo_levels)?;
+        }
+        print_info("Kept the changes; todo undo takes them back later");
+    }
+    end_intent(config)
+}
+
+/// Every todo an entry touched
+fn touched_ids(entry: &JournalEntry) -> Vec<ObjectId> {
+    let mut ids: Vec<ObjectId> = entry.before.iter().chain(&entry.after).map(|t| t.id).collect();
+    ids.sort();
+    ids.dedup();
+    ids
+}
+
+/// Put the todos an entry touched back to `state`, warning if they've changed since
+/// `expected` (those later changes are lost)
+fn restore(db: &TodoDb, entry: &JournalEntry, expected: &[Todo], state: &[Todo]) -> Result<()> {
+    let ids = touched_ids(entry);
+    let current: Vec<Todo> = db.list_all()?.into_iter().filter(|t| ids.contains(&t.id)).collect();
+    if documents(&current)? != documents(expected)? {
+        print_warning(&format!(
+            "Some todos changed after `todo {}`; those changes are replaced too",
+            entry.command
+        ));
+    }
+    db.restore_todos(&ids, state)
+}
+
+fn parse_steps(args: &[String], usage: &str) -> Result<usize> {
+    match args.first() {
+        Some(n) => n
+            .parse()
+            .ok()
+            .filter(|&n| n > 0)
+            .ok_or_else(|| anyhow!("Usage: {}", usage)),
+        None => Ok(1),
+    }
+}
+
+/// `todo undo [n]`: take back the last n changes to the list (add, rm, done, edit, clear)
+pub fn cmd_undo(db: &TodoDb, args: Vec<String>) -> Result<()> {
+    let steps = parse_steps(&args, "todo undo [n]")?;
+    let entries: Vec<JournalEntry> = db
+        .list_journal()?
+        .into_iter()
+        .rev()
+        .filter(|e| !e.undone)
+        .take(steps)
+        .collect();
+    if entries.is_empty() {
+        print_info("Nothing to undo");
+        return Ok(());
+    }
+
+    for entry in &entries {
+        restore(db, entry, &entry.after, &entry.before)?;
+        db.set_journal_undone(&entry.id, true)?;
+        print_success(&format!("Undid: todo {}", entry.command));
+    }
+    Ok(())
+}
+
+/// `todo redo [n]`: apply again what `todo undo` took back, until something else changes
+pub fn cmd_redo(db: &TodoDb, args: Vec<String>) -> Result<()> {
+    let steps = parse_steps(&args, "todo redo [n]")?;
+    let entries: Vec<JournalEntry> = db
+        .list_journal()?
+        .into_iter()
+        .filter(|e| e.undone)
+        .take(steps)
+        .collect();
+    if entries.is_empty() {
+        print_info("Nothing to redo");
+        return Ok(());
+    }
+
+    for entry in &entries {
+        restore(db, entry, &entry.before, &entry.after)?;
+        db.set_journal_undone(&entry.id, false)?;
+        print_success(&format!("Redid: todo {}", entry.command));
+    }
+    Ok(())
+}
@@ -0,0 +1,551 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use regex::RegexBuilder;
+use serde::Deserialize;
+
+/// The user's home directory: `HOME`, or `USERPROFILE` on Windows
+pub fn home_dir() -> Result<PathBuf> {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .filter(|h| !h.is_empty())
+        .map(PathBuf::from)
+        .context("could not find your home directory (set HOME or USERPROFILE)")
+}
+
+/// `%APPDATA%` on Windows, where per-user config and data belong
+fn windows_app_data() -> Option<PathBuf> {
+    if cfg!(windows) {
+        std::env::var_os("APPDATA").map(PathBuf::from)
+    } else {
+        None
+    }
+}
+
+pub fn config_path() -> Result<PathBuf> {
+    let dir = match windows_app_data() {
+        Some(app_data) => app_data.join("todo"),
+        None => home_dir()?.join(".config").join("todo"),
+    };
+    Ok(dir.join("config.toml"))
+}
+
+/// Default database directory: `~/.local/share/todo`, or `%APPDATA%\todo` on Windows
+pub fn default_data_dir() -> Result<PathBuf> {
+    match windows_app_data() {
+        Some(app_data) => Ok(app_data.join("todo")),
+        None => Ok(home_dir()?.join(".local").join("share").join("todo")),
+    }
+}
+
+/// User settings read from `~/.config/todo/config.toml` (`%APPDATA%\todo\config.toml`
+/// on Windows); every key is optional
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Mark a parent todo as done once all of its subtasks are done
+    pub auto_complete_parents: bool,
+    /// Pending todos older than this many days come up in `todo review`
+    pub review_age_days: i64,
+    /// Directory → tag; `todo list` inside a directory shows only that tag
+    pub dir_tags: BTreeMap<String, String>,
+    /// Shell command for the "push" notification backend; receives
+    /// TODO_TITLE, TODO_MESSAGE and TODO_URGENCY in its environment
+    pub push_command: Option<String>,
+    /// Where todo.db lives; defaults to `default_data_dir()`
+    pub data_dir: Option<String>,
+    /// The database file itself, instead of todo.db in `data_dir` (e.g. a separate work list)
+    pub db_path: Option<String>,
+    /// Named list to use instead of the one picked with `todo use`, e.g. "work"
+    pub list: Option<String>,
+    /// Time of day used when a reminder or due date is given as a bare date
+    pub reminder_time: String,
+    /// Days off for "next business day" and "every business day", e.g. ["sat", "sun"]
+    pub weekend: Vec<String>,
+    /// Backend for first reminders: "desktop" or "push"
+    pub notification_backend: String,
+    /// How desktop notifications are shown: "auto" (by platform), "osascript",
+    /// "notify-send", "dbus" or "toast"
+    pub desktop_notifier: String,
+    /// "auto", "always" or "never"
+    pub color: String,
+    /// Reminder given to new todos added without `-r` (e.g. "1d"); none by default
+    pub default_reminder: Option<String>,
+    /// Default order for `todo list`: "default" (pending first), "created", "title", "due",
+    /// "done" or "urgency"
+    pub sort: String,
+    /// Language for relative times: "en", "es" or "de"
+    pub locale: String,
+    /// "compact" (in 1h 25m) or "verbose" (in 1 hour 25 minutes)
+    pub time_style: String,
+    /// Show an Age column for pending todos in every table (also `todo list --age`)
+    pub age_column: bool,
+    /// Ages at or past this many days are shown in yellow
+    pub age_warn_days: i64,
+    /// Ages at or past this many days are shown in red
+    pub age_alert_days: i64,
+    /// Shell command run by `todo sync`, with `TODO_DB_PATH` set (e.g. an rsync or git push)
+    pub sync_command: Option<String>,
+    /// Folder another service carries between devices (a cloud drive, a mounted share);
+    /// `todo sync` leaves a chunked snapshot there, rewriting only the parts that changed
+    pub sync_dir: Option<String>,
+    /// What `todo sync` leaves in `sync_dir`: "snapshot" (chunks to `todo merge`) or
+    /// "oplog" (a log of changes per device, merged by `todo sync` itself)
+    pub sync_format: String,
+    /// Run `sync_command` in the background after add/done/rm
+    pub auto_sync: bool,
+    /// Seconds to wait after the last change before an automatic sync
+    pub auto_sync_delay: u64,
+    /// Reject every change to the database (also `--read-only` / `TODO_READ_ONLY`)
+    pub read_only: bool,
+    /// Your name on a shared list; defaults to `$USER` / `%USERNAME%`
+    pub user: Option<String>,
+    /// How much `--sort urgency` favors low-effort todos (and penalizes high-effort ones)
+    pub effort_weight: f64,
+    /// Tag → defaults applied by `todo add` to todos with that tag; flags still win
+    pub tag_defaults: BTreeMap<String, TagDefaults>,
+    /// Project → the same defaults, for todos added to that project; tags' defaults win
+    pub project_defaults: BTreeMap<String, TagDefaults>,
+    /// How much estimated work fits in a day for `todo plan`, e.g. "6h"
+    pub daily_capacity: String,
+    /// Title patterns that tag (and prioritize) todos as `todo add` creates them
+    pub rules: Vec<TitleRule>,
+    /// Reminders overdue by more than this when `todo notify` runs are sent as one
+    /// "while you were away" summary, e.g. "15m"
+    pub catch_up_after: String,
+    /// `todo notify` won't notify about the same todo twice within this, e.g. "5m"
+    pub notify_cooldown: String,
+    /// Time of day the digest of reminders for `notify = "digest"` tags goes out
+    pub digest_time: String,
+    /// How many changes `todo undo` can go back; 0 turns the journal off
+    pub undo_levels: usize,
+    /// Days deleted todos stay in the trash; 0 keeps them until `todo trash empty`
+    pub trash_days: u64,
+    /// Squeeze runs of spaces in new titles down to one
+    pub collapse_whitespace: bool,
+    /// "none" or "sentence" (capitalize the first letter of new titles)
+    pub title_case: String,
+    /// Prefix every new title must start with; added when missing
+    pub title_prefix: Option<String>,
+    /// Text expanded in new titles, e.g. ":mtg:" → "Meeting with"
+    pub snippets: BTreeMap<String, String>,
+}
+
+/// Where `todo init` puts a project's own settings, relative to the project directory
+pub const PROJECT_CONFIG: &str = ".todo/config.toml";
+
+/// Optional columns of the list table, for a project's `columns`
+pub const LIST_COLUMNS: &[&str] =
+    &["priority", "project", "assignee", "progress", "due", "remind", "age", "created"];
+
+/// How `todo list` looks inside a project, from the nearest `.todo/config.toml` above the
+/// working directory
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProjectConfig {
+    /// Filter terms used when `todo list` is given none, e.g. "+acme status:pending"
+    pub filter: Option<String>,
+    /// Sort key used when `todo list` is given no `--sort`
+    pub sort: Option<String>,
+    /// Optional columns to show (see `LIST_COLUMNS`); #, ID, Status and Title always are
+    pub columns: Option<Vec<String>>,
+}
+
+impl ProjectConfig {
+    /// The settings of the project `dir` is in, with the file they came from
+    pub fn find(dir: &Path) -> Result<Option<(PathBuf, Self)>> {
+        let Some(path) = dir.ancestors().map(|d| d.join(PROJECT_CONFIG)).find(|p| p.is_file())
+        else {
+            return Ok(None);
+        };
+        let contents = std::fs::read_to_string(&path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let project: Self = toml::from_str(&contents)
+            .with_context(|| format!("failed to parse {}", path.display()))?;
+        for column in project.columns.iter().flatten() {
+            if !LIST_COLUMNS.contains(&column.as_str()) {
+                return Err(anyhow!(
+                    "Unknown column '{}' in {}. Use any of: {}",
+                    column,
+                    path.display(),
+                    LIST_COLUMNS.join(", ")
+                ));
+            }
+        }
+        Ok(Some((path, project)))
+    }
+}
+
+/// Defaults for todos added with a tag, e.g. `[tag_defaults.work] remind = "09:00"`, or
+/// to a project (`[project_defaults.acme]`).
+/// Values use the same syntax as the matching `todo add` flag.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct TagDefaults {
+    pub remind: Option<String>,
+    pub due: Option<String>,
+    pub review: Option<String>,
+    pub effort: Option<String>,
+    pub priority: Option<String>,
+    pub location: Option<String>,
+    pub assign: Option<String>,
+    /// Extra tags to add, e.g. a context like "@out"
+    pub tags: Vec<String>,
+    /// How `todo notify` treats todos with the tag or project, whenever they were added:
+    /// "digest" (only in the daily digest at `digest_time`) or "push" (always to the phone)
+    pub notify: Option<String>,
+}
+
+/// `[[rules]] pattern = "\\bcall\\b" tags = ["phone"]`: a case-insensitive regex
+/// matched against new titles
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct TitleRule {
+    pub pattern: String,
+    pub tags: Vec<String>,
+    pub project: Option<String>,
+    pub priority: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            auto_complete_parents: false,
+            review_age_days: 7,
+            dir_tags: BTreeMap::new(),
+            push_command: None,
+            data_dir: None,
+            db_path: None,
+            list: None,
+            reminder_time: "09:00".to_string(),
+            weekend: vec!["sat".to_string(), "sun".to_string()],
+            notification_backend: "desktop".to_string(),
+            desktop_notifier: "auto".to_string(),
+            color: "auto".to_string(),
+            default_reminder: None,
+            sort: "default".to_string(),
+            locale: "en".to_string(),
+            time_style: "compact".to_string(),
+            age_column: false,
+            age_warn_days: 14,
+            age_alert_days: 30,
+            sync_command: None,
+            sync_dir: None,
+            sync_format: "snapshot".to_string(),
+            auto_sync: false,
+            auto_sync_delay: 10,
+            read_only: false,
+            user: None,
+            effort_weight: 1.0,
+            tag_defaults: BTreeMap::new(),
+            project_defaults: BTreeMap::new(),
+            daily_capacity: "6h".to_string(),
+            rules: Vec::new(),
+            catch_up_after: "15m".to_string(),
+            notify_cooldown: "5m".to_string(),
+            digest_time: "18:00".to_string(),
+            undo_levels: 20,
+            trash_days: 30,
+            collapse_whitespace: false,
+            title_case: "none".to_string(),
+            title_prefix: None,
+            snippets: BTreeMap::new(),
+        }
+    }
+}
+
+impl Config {
+    /// Load the config file (if any), then apply `TODO_*` environment overrides
+    pub fn load() -> Result<Self> {
+        let path = config_path()?;
+        let mut config: Self = if path.exists() {
+            let contents = std::fs::read_to_string(&path)
+                .with_context(|| format!("failed to read {}", path.display()))?;
+            toml::from_str(&contents)
+                .with_context(|| format!("failed to parse {}", path.display()))?
+        } else {
+            Self::default()
+        };
+
+        config.apply_env()?;
+        Ok(config)
+    }
+
+    /// Override settings from `TODO_<KEY>` environment variables (e.g. `TODO_SORT=title`).
+    /// `TODO_NO_COLOR` (any value) is shorthand for `TODO_COLOR=never`.
+    fn apply_env(&mut self) -> Result<()> {
+        if let Some(v) = env_value("TODO_AUTO_COMPLETE_PARENTS") {
+            self.auto_complete_parents = parse_env_bool("TODO_AUTO_COMPLETE_PARENTS", &v)?;
+        }
+        if let Some(v) = env_value("TODO_REVIEW_AGE_DAYS") {
+            self.review_age_days = v
+                .parse()
+                .with_context(|| format!("TODO_REVIEW_AGE_DAYS must be a number, got '{}'", v))?;
+        }
+        if let Some(v) = env_value("TODO_DIR_TAGS") {
+            // Comma-separated `dir=tag` pairs
+            for pair in v.split(',') {
+                let (dir, tag) = pair
+                    .split_once('=')
+                    .with_context(|| format!("TODO_DIR_TAGS entries look like dir=tag, got '{}'", pair))?;
+                self.dir_tags.insert(dir.trim().to_string(), tag.trim().to_string());
+            }
+        }
+        if let Some(v) = env_value("TODO_PUSH_COMMAND") {
+            self.push_command = Some(v);
+        }
+        if let Some(v) = env_value("TODO_DATA_DIR") {
+            self.data_dir = Some(v);
+        }
+        if let Some(v) = env_value("TODO_DB_PATH") {
+            self.db_path = Some(v);
+        }
+        if let Some(v) = env_value("TODO_LIST") {
+            self.list = Some(v);
+        }
+        if let Some(v) = env_value("TODO_REMINDER_TIME") {
+            self.reminder_time = v;
+        }
+        if let Some(v) = env_value("TODO_WEEKEND") {
+            self.weekend = v.split(',').map(|d| d.trim().to_string()).collect();
+        }
+        if let Some(v) = env_value("TODO_NOTIFICATION_BACKEND") {
+            self.notification_backend = v;
+        }
+        if let Some(v) = env_value("TODO_DESKTOP_NOTIFIER") {
+            self.desktop_notifier = v;
+        }
+        if let Some(v) = env_value("TODO_COLOR") {
+            self.color = v;
+        }
+        if env_value("TODO_NO_COLOR").is_some() {
+            self.color = "never".to_string();
+        }
+        if let Some(v) = env_value("TODO_DEFAULT_REMINDER") {
+            self.default_reminder = Some(v);
+        }
+        if let Some(v) = env_value("TODO_SORT") {
+            self.sort = v;
+        }
+        if let Some(v) = env_value("TODO_LOCALE") {
+            self.locale = v;
+        }
+        if let Some(v) = env_value("TODO_TIME_STYLE") {
+            self.time_style = v;
+        }
+        if let Some(v) = env_value("TODO_AGE_COLUMN") {
+            self.age_column = parse_env_bool("TODO_AGE_COLUMN", &v)?;
+        }
+        if let Some(v) = env_value("TODO_AGE_WARN_DAYS") {
+            self.age_warn_days = v
+                .parse()
+                .with_context(|| format!("TODO_AGE_WARN_DAYS must be a number, got '{}'", v))?;
+        }
+        if let Some(v) = env_value("TODO_AGE_ALERT_DAYS") {
+            self.age_alert_days = v
+                .parse()
+                .with_context(|| format!("TODO_AGE_ALERT_DAYS must be a number, got '{}'", v))?;
+        }
+        if let Some(v) = env_value("TODO_SYNC_COMMAND") {
+            self.sync_command = Some(v);
+        }
+        if let Some(v) = env_value("TODO_SYNC_DIR") {
+            self.sync_dir = Some(v);
+        }
+        if let Some(v) = env_value("TODO_SYNC_FORMAT") {
+            self.sync_format = v;
+        }
+        if let Some(v) = env_value("TODO_AUTO_SYNC") {
+            self.auto_sync = parse_env_bool("TODO_AUTO_SYNC", &v)?;
+        }
+        if let Some(v) = env_value("TODO_AUTO_SYNC_DELAY") {
+            self.auto_sync_delay = v
+                .parse()
+                .with_context(|| format!("TODO_AUTO_SYNC_DELAY must be a number, got '{}'", v))?;
+        }
+        if let Some(v) = env_value("TODO_READ_ONLY") {
+            self.read_only = parse_env_bool("TODO_READ_ONLY", &v)?;
+        }
+        if let Some(v) = env_value("TODO_USER") {
+            self.user = Some(v);
+        }
+        if let Some(v) = env_value("TODO_DAILY_CAPACITY") {
+            self.daily_capacity = v;
+        }
+        if let Some(v) = env_value("TODO_EFFORT_WEIGHT") {
+            self.effort_weight = v
+                .parse()
+                .with_context(|| format!("TODO_EFFORT_WEIGHT must be a number, got '{}'", v))?;
+        }
+        if let Some(v) = env_value("TODO_CATCH_UP_AFTER") {
+            self.catch_up_after = v;
+        }
+        if let Some(v) = env_value("TODO_NOTIFY_COOLDOWN") {
+            self.notify_cooldown = v;
+        }
+        if let Some(v) = env_value("TODO_DIGEST_TIME") {
+            self.digest_time = v;
+        }
+        if let Some(v) = env_value("TODO_UNDO_LEVELS") {
+            self.undo_levels = v
+                .parse()
+                .with_context(|| format!("TODO_UNDO_LEVELS must be a number, got '{}'", v))?;
+        }
+        if let Some(v) = env_value("TODO_TRASH_DAYS") {
+            self.trash_days = v
+                .parse()
+                .with_context(|| format!("TODO_TRASH_DAYS must be a number, got '{}'", v))?;
+        }
+        if let Some(v) = env_value("TODO_COLLAPSE_WHITESPACE") {
+            self.collapse_whitespace = parse_env_bool("TODO_COLLAPSE_WHITESPACE", &v)?;
+        }
+        if let Some(v) = env_value("TODO_TITLE_CASE") {
+            self.title_case = v;
+        }
+        if let Some(v) = env_value("TODO_TITLE_PREFIX") {
+            self.title_prefix = Some(v);
+        }
+        Ok(())
+    }
+
+    /// Directory holding the database, honoring `data_dir`
+    pub fn data_dir(&self) -> Result<PathBuf> {
+        match self.data_dir {
+            Some(ref dir) => Ok(expand_home(dir)),
+            None => default_data_dir(),
+        }
+    }
+
+    /// The database file: `db_path` if set, else the named list from `list` or `todo use`
+    pub fn db_path(&self) -> Result<PathBuf> {
+        if let Some(ref path) = self.db_path {
+            return Ok(expand_home(path));
+        }
+        match self.list {
+            Some(ref name) => self.list_path(name),
+            None => self.list_path(&self.current_list()?),
+        }
+    }
+
+    /// `sync_dir` with `~` expanded
+    pub fn sync_dir(&self) -> Option<PathBuf> {
+        self.sync_dir.as_deref().map(expand_home)
+    }
+
+    /// Database file of a named list: todo.db for "default", `lists/<name>.db` otherwise
+    pub fn list_path(&self, name: &str) -> Result<PathBuf> {
+        let valid = !name.is_empty()
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(anyhow!(
+                "Invalid list name '{}'. Use letters, digits, - and _, e.g. work",
+                name
+            ));
+        }
+        let data_dir = self.data_dir()?;
+        Ok(match name {
+            "default" => data_dir.join("todo.db"),
+            _ => data_dir.join("lists").join(format!("{}.db", name)),
+        })
+    }
+
+    /// The list picked with `todo use`, "default" if none
+    pub fn current_list(&self) -> Result<String> {
+        let path = self.data_dir()?.join("current-list");
+        let name = std::fs::read_to_string(path).unwrap_or_default();
+        Ok(match name.trim() {
+            "" => "default".to_string(),
+            name => name.to_string(),
+        })
+    }
+
+    /// Who "me" is for owners and `--mine`: the `user` setting, else the login name
+    pub fn user_name(&self) -> Option<String> {
+        self.user
+            .clone()
+            .or_else(|| env_value("USER"))
+            .or_else(|| env_value("USERNAME"))
+    }
+
+    /// Apply the `color` setting to terminal output
+    pub fn apply_color(&self) {
+        match self.color.as_str() {
+            "always" => colored::control::set_override(true),
+            "never" => colored::control::set_override(false),
+            _ => {}
+        }
+    }
+
+    /// Defaults for a new todo with these tags, in this project; when tags disagree the
+    /// first tag wins, and the project's defaults fill in what no tag sets
+    pub fn defaults_for(&self, tags: &[String], project: Option<&str>) -> TagDefaults {
+        let mut merged = TagDefaults::default();
+        let by_tag = tags.iter().filter_map(|t| self.tag_defaults.get(t));
+        let by_project = project.and_then(|p| self.project_defaults.get(p));
+        for rule in by_tag.chain(by_project) {
+            merged.remind = merged.remind.or_else(|| rule.remind.clone());
+            merged.due = merged.due.or_else(|| rule.due.clone());
+            merged.review = merged.review.or_else(|| rule.review.clone());
+            merged.effort = merged.effort.or_else(|| rule.effort.clone());
+            merged.priority = merged.priority.or_else(|| rule.priority.clone());
+            merged.location = merged.location.or_else(|| rule.location.clone());
+            merged.assign = merged.assign.or_else(|| rule.assign.clone());
+            merged.notify = merged.notify.or_else(|| rule.notify.clone());
+            for tag in &rule.tags {
+                if !merged.tags.contains(tag) {
+                    merged.tags.push(tag.clone());
+                }
+            }
+        }
+        merged
+    }
+
+    /// Rules whose pattern matches `title`, in config order
+    pub fn rules_matching(&self, title: &str) -> Result<Vec<&TitleRule>> {
+        let mut matched = Vec::new();
+        for rule in &self.rules {
+            let re = RegexBuilder::new(&rule.pattern)
+                .case_insensitive(true)
+                .build()
+                .with_context(|| format!("invalid rule pattern '{}'", rule.pattern))?;
+            if re.is_match(title) {
+                matched.push(rule);
+            }
+        }
+        Ok(matched)
+    }
+
+    /// Tag associated with `dir` (or its closest configured ancestor), with the directory it came from
+    pub fn tag_for_dir(&self, dir: &Path) -> Option<(&str, &str)> {
+        self.dir_tags
+            .iter()
+            .filter(|(path, _)| dir.starts_with(expand_home(path)))
+            .max_by_key(|(path, _)| expand_home(path).components().count())
+            .map(|(path, tag)| (path.as_str(), tag.as_str()))
+    }
+}
+
+/// A set, non-empty environment variable
+fn env_value(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|v| !v.is_empty())
+}
+
+fn parse_env_bool(name: &str, value: &str) -> Result<bool> {
+    match value.to_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Ok(true),
+        "0" | "false" | "no" | "off" => Ok(false),
+        _ => anyhow::bail!("{} must be true or false, got '{}'", name, value),
+    }
+}
+
+/// Expand a leading `~` to the home directory
+pub fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix('~'), home_dir()) {
+        (Some(rest), Ok(home)) => rest
+            .split(['/', '\\'])
+            .filter(|part| !part.is_empty())
+            .fold(home, |dir, part| dir.join(part)),
+        _ => PathBuf::from(path),
+    }
+}
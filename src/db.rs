@@ -1,43 +1,133 @@
-use std::path::PathBuf;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
 
-use anyhow::{Context, Result};
-use polodb_core::bson::{doc, oid::ObjectId, DateTime as BsonDateTime};
+use anyhow::{anyhow, Context, Result};
+use chrono::NaiveDate;
+use polodb_core::bson::{self, doc, oid::ObjectId, DateTime as BsonDateTime, Document};
 use polodb_core::{Collection, CollectionT, Database};
 
-use crate::models::Todo;
+use crate::config::Config;
+use crate::models::{
+    ApiToken, Comment, Effort, EscalationStep, Focus, GitLink, Goal, HistoryEntry, JournalEntry,
+    Priority, RemoteLink, ServeUser, Todo, TrashedTodo,
+};
 
 const COLLECTION_NAME: &str = "todos";
+const GOALS_COLLECTION: &str = "goals";
+/// Todos put away with `todo archive`, out of the list but not deleted
+const ARCHIVE_COLLECTION: &str = "archive";
+/// Deleted todos, kept until `todo restore --trash` brings them back or they expire
+const TRASH_COLLECTION: &str = "trash";
+/// Commands that `todo undo` can take back
+const JOURNAL_COLLECTION: &str = "journal";
+/// Single-document settings that live with the data, keyed by `_id`
+const META_COLLECTION: &str = "meta";
 
-fn db_path() -> Result<PathBuf> {
-    let home = std::env::var("HOME").context("HOME environment variable not set")?;
-    let data_dir = PathBuf::from(home).join(".local/share/todo");
-    std::fs::create_dir_all(&data_dir).context("failed to create data directory")?;
-    Ok(data_dir.join("todo.db"))
+/// Time spent loading the list from the database and how often, for `--timings`; calls
+/// answered from the cache don't count
+static LIST_NANOS: AtomicU64 = AtomicU64::new(0);
+static LIST_CALLS: AtomicU32 = AtomicU32::new(0);
+
+/// Total time spent loading the list so far, and how many times it was loaded
+pub fn list_stats() -> (Duration, u32) {
+    (
+        Duration::from_nanos(LIST_NANOS.load(Ordering::Relaxed)),
+        LIST_CALLS.load(Ordering::Relaxed),
+    )
+}
+
+pub fn db_path(config: &Config) -> Result<PathBuf> {
+    config.db_path()
 }
 
 pub struct TodoDb {
     db: Database,
+    read_only: bool,
+    /// The decoded, sorted list from the last `list_all`, until something is written.
+    /// Only this process writes while the database is open, so it can't go stale otherwise.
+    cache: RefCell<Option<Vec<Todo>>>,
 }
 
 impl TodoDb {
-    pub fn open() -> Result<Self> {
-        let path = db_path()?;
-        let db = Database::open_path(&path).context("failed to open database")?;
-        Ok(Self { db })
+    pub fn open(config: &Config) -> Result<Self> {
+        Self::open_path(&db_path(config)?, config.read_only)
+    }
+
+    /// Open the database file at `path`, creating it unless `read_only`
+    pub fn open_path(path: &Path, read_only: bool) -> Result<Self> {
+        if read_only && !path.exists() {
+            return Err(anyhow!("No database at {} to open read-only", path.display()));
+        }
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).context("failed to create data directory")?;
+        }
+        let db = Database::open_path(path).context("failed to open database")?;
+        Ok(Self {
+            db,
+            read_only,
+            cache: RefCell::new(None),
+        })
+    }
+
+    /// Every method that writes calls this first, so `--read-only` can't be bypassed and
+    /// the cached list is dropped before it goes out of date
+    fn check_writable(&self) -> Result<()> {
+        if self.read_only {
+            return Err(anyhow!(
+                "The database is open read-only (--read-only / TODO_READ_ONLY); nothing was changed"
+            ));
+        }
+        self.cache.replace(None);
+        Ok(())
     }
 
     fn collection(&self) -> Collection<Todo> {
         self.db.collection::<Todo>(COLLECTION_NAME)
     }
 
+    fn goals(&self) -> Collection<Goal> {
+        self.db.collection::<Goal>(GOALS_COLLECTION)
+    }
+
+    fn archive(&self) -> Collection<Todo> {
+        self.db.collection::<Todo>(ARCHIVE_COLLECTION)
+    }
+
+    fn trash(&self) -> Collection<TrashedTodo> {
+        self.db.collection::<TrashedTodo>(TRASH_COLLECTION)
+    }
+
+    fn journal(&self) -> Collection<JournalEntry> {
+        self.db.collection::<JournalEntry>(JOURNAL_COLLECTION)
+    }
+
+    fn meta(&self) -> Collection<Document> {
+        self.db.collection::<Document>(META_COLLECTION)
+    }
+
     pub fn insert(&self, todo: &Todo) -> Result<()> {
+        self.check_writable()?;
         self.collection()
             .insert_one(todo)
             .context("failed to insert todo")?;
         Ok(())
     }
 
+    /// Every todo in list order. Repeat calls reuse the list until the next write.
     pub fn list_all(&self) -> Result<Vec<Todo>> {
+        if let Some(ref todos) = *self.cache.borrow() {
+            return Ok(todos.clone());
+        }
+        let todos = self.load_all()?;
+        self.cache.replace(Some(todos.clone()));
+        Ok(todos)
+    }
+
+    fn load_all(&self) -> Result<Vec<Todo>> {
+        let started = Instant::now();
         let cursor = self
             .collection()
             .find(doc! {})
@@ -48,49 +138,106 @@ impl TodoDb {
             .map(|item| item.context("failed to decode todo"))
             .collect::<Result<Vec<_>>>()?;
 
-        // Sort: pending first, then by created_at ascending
+        // Sort: pending first (highest priority first, unset counting as medium, then
+        // oldest first), then done ones, most recently completed first. Todos completed
+        // before done_at was recorded go last, oldest first.
+        let rank = |t: &Todo| t.priority.unwrap_or(Priority::Medium);
+        let completed = |t: &Todo| std::cmp::Reverse(t.done_at.map(|d| d.timestamp_millis()));
         todos.sort_by(|a, b| {
             match (a.done, b.done) {
                 (false, true) => std::cmp::Ordering::Less,
                 (true, false) => std::cmp::Ordering::Greater,
-                _ => a.created_at.timestamp_millis().cmp(&b.created_at.timestamp_millis()),
+                (false, false) => rank(b).cmp(&rank(a)).then_with(|| {
+                    a.created_at.timestamp_millis().cmp(&b.created_at.timestamp_millis())
+                }),
+                _ => completed(a).cmp(&completed(b)).then_with(|| {
+                    a.created_at.timestamp_millis().cmp(&b.created_at.timestamp_millis())
+                }),
             }
         });
 
+        LIST_NANOS.fetch_add(started.elapsed().as_nanos() as u64, Ordering::Relaxed);
+        LIST_CALLS.fetch_add(1, Ordering::Relaxed);
         Ok(todos)
     }
 
+    /// Every todo as a raw document, without decoding; `todo bench` times the two apart
+    pub fn list_documents(&self) -> Result<Vec<Document>> {
+        self.db
+            .collection::<Document>(COLLECTION_NAME)
+            .find(doc! {})
+            .run()
+            .context("failed to query todos")?
+            .map(|item| item.context("failed to read todo"))
+            .collect()
+    }
+
     #[allow(dead_code)]
     pub fn find_by_id(&self, id: &ObjectId) -> Result<Option<Todo>> {
-        let cursor = self
+        let mut cursor = self
             .collection()
             .find(doc! { "_id": id })
             .run()
             .context("failed to query todo")?;
 
-        for item in cursor {
-            return Ok(Some(item.context("failed to decode todo")?));
-        }
-        Ok(None)
+        cursor
+            .next()
+            .transpose()
+            .context("failed to decode todo")
     }
 
     pub fn mark_done(&self, id: &ObjectId) -> Result<bool> {
+        self.mark_done_by(id, None)
+    }
+
+    /// Mark a todo done on behalf of a `todo serve` user
+    pub fn mark_done_by(&self, id: &ObjectId, by: Option<&str>) -> Result<bool> {
+        self.check_writable()?;
         let res = self
             .collection()
-            .update_one(doc! { "_id": id }, doc! { "$set": { "done": true } })
+            .update_one(
+                doc! { "_id": id },
+                doc! { "$set": {
+                    "done": true,
+                    "done_at": BsonDateTime::now(),
+                    "completed_by": by,
+                } },
+            )
             .context("failed to update todo")?;
         Ok(res.matched_count > 0)
     }
 
     pub fn mark_undone(&self, id: &ObjectId) -> Result<bool> {
+        self.check_writable()?;
         let res = self
             .collection()
-            .update_one(doc! { "_id": id }, doc! { "$set": { "done": false } })
+            .update_one(
+                doc! { "_id": id },
+                doc! { "$set": { "done": false, "done_at": null, "completed_by": null } },
+            )
             .context("failed to update todo")?;
         Ok(res.matched_count > 0)
     }
 
+    /// Set `done` on several todos in one update, returning how many changed
+    pub fn set_done_many(&self, ids: &[ObjectId], done: bool) -> Result<u64> {
+        self.check_writable()?;
+        let res = self
+            .collection()
+            .update_many(
+                doc! { "_id": { "$in": ids.to_vec() } },
+                doc! { "$set": {
+                    "done": done,
+                    "done_at": done.then(BsonDateTime::now),
+                    "completed_by": null,
+                } },
+            )
+            .context("failed to update todos")?;
+        Ok(res.modified_count)
+    }
+
     pub fn update_title(&self, id: &ObjectId, new_title: &str) -> Result<bool> {
+        self.check_writable()?;
         let res = self
             .collection()
             .update_one(doc! { "_id": id }, doc! { "$set": { "title": new_title } })
@@ -98,7 +245,261 @@ impl TodoDb {
         Ok(res.matched_count > 0)
     }
 
+    /// Delegate a todo to someone (`None` takes it back), stamping when the wait began
+    pub fn set_waiting(&self, id: &ObjectId, waiting_on: Option<&str>) -> Result<bool> {
+        self.check_writable()?;
+        let since = waiting_on.map(|_| BsonDateTime::now());
+        let res = self
+            .collection()
+            .update_one(
+                doc! { "_id": id },
+                doc! { "$set": { "waiting_on": waiting_on, "waiting_since": since } },
+            )
+            .context("failed to update delegation")?;
+        Ok(res.matched_count > 0)
+    }
+
+    /// Record why a todo is stuck (`None` unblocks it), stamping when it got stuck
+    pub fn set_blocked(&self, id: &ObjectId, reason: Option<&str>) -> Result<bool> {
+        self.check_writable()?;
+        let since = reason.map(|_| BsonDateTime::now());
+        let res = self
+            .collection()
+            .update_one(
+                doc! { "_id": id },
+                doc! { "$set": { "block_reason": reason, "blocked_since": since } },
+            )
+            .context("failed to update block reason")?;
+        Ok(res.matched_count > 0)
+    }
+
+    pub fn set_inbox(&self, id: &ObjectId, inbox: bool) -> Result<bool> {
+        self.check_writable()?;
+        let res = self
+            .collection()
+            .update_one(doc! { "_id": id }, doc! { "$set": { "inbox": inbox } })
+            .context("failed to update inbox")?;
+        Ok(res.matched_count > 0)
+    }
+
+    pub fn set_estimate(&self, id: &ObjectId, minutes: Option<i64>) -> Result<bool> {
+        self.check_writable()?;
+        let res = self
+            .collection()
+            .update_one(doc! { "_id": id }, doc! { "$set": { "estimate_minutes": minutes } })
+            .context("failed to update estimate")?;
+        Ok(res.matched_count > 0)
+    }
+
+    pub fn set_project(&self, id: &ObjectId, project: Option<&str>) -> Result<bool> {
+        self.check_writable()?;
+        let res = self
+            .collection()
+            .update_one(doc! { "_id": id }, doc! { "$set": { "project": project } })
+            .context("failed to update project")?;
+        Ok(res.matched_count > 0)
+    }
+
+    pub fn set_sprint(&self, id: &ObjectId, sprint: Option<&str>) -> Result<bool> {
+        self.check_writable()?;
+        let res = self
+            .collection()
+            .update_one(doc! { "_id": id }, doc! { "$set": { "sprint": sprint } })
+            .context("failed to update sprint")?;
+        Ok(res.matched_count > 0)
+    }
+
+    pub fn set_priority(&self, id: &ObjectId, priority: Option<Priority>) -> Result<bool> {
+        self.check_writable()?;
+        let priority = bson::to_bson(&priority).context("failed to encode priority")?;
+        let res = self
+            .collection()
+            .update_one(doc! { "_id": id }, doc! { "$set": { "priority": priority } })
+            .context("failed to update priority")?;
+        Ok(res.matched_count > 0)
+    }
+
+    pub fn set_effort(&self, id: &ObjectId, effort: Option<Effort>) -> Result<bool> {
+        self.check_writable()?;
+        let effort = bson::to_bson(&effort).context("failed to encode effort")?;
+        let res = self
+            .collection()
+            .update_one(doc! { "_id": id }, doc! { "$set": { "effort": effort } })
+            .context("failed to update effort")?;
+        Ok(res.matched_count > 0)
+    }
+
+    pub fn set_comments(&self, id: &ObjectId, comments: &[Comment]) -> Result<bool> {
+        self.check_writable()?;
+        let comments = bson::to_bson(comments).context("failed to encode comments")?;
+        let res = self
+            .collection()
+            .update_one(doc! { "_id": id }, doc! { "$set": { "comments": comments } })
+            .context("failed to update comments")?;
+        Ok(res.matched_count > 0)
+    }
+
+    /// Overwrite every field of a todo with `todo`'s
+    pub fn replace(&self, todo: &Todo) -> Result<bool> {
+        self.check_writable()?;
+        let mut fields = bson::to_document(todo).context("failed to encode todo")?;
+        fields.remove("_id");
+        let res = self
+            .collection()
+            .update_one(doc! { "_id": todo.id }, doc! { "$set": fields })
+            .context("failed to update todo")?;
+        Ok(res.matched_count > 0)
+    }
+
+    pub fn set_notes(&self, id: &ObjectId, notes: Option<&str>) -> Result<bool> {
+        self.check_writable()?;
+        let res = self
+            .collection()
+            .update_one(doc! { "_id": id }, doc! { "$set": { "notes": notes } })
+            .context("failed to update notes")?;
+        Ok(res.matched_count > 0)
+    }
+
+    pub fn set_dependencies(&self, id: &ObjectId, depends_on: &[ObjectId]) -> Result<bool> {
+        self.check_writable()?;
+        let res = self
+            .collection()
+            .update_one(
+                doc! { "_id": id },
+                doc! { "$set": { "depends_on": depends_on.to_vec() } },
+            )
+            .context("failed to update dependencies")?;
+        Ok(res.matched_count > 0)
+    }
+
+    pub fn set_tags(&self, id: &ObjectId, tags: &[String]) -> Result<bool> {
+        self.check_writable()?;
+        let res = self
+            .collection()
+            .update_one(doc! { "_id": id }, doc! { "$set": { "tags": tags.to_vec() } })
+            .context("failed to update tags")?;
+        Ok(res.matched_count > 0)
+    }
+
+    pub fn set_git_link(&self, id: &ObjectId, link: Option<&GitLink>) -> Result<bool> {
+        self.check_writable()?;
+        let link = link
+            .map(bson::to_bson)
+            .transpose()
+            .context("failed to encode git link")?;
+        let res = self
+            .collection()
+            .update_one(doc! { "_id": id }, doc! { "$set": { "git": link } })
+            .context("failed to update git link")?;
+        Ok(res.matched_count > 0)
+    }
+
+    pub fn set_due(&self, id: &ObjectId, due_at: Option<BsonDateTime>) -> Result<bool> {
+        self.check_writable()?;
+        let res = self
+            .collection()
+            .update_one(doc! { "_id": id }, doc! { "$set": { "due_at": due_at } })
+            .context("failed to set due date")?;
+        Ok(res.matched_count > 0)
+    }
+
+    /// Store lead times and the pending notifications derived from them
+    pub fn set_notifications(
+        &self,
+        id: &ObjectId,
+        leads: &[i64],
+        pending: &[BsonDateTime],
+    ) -> Result<bool> {
+        self.check_writable()?;
+        let res = self
+            .collection()
+            .update_one(
+                doc! { "_id": id },
+                doc! { "$set": { "notify_before": leads, "notifications": pending } },
+            )
+            .context("failed to set notifications")?;
+        Ok(res.matched_count > 0)
+    }
+
+    pub fn set_review(&self, id: &ObjectId, review_at: Option<BsonDateTime>) -> Result<bool> {
+        self.check_writable()?;
+        let res = self
+            .collection()
+            .update_one(doc! { "_id": id }, doc! { "$set": { "review_at": review_at } })
+            .context("failed to update review date")?;
+        Ok(res.matched_count > 0)
+    }
+
+    pub fn set_recurrence(&self, id: &ObjectId, rule: Option<&str>) -> Result<bool> {
+        self.check_writable()?;
+        let res = self
+            .collection()
+            .update_one(doc! { "_id": id }, doc! { "$set": { "recur": rule } })
+            .context("failed to set recurrence")?;
+        Ok(res.matched_count > 0)
+    }
+
+    /// Move a recurring todo on to its next occurrence, appending to its history
+    pub fn advance_occurrence(
+        &self,
+        id: &ObjectId,
+        next_due: BsonDateTime,
+        history: &[HistoryEntry],
+    ) -> Result<bool> {
+        self.check_writable()?;
+        let history = bson::to_bson(history).context("failed to encode history")?;
+        let res = self
+            .collection()
+            .update_one(
+                doc! { "_id": id },
+                doc! { "$set": { "due_at": next_due, "done": false, "history": history } },
+            )
+            .context("failed to advance recurring todo")?;
+        Ok(res.matched_count > 0)
+    }
+
+    /// Delete several todos in one query, returning how many were removed
+    pub fn delete_many(&self, ids: &[ObjectId]) -> Result<u64> {
+        self.check_writable()?;
+        let res = self
+            .collection()
+            .delete_many(doc! { "_id": { "$in": ids.to_vec() } })
+            .context("failed to delete todos")?;
+        Ok(res.deleted_count)
+    }
+
+    /// Permanently delete every completed todo, returning how many were removed
+    pub fn delete_done(&self) -> Result<u64> {
+        self.check_writable()?;
+        let res = self
+            .collection()
+            .delete_many(doc! { "done": true })
+            .context("failed to delete completed todos")?;
+        Ok(res.deleted_count)
+    }
+
+    /// Empty every collection in the database (todos, goals and anything added later)
+    pub fn purge_all(&self) -> Result<u64> {
+        self.check_writable()?;
+        let names = self
+            .db
+            .list_collection_names()
+            .context("failed to list collections")?;
+
+        let mut deleted = 0;
+        for name in names {
+            let res = self
+                .db
+                .collection::<Document>(&name)
+                .delete_many(doc! {})
+                .with_context(|| format!("failed to purge {}", name))?;
+            deleted += res.deleted_count;
+        }
+        Ok(deleted)
+    }
+
     pub fn delete(&self, id: &ObjectId) -> Result<bool> {
+        self.check_writable()?;
         let res = self
             .collection()
             .delete_one(doc! { "_id": id })
@@ -107,16 +508,58 @@ impl TodoDb {
     }
 
     pub fn set_reminder(&self, id: &ObjectId, remind_at: Option<BsonDateTime>) -> Result<bool> {
+        self.check_writable()?;
+        let res = self
+            .collection()
+            .update_one(
+                doc! { "_id": id },
+                doc! { "$set": {
+                    "remind_at": remind_at,
+                    "remind_every": null,
+                    "notified": false,
+                    "escalation_step": 0,
+                } },
+            )
+            .context("failed to set reminder")?;
+        Ok(res.matched_count > 0)
+    }
+
+    /// Set a repeating reminder: `remind_at` is the first firing, `rule` the schedule text
+    pub fn set_repeating_reminder(
+        &self,
+        id: &ObjectId,
+        remind_at: BsonDateTime,
+        rule: &str,
+    ) -> Result<bool> {
+        self.check_writable()?;
         let res = self
             .collection()
             .update_one(
                 doc! { "_id": id },
-                doc! { "$set": { "remind_at": remind_at, "notified": false } },
+                doc! { "$set": {
+                    "remind_at": remind_at,
+                    "remind_every": rule,
+                    "notified": false,
+                    "escalation_step": 0,
+                } },
             )
             .context("failed to set reminder")?;
         Ok(res.matched_count > 0)
     }
 
+    /// Move a repeating reminder on to its next firing instead of marking it notified
+    pub fn advance_reminder(&self, id: &ObjectId, next: BsonDateTime) -> Result<bool> {
+        self.check_writable()?;
+        let res = self
+            .collection()
+            .update_one(
+                doc! { "_id": id },
+                doc! { "$set": { "remind_at": next, "notified": false, "escalation_step": 0 } },
+            )
+            .context("failed to advance reminder")?;
+        Ok(res.matched_count > 0)
+    }
+
     pub fn get_due_reminders(&self) -> Result<Vec<Todo>> {
         let now = BsonDateTime::now();
         let todos = self.list_all()?;
@@ -133,7 +576,24 @@ impl TodoDb {
             .collect())
     }
 
+    /// Pending todos with at least one lead-time notification that has come due
+    pub fn get_due_notifications(&self) -> Result<Vec<Todo>> {
+        let now = BsonDateTime::now();
+        let todos = self.list_all()?;
+
+        Ok(todos
+            .into_iter()
+            .filter(|t| {
+                !t.done
+                    && t.notifications
+                        .iter()
+                        .any(|n| n.timestamp_millis() <= now.timestamp_millis())
+            })
+            .collect())
+    }
+
     pub fn mark_notified(&self, id: &ObjectId) -> Result<bool> {
+        self.check_writable()?;
         let res = self
             .collection()
             .update_one(doc! { "_id": id }, doc! { "$set": { "notified": true } })
@@ -141,14 +601,537 @@ impl TodoDb {
         Ok(res.matched_count > 0)
     }
 
+    /// Record when `todo notify` last sent something for the todo
+    pub fn mark_sent(&self, id: &ObjectId, at: BsonDateTime) -> Result<bool> {
+        self.check_writable()?;
+        let res = self
+            .collection()
+            .update_one(doc! { "_id": id }, doc! { "$set": { "last_notified_at": at } })
+            .context("failed to record notification")?;
+        Ok(res.matched_count > 0)
+    }
+
     pub fn clear_reminder(&self, id: &ObjectId) -> Result<bool> {
+        self.check_writable()?;
         let res = self
             .collection()
             .update_one(
                 doc! { "_id": id },
-                doc! { "$set": { "remind_at": null, "notified": false } },
+                doc! { "$set": {
+                    "remind_at": null,
+                    "remind_every": null,
+                    "notified": false,
+                    "escalation": [],
+                    "escalation_step": 0,
+                } },
             )
             .context("failed to clear reminder")?;
         Ok(res.matched_count > 0)
     }
+
+    pub fn set_escalation(&self, id: &ObjectId, steps: &[EscalationStep]) -> Result<bool> {
+        self.check_writable()?;
+        let steps = bson::to_bson(steps).context("failed to encode escalation")?;
+        let res = self
+            .collection()
+            .update_one(
+                doc! { "_id": id },
+                doc! { "$set": { "escalation": steps, "escalation_step": 0 } },
+            )
+            .context("failed to set escalation")?;
+        Ok(res.matched_count > 0)
+    }
+
+    pub fn get_due_escalations(&self) -> Result<Vec<Todo>> {
+        let now = BsonDateTime::now();
+        Ok(self
+            .list_all()?
+            .into_iter()
+            .filter(|t| t.due_escalation(now).is_some())
+            .collect())
+    }
+
+    /// Record that escalation step `step` fired, so the next call moves on to `step + 1`
+    pub fn mark_escalated(&self, id: &ObjectId, step: u32) -> Result<bool> {
+        self.check_writable()?;
+        let res = self
+            .collection()
+            .update_one(
+                doc! { "_id": id },
+                doc! { "$set": { "escalation_step": step as i64 + 1 } },
+            )
+            .context("failed to record escalation")?;
+        Ok(res.matched_count > 0)
+    }
+
+    pub fn set_goal(&self, id: &ObjectId, goal_id: Option<ObjectId>) -> Result<bool> {
+        self.check_writable()?;
+        let res = self
+            .collection()
+            .update_one(doc! { "_id": id }, doc! { "$set": { "goal_id": goal_id } })
+            .context("failed to link goal")?;
+        Ok(res.matched_count > 0)
+    }
+
+    pub fn insert_goal(&self, goal: &Goal) -> Result<()> {
+        self.check_writable()?;
+        self.goals()
+            .insert_one(goal)
+            .context("failed to insert goal")?;
+        Ok(())
+    }
+
+    pub fn list_goals(&self) -> Result<Vec<Goal>> {
+        let cursor = self
+            .goals()
+            .find(doc! {})
+            .run()
+            .context("failed to query goals")?;
+
+        let mut goals: Vec<Goal> = cursor
+            .map(|item| item.context("failed to decode goal"))
+            .collect::<Result<Vec<_>>>()?;
+
+        goals.sort_by_key(|g| g.created_at.timestamp_millis());
+        Ok(goals)
+    }
+
+    pub fn delete_goal(&self, id: &ObjectId) -> Result<bool> {
+        self.check_writable()?;
+        let res = self
+            .goals()
+            .delete_one(doc! { "_id": id })
+            .context("failed to delete goal")?;
+
+        // Unlink any todos that pointed at the removed goal
+        self.collection()
+            .update_many(doc! { "goal_id": id }, doc! { "$set": { "goal_id": null } })
+            .context("failed to unlink todos from goal")?;
+
+        Ok(res.deleted_count > 0)
+    }
+
+    /// Start a `todo notify` run unless another one started less than `min_gap_ms` ago.
+    /// Returns false when this run should be skipped.
+    pub fn claim_notify_run(&self, min_gap_ms: i64) -> Result<bool> {
+        self.check_writable()?;
+        let now = BsonDateTime::now();
+        let last_run = self
+            .meta()
+            .find_one(doc! { "_id": "notify" })
+            .context("failed to read notify state")?
+            .and_then(|d| d.get_datetime("last_run").ok().copied());
+        if last_run.is_some_and(|t| now.timestamp_millis() - t.timestamp_millis() < min_gap_ms) {
+            return Ok(false);
+        }
+
+        self.meta()
+            .delete_one(doc! { "_id": "notify" })
+            .context("failed to save notify state")?;
+        self.meta()
+            .insert_one(doc! { "_id": "notify", "last_run": now })
+            .context("failed to save notify state")?;
+        Ok(true)
+    }
+
+    /// Holidays skipped by business-day scheduling, earliest first
+    pub fn get_holidays(&self) -> Result<Vec<NaiveDate>> {
+        let found = self
+            .meta()
+            .find_one(doc! { "_id": "holidays" })
+            .context("failed to read holidays")?;
+        let Some(doc) = found else {
+            return Ok(Vec::new());
+        };
+        let dates: Vec<String> = bson::from_bson(doc.get("dates").cloned().unwrap_or_default())
+            .context("failed to decode holidays")?;
+        Ok(dates
+            .iter()
+            .filter_map(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+            .collect())
+    }
+
+    /// Replace the holiday list
+    pub fn set_holidays(&self, dates: &[NaiveDate]) -> Result<()> {
+        self.check_writable()?;
+        let dates: Vec<String> = dates.iter().map(|d| d.format("%Y-%m-%d").to_string()).collect();
+        self.meta()
+            .delete_one(doc! { "_id": "holidays" })
+            .context("failed to save holidays")?;
+        self.meta()
+            .insert_one(doc! { "_id": "holidays", "dates": dates })
+            .context("failed to save holidays")?;
+        Ok(())
+    }
+
+    /// Archived todos, oldest first
+    pub fn list_archived(&self) -> Result<Vec<Todo>> {
+        let mut todos = self
+            .archive()
+            .find(doc! {})
+            .run()
+            .context("failed to query the archive")?
+            .map(|item| item.context("failed to decode archived todo"))
+            .collect::<Result<Vec<_>>>()?;
+        todos.sort_by_key(|t| t.created_at.timestamp_millis());
+        Ok(todos)
+    }
+
+    /// Move todos from the list into the archive, returning how many moved
+    pub fn archive_many(&self, todos: &[Todo]) -> Result<u64> {
+        self.check_writable()?;
+        if todos.is_empty() {
+            return Ok(0);
+        }
+        self.archive()
+            .insert_many(todos)
+            .context("failed to archive todos")?;
+        let ids: Vec<ObjectId> = todos.iter().map(|t| t.id).collect();
+        self.delete_many(&ids)
+    }
+
+    /// Move archived todos back into the list, returning how many moved
+    pub fn unarchive_many(&self, todos: &[Todo]) -> Result<u64> {
+        self.check_writable()?;
+        if todos.is_empty() {
+            return Ok(0);
+        }
+        self.collection()
+            .insert_many(todos)
+            .context("failed to restore todos")?;
+        let ids: Vec<ObjectId> = todos.iter().map(|t| t.id).collect();
+        let res = self
+            .archive()
+            .delete_many(doc! { "_id": { "$in": ids } })
+            .context("failed to restore todos")?;
+        Ok(res.deleted_count)
+    }
+
+    /// The trash, most recently deleted first
+    pub fn list_trash(&self) -> Result<Vec<TrashedTodo>> {
+        let mut entries = self
+            .trash()
+            .find(doc! {})
+            .run()
+            .context("failed to query the trash")?
+            .map(|item| item.context("failed to decode trashed todo"))
+            .collect::<Result<Vec<_>>>()?;
+        entries.sort_by_key(|e| std::cmp::Reverse(e.deleted_at.timestamp_millis()));
+        Ok(entries)
+    }
+
+    /// Move todos from the list into the trash, returning how many moved
+    pub fn trash_many(&self, todos: &[Todo]) -> Result<u64> {
+        self.check_writable()?;
+        if todos.is_empty() {
+            return Ok(0);
+        }
+        let ids: Vec<ObjectId> = todos.iter().map(|t| t.id).collect();
+        // A todo deleted, brought back with undo and deleted again replaces its old entry
+        self.trash()
+            .delete_many(doc! { "_id": { "$in": ids.clone() } })
+            .context("failed to move todos to the trash")?;
+        let deleted_at = BsonDateTime::now();
+        let entries: Vec<TrashedTodo> = todos
+            .iter()
+            .map(|t| TrashedTodo { id: t.id, todo: t.clone(), deleted_at })
+            .collect();
+        self.trash()
+            .insert_many(entries)
+            .context("failed to move todos to the trash")?;
+        self.delete_many(&ids)
+    }
+
+    /// Move trashed todos back into the list, returning how many came back. Todos already
+    /// back in the list (through `todo undo`) just leave the trash.
+    pub fn untrash_many(&self, entries: &[TrashedTodo]) -> Result<u64> {
+        self.check_writable()?;
+        if entries.is_empty() {
+            return Ok(0);
+        }
+        let listed: HashSet<ObjectId> = self.list_all()?.iter().map(|t| t.id).collect();
+        let todos: Vec<&Todo> =
+            entries.iter().map(|e| &e.todo).filter(|t| !listed.contains(&t.id)).collect();
+        if !todos.is_empty() {
+            self.collection()
+                .insert_many(todos.iter().copied())
+                .context("failed to restore todos")?;
+        }
+        let ids: Vec<ObjectId> = entries.iter().map(|e| e.id).collect();
+        self.remove_from_trash(&ids)?;
+        Ok(todos.len() as u64)
+    }
+
+    /// Delete entries from the trash for good, returning how many went
+    pub fn remove_from_trash(&self, ids: &[ObjectId]) -> Result<u64> {
+        self.check_writable()?;
+        let res = self
+            .trash()
+            .delete_many(doc! { "_id": { "$in": ids.to_vec() } })
+            .context("failed to empty the trash")?;
+        Ok(res.deleted_count)
+    }
+
+    /// Every journal entry, oldest first
+    pub fn list_journal(&self) -> Result<Vec<JournalEntry>> {
+        let mut entries = self
+            .journal()
+            .find(doc! {})
+            .run()
+            .context("failed to read the undo journal")?
+            .map(|item| item.context("failed to decode journal entry"))
+            .collect::<Result<Vec<_>>>()?;
+        entries.sort_by_key(|e| e.seq);
+        Ok(entries)
+    }
+
+    /// Add a command to the journal. Anything waiting to be redone is dropped, and only the
+    /// newest `levels` entries are kept.
+    pub fn record_journal(
+        &self,
+        command: &str,
+        before: Vec<Todo>,
+        after: Vec<Todo>,
+        levels: usize,
+    ) -> Result<()> {
+        self.check_writable()?;
+        self.journal()
+            .delete_many(doc! { "undone": true })
+            .context("failed to update the undo journal")?;
+
+        let entries = self.list_journal()?;
+        let entry = JournalEntry {
+            id: ObjectId::new(),
+            seq: entries.last().map_or(1, |e| e.seq + 1),
+            command: command.to_string(),
+            at: BsonDateTime::now(),
+            before,
+            after,
+            undone: false,
+        };
+        self.journal()
+            .insert_one(&entry)
+            .context("failed to update the undo journal")?;
+
+        let excess = (entries.len() + 1).saturating_sub(levels);
+        if excess > 0 {
+            let old: Vec<ObjectId> = entries.iter().take(excess).map(|e| e.id).collect();
+            self.journal()
+                .delete_many(doc! { "_id": { "$in": old } })
+                .context("failed to trim the undo journal")?;
+        }
+        Ok(())
+    }
+
+    pub fn set_journal_undone(&self, id: &ObjectId, undone: bool) -> Result<bool> {
+        self.check_writable()?;
+        let res = self
+            .journal()
+            .update_one(doc! { "_id": id }, doc! { "$set": { "undone": undone } })
+            .context("failed to update the undo journal")?;
+        Ok(res.matched_count > 0)
+    }
+
+    /// Replace the todos with the given ids by `todos`: ids missing from `todos` are deleted,
+    /// and todos not in the list yet come back (leaving the trash, if `todo rm` put them
+    /// there)
+    pub fn restore_todos(&self, ids: &[ObjectId], todos: &[Todo]) -> Result<()> {
+        self.check_writable()?;
+        self.collection()
+            .delete_many(doc! { "_id": { "$in": ids.to_vec() } })
+            .context("failed to restore todos")?;
+        if !todos.is_empty() {
+            self.collection()
+                .insert_many(todos)
+                .context("failed to restore todos")?;
+            let restored: Vec<ObjectId> = todos.iter().map(|t| t.id).collect();
+            self.remove_from_trash(&restored)?;
+        }
+        Ok(())
+    }
+
+    /// Which side `todo merge` takes for each field the user chose to always resolve one way
+    pub fn get_merge_preferences(&self) -> Result<HashMap<String, String>> {
+        let found = self
+            .meta()
+            .find_one(doc! { "_id": "merge" })
+            .context("failed to read merge preferences")?;
+        let Some(doc) = found else {
+            return Ok(HashMap::new());
+        };
+        bson::from_bson(doc.get("prefer").cloned().unwrap_or_else(|| Document::new().into()))
+            .context("failed to decode merge preferences")
+    }
+
+    /// Replace the remembered merge choices
+    pub fn set_merge_preferences(&self, prefer: &HashMap<String, String>) -> Result<()> {
+        self.check_writable()?;
+        let prefer = bson::to_bson(prefer).context("failed to encode merge preferences")?;
+        self.meta()
+            .delete_one(doc! { "_id": "merge" })
+            .context("failed to save merge preferences")?;
+        self.meta()
+            .insert_one(doc! { "_id": "merge", "prefer": prefer })
+            .context("failed to save merge preferences")?;
+        Ok(())
+    }
+
+    /// Fields the last non-interactive `todo merge` couldn't settle, as "title: field"
+    pub fn get_merge_conflicts(&self) -> Result<Vec<String>> {
+        let found = self
+            .meta()
+            .find_one(doc! { "_id": "merge_conflicts" })
+            .context("failed to read merge conflicts")?;
+        let Some(doc) = found else {
+            return Ok(Vec::new());
+        };
+        bson::from_bson(doc.get("fields").cloned().unwrap_or_default())
+            .context("failed to decode merge conflicts")
+    }
+
+    pub fn set_merge_conflicts(&self, fields: &[String]) -> Result<()> {
+        self.check_writable()?;
+        self.meta()
+            .delete_one(doc! { "_id": "merge_conflicts" })
+            .context("failed to save merge conflicts")?;
+        if !fields.is_empty() {
+            self.meta()
+                .insert_one(doc! { "_id": "merge_conflicts", "fields": fields.to_vec() })
+                .context("failed to save merge conflicts")?;
+        }
+        Ok(())
+    }
+
+    /// When each device last synced successfully, as far as this copy knows
+    pub fn get_sync_devices(&self) -> Result<Vec<(String, BsonDateTime)>> {
+        let found = self
+            .meta()
+            .find_one(doc! { "_id": "sync" })
+            .context("failed to read sync devices")?;
+        let Some(doc) = found else {
+            return Ok(Vec::new());
+        };
+        let devices = doc.get_document("devices").cloned().unwrap_or_default();
+        Ok(devices
+            .iter()
+            .filter_map(|(name, at)| Some((name.clone(), at.as_datetime().copied()?)))
+            .collect())
+    }
+
+    /// Record a device's latest successful sync
+    pub fn set_device_synced(&self, device: &str, at: BsonDateTime) -> Result<()> {
+        self.check_writable()?;
+        let mut devices = Document::new();
+        for (name, synced) in self.get_sync_devices()? {
+            devices.insert(name, synced);
+        }
+        devices.insert(device, at);
+        self.meta()
+            .delete_one(doc! { "_id": "sync" })
+            .context("failed to save sync devices")?;
+        self.meta()
+            .insert_one(doc! { "_id": "sync", "devices": devices })
+            .context("failed to save sync devices")?;
+        Ok(())
+    }
+
+    /// Keys `todo serve` accepts
+    pub fn get_api_tokens(&self) -> Result<Vec<ApiToken>> {
+        let found = self
+            .meta()
+            .find_one(doc! { "_id": "serve_tokens" })
+            .context("failed to read API tokens")?;
+        let Some(doc) = found else {
+            return Ok(Vec::new());
+        };
+        bson::from_bson(doc.get("tokens").cloned().unwrap_or_default())
+            .context("failed to decode API tokens")
+    }
+
+    pub fn set_api_tokens(&self, tokens: &[ApiToken]) -> Result<()> {
+        self.check_writable()?;
+        let tokens = bson::to_bson(tokens).context("failed to encode API tokens")?;
+        self.meta()
+            .delete_one(doc! { "_id": "serve_tokens" })
+            .context("failed to save API tokens")?;
+        self.meta()
+            .insert_one(doc! { "_id": "serve_tokens", "tokens": tokens })
+            .context("failed to save API tokens")?;
+        Ok(())
+    }
+
+    pub fn get_serve_users(&self) -> Result<Vec<ServeUser>> {
+        let found = self
+            .meta()
+            .find_one(doc! { "_id": "serve_users" })
+            .context("failed to read server users")?;
+        let Some(doc) = found else {
+            return Ok(Vec::new());
+        };
+        bson::from_bson(doc.get("users").cloned().unwrap_or_default())
+            .context("failed to decode server users")
+    }
+
+    pub fn set_serve_users(&self, users: &[ServeUser]) -> Result<()> {
+        self.check_writable()?;
+        let users = bson::to_bson(users).context("failed to encode server users")?;
+        self.meta()
+            .delete_one(doc! { "_id": "serve_users" })
+            .context("failed to save server users")?;
+        self.meta()
+            .insert_one(doc! { "_id": "serve_users", "users": users })
+            .context("failed to save server users")?;
+        Ok(())
+    }
+
+    /// What was last synced with a task server, keyed by server (e.g. "caldav:<url>")
+    pub fn get_remote_links(&self, server: &str) -> Result<Vec<RemoteLink>> {
+        let id = format!("remote:{}", server);
+        let found = self
+            .meta()
+            .find_one(doc! { "_id": &id })
+            .context("failed to read sync state")?;
+        let Some(doc) = found else {
+            return Ok(Vec::new());
+        };
+        bson::from_bson(doc.get("links").cloned().unwrap_or_default())
+            .context("failed to decode sync state")
+    }
+
+    pub fn set_remote_links(&self, server: &str, links: &[RemoteLink]) -> Result<()> {
+        self.check_writable()?;
+        let id = format!("remote:{}", server);
+        let links = bson::to_bson(links).context("failed to encode sync state")?;
+        self.meta()
+            .delete_one(doc! { "_id": &id })
+            .context("failed to save sync state")?;
+        self.meta()
+            .insert_one(doc! { "_id": &id, "links": links })
+            .context("failed to save sync state")?;
+        Ok(())
+    }
+
+    pub fn get_focus(&self) -> Result<Option<Focus>> {
+        let found = self
+            .meta()
+            .find_one(doc! { "_id": "focus" })
+            .context("failed to read focus")?;
+        found
+            .map(|d| bson::from_document(d).context("failed to decode focus"))
+            .transpose()
+    }
+
+    /// Replace the focus task (`None` ends focus)
+    pub fn set_focus(&self, focus: Option<&Focus>) -> Result<()> {
+        self.check_writable()?;
+        self.meta()
+            .delete_one(doc! { "_id": "focus" })
+            .context("failed to clear focus")?;
+        if let Some(focus) = focus {
+            self.meta()
+                .insert_one(doc! { "_id": "focus", "todo_id": focus.todo_id, "since": focus.since })
+                .context("failed to save focus")?;
+        }
+        Ok(())
+    }
 }
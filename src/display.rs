@@ -1,8 +1,21 @@
-use chrono::{Local, TimeZone};
-use colored::Colorize;
-use polodb_core::bson::DateTime as BsonDateTime;
+use std::io::{self, Write};
+use std::sync::OnceLock;
+use std::time::Duration;
 
-use crate::models::Todo;
+use anyhow::Result;
+use chrono::{DateTime, Local, NaiveDate, SecondsFormat, TimeZone, Utc};
+use colored::{ColoredString, Colorize};
+use polodb_core::bson::{oid::ObjectId, Bson, DateTime as BsonDateTime};
+use serde_json::{json, Map, Value};
+
+use crate::config::{Config, TitleRule};
+use crate::git;
+use crate::filter::tree_order;
+use crate::i18n;
+use crate::json::{bson_to_json, todo_json};
+use crate::links::{linkify, shorten_urls};
+use crate::models::{ApiToken, Goal, HistoryKind, Priority, Role, ServeUser, Todo, TrashedTodo};
+use crate::sync::SyncStatus;
 
 fn format_datetime(dt: &BsonDateTime) -> String {
     let millis = dt.timestamp_millis();
@@ -17,31 +30,107 @@ fn format_datetime(dt: &BsonDateTime) -> String {
 }
 
 fn format_reminder(dt: &BsonDateTime) -> String {
-    let millis = dt.timestamp_millis();
-    let secs = millis / 1000;
-    let nsecs = ((millis % 1000) * 1_000_000) as u32;
+    let minutes = (dt.timestamp_millis() - BsonDateTime::now().timestamp_millis()) / 60_000;
 
-    if let Some(local_dt) = Local.timestamp_opt(secs, nsecs).single() {
-        let now = Local::now();
-        let diff = local_dt.signed_duration_since(now);
-
-        if diff.num_seconds() < 0 {
-            "overdue".to_string()
-        } else if diff.num_minutes() < 1 {
-            "now".to_string()
-        } else if diff.num_minutes() < 60 {
-            format!("{}m", diff.num_minutes())
-        } else if diff.num_hours() < 24 {
-            format!("{}h", diff.num_hours())
-        } else {
-            format!("{}d", diff.num_days())
-        }
+    if is_overdue(dt) {
+        i18n::overdue().to_string()
+    } else if minutes < 1 {
+        i18n::now().to_string()
+    } else {
+        i18n::duration(minutes)
+    }
+}
+
+fn is_overdue(dt: &BsonDateTime) -> bool {
+    dt.timestamp_millis() < BsonDateTime::now().timestamp_millis()
+}
+
+/// Day thresholds for coloring the Age column; the column is hidden until this is set
+static AGE_COLUMN: OnceLock<(i64, i64)> = OnceLock::new();
+
+/// Turn on the Age column for every table printed in this run
+pub fn enable_age_column(config: &Config) {
+    let _ = AGE_COLUMN.set((config.age_warn_days, config.age_alert_days));
+}
+
+/// Optional list columns to show, from a project's `columns`; all of them until this is set
+static SHOWN_COLUMNS: OnceLock<Vec<String>> = OnceLock::new();
+
+/// Show only these optional columns (see `LIST_COLUMNS`) in every table printed in this run
+pub fn limit_columns(columns: Vec<String>) {
+    let _ = SHOWN_COLUMNS.set(columns);
+}
+
+fn column_shown(name: &str) -> bool {
+    SHOWN_COLUMNS.get().is_none_or(|shown| shown.iter().any(|c| c == name))
+}
+
+/// Machine-readable output for this run, from the global `--json` flag
+static JSON_OUTPUT: OnceLock<()> = OnceLock::new();
+
+/// Print JSON instead of tables and messages for the rest of this run
+pub fn enable_json_output() {
+    let _ = JSON_OUTPUT.set(());
+    colored::control::set_override(false);
+}
+
+pub fn json_output() -> bool {
+    JSON_OUTPUT.get().is_some()
+}
+
+/// One JSON line per message: `{"status":"success","message":"..."}`
+fn print_json_message(status: &str, message: &str) {
+    println!("{}", json!({ "status": status, "message": message.trim() }));
+}
+
+/// A JSON array of todos, each with its list number
+fn print_json_rows(todos: &[Todo], rows: &[usize]) {
+    let items: Vec<Value> = rows.iter().map(|&i| todo_json(i + 1, &todos[i])).collect();
+    println!("{}", Value::Array(items));
+}
+
+/// Critical stands out most; low fades into the background
+fn color_priority(priority: Priority, text: &str) -> ColoredString {
+    match priority {
+        Priority::Critical => text.red().bold(),
+        Priority::High => text.red(),
+        Priority::Medium => text.yellow(),
+        Priority::Low => text.dimmed(),
+    }
+}
+
+/// How long ago a todo was created: 45m, 5h, 2d, 3w, 5mo, 1y
+fn format_age(dt: &BsonDateTime) -> String {
+    let minutes = ((BsonDateTime::now().timestamp_millis() - dt.timestamp_millis()) / 60_000).max(0);
+    let days = minutes / 1440;
+
+    if minutes < 60 {
+        format!("{}m", minutes)
+    } else if days < 1 {
+        format!("{}h", minutes / 60)
+    } else if days < 14 {
+        format!("{}d", days)
+    } else if days < 60 {
+        format!("{}w", days / 7)
+    } else if days < 365 {
+        format!("{}mo", days / 30)
     } else {
-        "?".to_string()
+        format!("{}y", days / 365)
     }
 }
 
 pub fn print_todo_table(todos: &[Todo]) {
+    let mut rows: Vec<usize> = (0..todos.len()).collect();
+    tree_order(todos, &mut rows);
+    print_todo_rows(todos, &rows);
+}
+
+/// Print a subset of todos (given as positions in `todos`), keeping their list numbers
+pub fn print_todo_rows(todos: &[Todo], rows: &[usize]) {
+    if json_output() {
+        print_json_rows(todos, rows);
+        return;
+    }
     if todos.is_empty() {
         println!(
             "{}",
@@ -49,177 +138,1789 @@ pub fn print_todo_table(todos: &[Todo]) {
         );
         return;
     }
+    if rows.is_empty() {
+        println!("{}", "No matching todos.".yellow());
+        return;
+    }
+
+    // Streamed through a buffer; a closed pipe (`todo list | head`) just ends the output
+    let mut out = io::BufWriter::new(io::stdout().lock());
+    let _ = write_todo_rows(&mut out, todos, rows).and_then(|_| out.flush());
+}
+
+/// Write the list table and summary line for `print_todo_rows`. Column widths come from
+/// `rows` alone, and each row is written as soon as it's formatted.
+pub fn write_todo_rows(out: &mut impl Write, todos: &[Todo], rows: &[usize]) -> io::Result<()> {
+    let visible: Vec<&Todo> = rows.iter().map(|&i| &todos[i]).collect();
+
+    // Subtasks that directly follow their parent (see `tree_order`) are indented under it
+    let mut ancestors: Vec<ObjectId> = Vec::new();
+    let depths: Vec<usize> = visible
+        .iter()
+        .map(|t| {
+            while ancestors.last().is_some_and(|&id| Some(id) != t.parent_id) {
+                ancestors.pop();
+            }
+            ancestors.push(t.id);
+            ancestors.len() - 1
+        })
+        .collect();
 
     // Calculate column widths
-    let max_title_len = todos
+    let max_title_len = visible
         .iter()
-        .map(|t| t.title.len())
+        .zip(&depths)
+        .map(|(t, &depth)| shorten_urls(&t.title).chars().count() + depth * 2)
         .max()
         .unwrap_or(5)
         .max(5);
-    let title_width = max_title_len.min(40);
+    // Room for each row's branch and comment badge on top of at least one title character
+    let decorations = visible
+        .iter()
+        .zip(&depths)
+        .map(|(t, &depth)| depth * 2 + comment_badge(t).chars().count() + 2)
+        .max()
+        .unwrap_or(0);
+    let title_width = max_title_len.min(40).max(decorations);
+
+    let has_due = column_shown("due") && visible.iter().any(|t| t.due_at.is_some());
+    let has_reminders = column_shown("remind") && visible.iter().any(|t| t.remind_at.is_some());
+    let has_progress =
+        column_shown("progress") && visible.iter().any(|t| t.progress(todos).is_some());
+    let has_priority = column_shown("priority") && visible.iter().any(|t| t.priority.is_some());
+    let age_thresholds = AGE_COLUMN.get().copied().filter(|_| column_shown("age"));
+    let has_created = column_shown("created");
+    let project_width = visible
+        .iter()
+        .filter(|_| column_shown("project"))
+        .filter_map(|t| t.project.as_ref())
+        .map(|p| p.chars().count() + 1)
+        .max()
+        .map(|w| w.max(7));
+    let assignee_width = visible
+        .iter()
+        .filter(|_| column_shown("assignee"))
+        .filter_map(|t| t.assigned_to.as_ref())
+        .map(|a| a.chars().count() + 1)
+        .max()
+        .map(|w| w.max(8));
 
-    let has_reminders = todos.iter().any(|t| t.remind_at.is_some());
+    // Due/Remind cells grow with the verbose time style; +1 leaves room for the ↻ marker
+    let time_width = visible
+        .iter()
+        .flat_map(|t| [t.due_at, t.remind_at])
+        .flatten()
+        .map(|d| format_reminder(&d).chars().count() + 1)
+        .max()
+        .unwrap_or(0)
+        .max(7);
 
     // Print header
-    println!();
+    let mut header = vec![
+        format!("{:>2}", "#"),
+        format!("{:4}", "ID"),
+        "Status".to_string(),
+    ];
+    if has_priority {
+        header.push(format!("{:8}", "Priority"));
+    }
+    header.push(format!("{:title_width$}", "Title"));
+    if let Some(width) = project_width {
+        header.push(format!("{:width$}", "Project"));
+    }
+    if let Some(width) = assignee_width {
+        header.push(format!("{:width$}", "Assignee"));
+    }
+    if has_progress {
+        header.push(format!("{:10}", "Progress"));
+    }
+    if has_due {
+        header.push(format!("{:>time_width$}", "Due"));
+    }
     if has_reminders {
-        println!(
-            "  {}  {}  {:title_width$}  {}  {}",
-            "#".dimmed(),
-            "Status".dimmed(),
-            "Title".dimmed(),
-            "Remind".dimmed(),
-            "Created".dimmed(),
-            title_width = title_width
-        );
-        println!("  {}", "─".repeat(4 + 8 + title_width + 10 + 18).dimmed());
-    } else {
-        println!(
-            "  {}  {}  {:title_width$}  {}",
-            "#".dimmed(),
-            "Status".dimmed(),
-            "Title".dimmed(),
-            "Created".dimmed(),
-            title_width = title_width
-        );
-        println!("  {}", "─".repeat(4 + 8 + title_width + 18).dimmed());
+        header.push(format!("{:>time_width$}", "Remind"));
+    }
+    if age_thresholds.is_some() {
+        header.push(format!("{:>4}", "Age"));
     }
+    if has_created {
+        header.push(format!("{:16}", "Created"));
+    }
+
+    let header = header.join("  ");
+    writeln!(out)?;
+    writeln!(out, "  {}", header.dimmed())?;
+    writeln!(out, "  {}", "─".repeat(header.chars().count()).dimmed())?;
 
     // Print rows
-    for (i, todo) in todos.iter().enumerate() {
-        let index = format!("{:>2}", i + 1);
+    for (&i, &depth) in rows.iter().zip(&depths) {
+        let todo = &todos[i];
+        let branch = if depth == 0 {
+            String::new()
+        } else {
+            format!("{}└ ", "  ".repeat(depth - 1))
+        };
+        let index = format!("{:>2}", i + 1).cyan();
         let status = if todo.done {
-            " ✓ ".green()
+            "  ✓   ".green()
+        } else if todo.inbox {
+            "  ✉   ".blue()
+        } else if todo.is_blocked() {
+            "  ⊘   ".red()
+        } else if todo.is_waiting() {
+            "  ◷   ".cyan()
+        } else {
+            "  ○   ".yellow()
+        };
+
+        // 💬 takes two terminal columns, so the badge is one wider than its char count
+        let badge = comment_badge(todo);
+        let text_width = if badge.is_empty() {
+            title_width
         } else {
-            " ○ ".yellow()
+            title_width.saturating_sub(badge.chars().count() + 1)
         };
+        let text_width = text_width.saturating_sub(branch.chars().count()).max(1);
 
+        let truncated = truncate_str(&shorten_urls(&todo.title), text_width);
+        let padded = linkify(&format!("{:text_width$}", truncated), &todo.title);
         let title = if todo.done {
-            let truncated = truncate_str(&todo.title, title_width);
-            format!("{:title_width$}", truncated)
-                .dimmed()
-                .strikethrough()
+            padded.dimmed().strikethrough()
         } else {
-            let truncated = truncate_str(&todo.title, title_width);
-            format!("{:title_width$}", truncated).normal()
+            padded.normal()
         };
+        let title = format!("{}{}{}", branch.dimmed(), title, badge.dimmed());
+
+        let short_id = todo.short_id().dimmed();
+        let mut row = vec![index.to_string(), short_id.to_string(), status.to_string()];
+        if has_priority {
+            let cell = match todo.priority {
+                Some(p) if !todo.done => color_priority(p, &format!("{:8}", p.as_str())),
+                _ => format!("{:8}", "-").dimmed(),
+            };
+            row.push(cell.to_string());
+        }
+        row.push(title);
+
+        if let Some(width) = project_width {
+            let project = match todo.project {
+                Some(ref name) => format!("{:width$}", format!("@{}", name)).green(),
+                None => format!("{:width$}", "-").dimmed(),
+            };
+            row.push(project.to_string());
+        }
 
-        let created = format_datetime(&todo.created_at).dimmed();
+        if let Some(width) = assignee_width {
+            let assignee = match todo.assigned_to {
+                Some(ref name) => format!("{:width$}", format!("@{}", name)).cyan(),
+                None => format!("{:width$}", "-").dimmed(),
+            };
+            row.push(assignee.to_string());
+        }
+
+        if has_progress {
+            let progress = match todo.progress(todos) {
+                Some((done, total)) => {
+                    let cell = format!("{:10}", format_progress(done, total));
+                    if done == total {
+                        cell.green()
+                    } else {
+                        cell.cyan()
+                    }
+                }
+                None => format!("{:10}", "-").dimmed(),
+            };
+            row.push(progress.to_string());
+        }
+
+        if has_due {
+            let due = match todo.due_at {
+                Some(ref d) if !todo.done => {
+                    let d_str = format_reminder(d);
+                    let marker = if todo.recur.is_some() { "↻" } else { "" };
+                    let cell = format!("{:>time_width$}", format!("{}{}", marker, d_str));
+                    if is_overdue(d) {
+                        cell.red()
+                    } else {
+                        cell.blue()
+                    }
+                }
+                _ => format!("{:>time_width$}", "-").dimmed(),
+            };
+            row.push(due.to_string());
+        }
 
         if has_reminders {
             let remind = if let Some(ref r) = todo.remind_at {
-                let r_str = format_reminder(r);
-                if r_str == "overdue" {
-                    format!("{:>7}", r_str).red()
+                let r_str = format!("{:>time_width$}", format_reminder(r));
+                if is_overdue(r) {
+                    r_str.red()
                 } else {
-                    format!("{:>7}", r_str).magenta()
+                    r_str.magenta()
                 }
             } else {
-                format!("{:>7}", "-").dimmed()
+                format!("{:>time_width$}", "-").dimmed()
             };
+            row.push(remind.to_string());
+        }
 
-            println!(
-                "  {}  {}   {}  {}  {}",
-                index.cyan(),
-                status,
-                title,
-                remind,
-                created
-            );
-        } else {
-            println!(
-                "  {}  {}   {}  {}",
-                index.cyan(),
-                status,
-                title,
-                created
-            );
+        if let Some((warn_days, alert_days)) = age_thresholds {
+            let age = if todo.done {
+                format!("{:>4}", "-").dimmed()
+            } else {
+                let cell = format!("{:>4}", format_age(&todo.created_at));
+                let days = (BsonDateTime::now().timestamp_millis()
+                    - todo.created_at.timestamp_millis())
+                    / 86_400_000;
+                if days >= alert_days {
+                    cell.red()
+                } else if days >= warn_days {
+                    cell.yellow()
+                } else {
+                    cell.dimmed()
+                }
+            };
+            row.push(age.to_string());
         }
+
+        if has_created {
+            row.push(format_datetime(&todo.created_at).dimmed().to_string());
+        }
+
+        writeln!(out, "  {}", row.join("  "))?;
     }
 
-    println!();
+    writeln!(out)?;
 
     // Summary
-    let done_count = todos.iter().filter(|t| t.done).count();
-    let pending_count = todos.len() - done_count;
-    let reminder_count = todos
+    let done_count = visible.iter().filter(|t| t.done).count();
+    let pending_count = visible.len() - done_count;
+    let reminder_count = visible
         .iter()
         .filter(|t| !t.done && t.remind_at.is_some())
         .count();
 
-    print!("  ");
+    write!(out, "  ")?;
     if pending_count > 0 {
-        print!("{} pending", pending_count.to_string().yellow());
+        write!(out, "{} pending", pending_count.to_string().yellow())?;
     }
     if done_count > 0 {
         if pending_count > 0 {
-            print!(" · ");
+            write!(out, " · ")?;
         }
-        print!("{} done", done_count.to_string().green());
+        write!(out, "{} done", done_count.to_string().green())?;
     }
     if reminder_count > 0 {
-        print!(" · {} with reminders", reminder_count.to_string().magenta());
+        write!(out, " · {} with reminders", reminder_count.to_string().magenta())?;
     }
-    println!();
-    println!();
+    // Counted over the whole list so a filtered view still nags about untriaged items
+    let inbox_count = todos.iter().filter(|t| t.inbox && !t.done).count();
+    if inbox_count > 0 {
+        write!(out, " · {} in inbox (todo triage)", inbox_count.to_string().blue())?;
+    }
+    writeln!(out)?;
+    writeln!(out)?;
+    Ok(())
 }
 
 fn truncate_str(s: &str, max_len: usize) -> String {
-    if s.len() <= max_len {
+    if max_len == 0 {
+        String::new()
+    } else if s.chars().count() <= max_len {
         s.to_string()
     } else {
-        format!("{}…", &s[..max_len - 1])
+        let head: String = s.chars().take(max_len - 1).collect();
+        format!("{}…", head)
+    }
+}
+
+/// " 💬3" after a title with comments, nothing otherwise
+fn comment_badge(todo: &Todo) -> String {
+    if todo.comments.is_empty() {
+        String::new()
+    } else {
+        format!(" 💬{}", todo.comments.len())
+    }
+}
+
+/// Render subtask completion as a five-cell bar plus percentage, e.g. `███░░ 60%`
+fn format_progress(done: usize, total: usize) -> String {
+    let percent = done * 100 / total;
+    let filled = done * 5 / total;
+    format!("{}{} {}%", "█".repeat(filled), "░".repeat(5 - filled), percent)
+}
+
+/// Ask a question on stdout and read one trimmed line; `None` means stdin was closed
+pub fn prompt(message: &str) -> Result<Option<String>> {
+    print!("{} {} ", "?".cyan().bold(), message);
+    io::stdout().flush()?;
+
+    let mut line = String::new();
+    if io::stdin().read_line(&mut line)? == 0 {
+        println!();
+        return Ok(None);
     }
+    Ok(Some(line.trim().to_string()))
 }
 
 pub fn print_success(message: &str) {
+    if json_output() {
+        return print_json_message("success", message);
+    }
     println!("{} {}", "✓".green().bold(), message.green());
 }
 
 pub fn print_error(message: &str) {
+    if json_output() {
+        return print_json_message("error", message);
+    }
     println!("{} {}", "✗".red().bold(), message.red());
 }
 
 pub fn print_info(message: &str) {
+    if json_output() {
+        return print_json_message("info", message);
+    }
     println!("{} {}", "→".cyan().bold(), message);
 }
 
 pub fn print_warning(message: &str) {
+    if json_output() {
+        return print_json_message("warning", message);
+    }
     println!("{} {}", "!".yellow().bold(), message.yellow());
 }
 
-pub fn print_added_todo(index: usize, title: &str) {
+pub fn print_added_todo(index: usize, todo: &Todo) {
+    if json_output() {
+        println!("{}", todo_json(index, todo));
+        return;
+    }
     println!(
         "{} Added todo #{}: {}",
         "✓".green().bold(),
         index.to_string().cyan(),
-        title
+        todo.title
     );
 }
 
-pub fn print_usage() {
-    let title = "Todo CLI".cyan().bold();
-    let version = "v0.3.0".dimmed();
-
-    println!("\n{} {}\n", title, version);
-    println!("{}", "USAGE:".yellow().bold());
-    println!("    {} <command> [arguments]\n", "todo".green());
+/// Print every field of a single todo, including its parent and subtasks
+pub fn print_todo_detail(index: usize, todo: &Todo, todos: &[Todo]) {
+    if json_output() {
+        println!("{}", todo_json(index, todo));
+        return;
+    }
+    let status = if todo.done {
+        "done".green()
+    } else {
+        "pending".yellow()
+    };
 
-    println!("{}", "COMMANDS:".yellow().bold());
-    println!("    {}   Add a new todo", "add <title> [-r <time>]".green());
-    println!("    {}                  List all todos", "list".green());
-    println!("    {}                Mark a todo as done", "done <#>".green());
-    println!("    {}              Mark a todo as pending", "undone <#>".green());
-    println!("    {}      Edit a todo's title", "edit <#> <title>".green());
-    println!("    {}      Set/clear a reminder", "remind <#> <time>".green());
-    println!("    {}                  Remove a todo", "rm <#>".green());
-    println!("    {}                 Clear completed todos", "clear".green());
+    println!();
     println!(
-        "    {}                Send due notifications",
-        "notify".green()
+        "  {} {} {}",
+        format!("#{}", index).cyan().bold(),
+        todo.short_id().dimmed(),
+        linkify(&todo.title, &todo.title).bold()
+    );
+    println!("  {}   {}", "Status:".dimmed(), status);
+    println!("  {}  {}", "Created:".dimmed(), format_datetime(&todo.created_at));
+    if let Some(ref d) = todo.done_at {
+        let by = todo.completed_by.as_ref().map(|u| format!(" by {}", u)).unwrap_or_default();
+        println!("  {}     {}{}", "Done:".dimmed(), format_datetime(d), by);
+    }
+
+    if let Some(ref project) = todo.project {
+        println!("  {}  {}", "Project:".dimmed(), format!("@{}", project).green());
+    }
+    if let Some(ref owner) = todo.owner {
+        println!("  {}    {}", "Owner:".dimmed(), owner);
+    }
+    if let Some(ref assignee) = todo.assigned_to {
+        println!("  {} {}", "Assigned:".dimmed(), format!("@{}", assignee).cyan());
+    }
+    if let Some(ref location) = todo.location {
+        println!("  {}    {}", "Where:".dimmed(), location);
+    }
+    if let Some(priority) = todo.priority {
+        println!("  {} {}", "Priority:".dimmed(), color_priority(priority, priority.as_str()));
+    }
+    if let Some(effort) = todo.effort {
+        println!("  {}   {}", "Effort:".dimmed(), effort.as_str());
+    }
+    if !todo.notify_before.is_empty() {
+        let leads: Vec<String> = todo.notify_before.iter().map(|&m| i18n::duration(m)).collect();
+        let next = match todo.notifications.first() {
+            Some(next) => format!(" (next {})", format_reminder(next)),
+            None => String::new(),
+        };
+        println!("  {}   {} before due{}", "Notify:".dimmed(), leads.join(", "), next);
+    }
+    if let Some(minutes) = todo.estimate_minutes {
+        println!("  {} {}", "Estimate:".dimmed(), i18n::duration(minutes));
+    }
+    if let Some(ref reason) = todo.block_reason {
+        let since = todo.blocked_since.as_ref().map(format_datetime).unwrap_or_default();
+        println!("  {}  {} (since {})", "Blocked:".dimmed(), reason.red(), since);
+    }
+    if let Some(ref name) = todo.waiting_on {
+        let since = todo.waiting_since.as_ref().map(format_datetime).unwrap_or_default();
+        println!("  {}  {} (since {})", "Waiting:".dimmed(), name.magenta(), since);
+    }
+
+    if let Some(ref r) = todo.remind_at {
+        println!(
+            "  {} {} ({})",
+            "Reminder:".dimmed(),
+            format_datetime(r),
+            format_reminder(r).magenta()
+        );
+    }
+    if let Some(ref t) = todo.last_notified_at {
+        println!("  {} {}", "Notified:".dimmed(), format_datetime(t));
+    }
+
+    if let Some(ref d) = todo.due_at {
+        println!(
+            "  {}      {} ({})",
+            "Due:".dimmed(),
+            format_datetime(d),
+            format_reminder(d).blue()
+        );
+    }
+
+    if let Some(ref r) = todo.review_at {
+        println!("  {}   {}", "Review:".dimmed(), format_datetime(r));
+    }
+
+    if let Some(ref rule) = todo.recur {
+        let completed = todo
+            .history
+            .iter()
+            .filter(|h| h.kind == HistoryKind::Completed)
+            .count();
+        let skipped = todo.history.len() - completed;
+        println!(
+            "  {}    {} ({} done, {} skipped)",
+            "Recurs:".dimmed(),
+            rule.blue(),
+            completed,
+            skipped
+        );
+    }
+
+    if let Some(ref rule) = todo.remind_every {
+        println!("  {}  {}", "Repeats:".dimmed(), rule.magenta());
+    }
+
+    if !todo.escalation.is_empty() {
+        let steps: Vec<String> = todo
+            .escalation
+            .iter()
+            .map(|s| {
+                let mut step = format!("+{}m {:?}", s.after_minutes, s.urgency).to_lowercase();
+                if let Some(ref backend) = s.backend {
+                    step.push_str(&format!(" via {}", backend));
+                }
+                step
+            })
+            .collect();
+        println!(
+            "  {} {} ({}/{} fired)",
+            "Escalate:".dimmed(),
+            steps.join(", "),
+            todo.escalation_step,
+            todo.escalation.len()
+        );
+    }
+
+    if !todo.tags.is_empty() {
+        let tags: Vec<String> = todo.tags.iter().map(|t| format!("+{}", t)).collect();
+        println!("  {}     {}", "Tags:".dimmed(), tags.join(" ").blue());
+    }
+
+    if let Some(ref link) = todo.git {
+        println!("  {}      {} {}", "Git:".dimmed(), link.branch.cyan(), link.repo.dimmed());
+        if let Some(commit) = git::last_commit(link) {
+            println!("  {}   {}", "Commit:".dimmed(), commit);
+        }
+    }
+
+    if let Some(ref s) = todo.start_at {
+        println!("  {}    {}", "Start:".dimmed(), format_datetime(s));
+    }
+
+    for dep_id in &todo.depends_on {
+        if let Some((i, dep)) = todos.iter().enumerate().find(|(_, t)| t.id == *dep_id) {
+            let mark = if dep.done { "✓".green() } else { "○".yellow() };
+            println!("  {}  {} #{} {}", "Waits on:".dimmed(), mark, i + 1, dep.title);
+        }
+    }
+
+    if let Some(parent_id) = todo.parent_id
+        && let Some((i, parent)) = todos.iter().enumerate().find(|(_, t)| t.id == parent_id)
+    {
+        println!("  {}   #{} {}", "Parent:".dimmed(), i + 1, parent.title);
+    }
+
+    if let Some((done, total)) = todo.progress(todos) {
+        println!(
+            "  {} {} ({}/{})",
+            "Progress:".dimmed(),
+            format_progress(done, total).cyan(),
+            done,
+            total
+        );
+        for (i, child) in todos.iter().enumerate() {
+            if child.parent_id == Some(todo.id) {
+                let mark = if child.done { "✓".green() } else { "○".yellow() };
+                println!("    {} {} {}", mark, format!("#{}", i + 1).cyan(), child.title);
+            }
+        }
+    }
+
+    if let Some(ref notes) = todo.notes {
+        println!();
+        println!("  {}", "📝 Notes".dimmed());
+        for line in notes.lines() {
+            println!("    {}", line);
+        }
+    }
+
+    if !todo.comments.is_empty() {
+        println!();
+        println!("  {}", format!("💬 Comments ({})", todo.comments.len()).dimmed());
+        for comment in &todo.comments {
+            println!(
+                "    {} {}",
+                comment.author.as_deref().unwrap_or("someone").cyan(),
+                format_datetime(&comment.at).dimmed()
+            );
+            println!("    {}", comment.text);
+        }
+    }
+
+    println!();
+}
+
+/// Print each goal with its linked-todo completion and days remaining
+pub fn print_goal_status(goals: &[Goal], todos: &[Todo]) {
+    let days_left = |goal: &Goal| {
+        goal.due_at.map(|due| {
+            (due.timestamp_millis() - BsonDateTime::now().timestamp_millis()).div_euclid(86_400_000)
+        })
+    };
+    if json_output() {
+        let items: Vec<Value> = goals
+            .iter()
+            .enumerate()
+            .map(|(i, goal)| {
+                let linked: Vec<&Todo> =
+                    todos.iter().filter(|t| t.goal_id == Some(goal.id)).collect();
+                json!({
+                    "number": i + 1,
+                    "id": goal.id.to_hex(),
+                    "title": goal.title,
+                    "due_at": bson_to_json(&goal.due_at.map_or(Bson::Null, Bson::DateTime)),
+                    "days_left": days_left(goal),
+                    "todos": linked.len(),
+                    "done": linked.iter().filter(|t| t.done).count(),
+                })
+            })
+            .collect();
+        println!("{}", Value::Array(items));
+        return;
+    }
+    if goals.is_empty() {
+        println!(
+            "{}",
+            "No goals yet. Add one with: todo goal add \"Ship v1\" --due 2025-09-01".yellow()
+        );
+        return;
+    }
+
+    println!();
+    for (i, goal) in goals.iter().enumerate() {
+        let linked: Vec<&Todo> = todos.iter().filter(|t| t.goal_id == Some(goal.id)).collect();
+        let done = linked.iter().filter(|t| t.done).count();
+
+        let progress = if linked.is_empty() {
+            "no todos linked".dimmed()
+        } else if done == linked.len() {
+            format!("{} {}/{}", format_progress(done, linked.len()), done, linked.len()).green()
+        } else {
+            format!("{} {}/{}", format_progress(done, linked.len()), done, linked.len()).cyan()
+        };
+
+        let remaining = match days_left(goal) {
+            Some(days) => {
+                if days < 0 {
+                    format!("{}d overdue", -days).red()
+                } else {
+                    format!("{}d left", days).magenta()
+                }
+            }
+            None => "no due date".dimmed(),
+        };
+
+        println!(
+            "  {}  {}  {}  {}",
+            format!("{:>2}", i + 1).cyan(),
+            goal.title.bold(),
+            progress,
+            remaining
+        );
+    }
+    println!();
+}
+
+/// Title rules with what each one does to a new todo
+pub fn print_rules(rules: &[&TitleRule]) {
+    if json_output() {
+        let items: Vec<Value> = rules
+            .iter()
+            .map(|rule| {
+                json!({
+                    "pattern": rule.pattern,
+                    "tags": rule.tags,
+                    "project": rule.project,
+                    "priority": rule.priority,
+                })
+            })
+            .collect();
+        println!("{}", Value::Array(items));
+        return;
+    }
+    println!();
+    for rule in rules {
+        let mut effects: Vec<String> = rule.tags.iter().map(|t| format!("+{}", t)).collect();
+        if let Some(ref project) = rule.project {
+            effects.push(format!("@{}", project));
+        }
+        if let Some(ref priority) = rule.priority {
+            effects.push(format!("priority {}", priority));
+        }
+        println!(
+            "  {} {} {}",
+            format!("/{}/", rule.pattern).cyan(),
+            "→".dimmed(),
+            effects.join(" ")
+        );
+    }
+    println!();
+}
+
+fn millis(d: Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
+
+/// `--timings` breakdown, on stderr so it never mixes with the command's output
+pub fn print_timings(phases: &[(&str, Duration)], list: (Duration, u32)) {
+    let mut parts: Vec<String> = phases
+        .iter()
+        .map(|(name, d)| format!("{} {:.1}ms", name, millis(*d)))
+        .collect();
+    if list.1 > 0 {
+        parts.push(format!("list loaded {}× in {:.1}ms", list.1, millis(list.0)));
+    }
+    let total: Duration = phases.iter().map(|(_, d)| *d).sum();
+    parts.push(format!("total {:.1}ms", millis(total)));
+    eprintln!("  {}", format!("timings: {}", parts.join(" · ")).dimmed());
+}
+
+/// `todo bench` results: min, median and max of each phase over the runs
+pub fn print_bench(count: usize, runs: usize, phases: &[(&str, Vec<Duration>)]) {
+    let stats = |samples: &[Duration]| {
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        let pick = |i: usize| sorted.get(i).copied().map(millis).unwrap_or_default();
+        (pick(0), pick(sorted.len() / 2), pick(sorted.len().saturating_sub(1)))
+    };
+
+    if json_output() {
+        // Microseconds are as fine as the timings are worth
+        let round = |ms: f64| (ms * 1000.0).round() / 1000.0;
+        let items: Vec<Value> = phases
+            .iter()
+            .map(|(name, samples)| {
+                let (min, median, max) = stats(samples);
+                json!({
+                    "phase": name,
+                    "min_ms": round(min),
+                    "median_ms": round(median),
+                    "max_ms": round(max),
+                })
+            })
+            .collect();
+        println!("{}", json!({ "todos": count, "runs": runs, "phases": items }));
+        return;
+    }
+
+    let header = format!("{:14}  {:>9}  {:>9}  {:>9}", "Phase", "Min", "Median", "Max");
+    println!();
+    println!("  {} todos, {} runs", count.to_string().bold(), runs);
+    println!();
+    println!("  {}", header.dimmed());
+    println!("  {}", "─".repeat(header.chars().count()).dimmed());
+    for (name, samples) in phases {
+        let (min, median, max) = stats(samples);
+        println!(
+            "  {:14}  {:>7.2}ms  {:>7.2}ms  {:>7.2}ms",
+            name, min, median, max
+        );
+    }
+    println!();
+}
+
+/// Holidays with their weekday; past ones dimmed
+pub fn print_holidays(holidays: &[NaiveDate]) {
+    if json_output() {
+        let items: Vec<String> =
+            holidays.iter().map(|d| d.format("%Y-%m-%d").to_string()).collect();
+        println!("{}", json!(items));
+        return;
+    }
+    let today = Local::now().date_naive();
+    println!();
+    for day in holidays {
+        let line = day.format("%Y-%m-%d  %a").to_string();
+        if *day < today {
+            println!("  {}", line.dimmed());
+        } else {
+            println!("  {}", line);
+        }
+    }
+    println!();
+}
+
+/// `todo forecast`: the narrative, then each todo with a due date, when the forecast has it
+/// done, and whether that's in time
+pub fn print_forecast(
+    todos: &[Todo],
+    narrative: &[String],
+    per_day: f64,
+    clear_by: Option<NaiveDate>,
+    rows: &[(usize, BsonDateTime, BsonDateTime)],
+) {
+    let now = BsonDateTime::now();
+    let status = |due: BsonDateTime, done_by: BsonDateTime| {
+        if due < now {
+            "overdue"
+        } else if done_by > due {
+            "at risk"
+        } else {
+            "on track"
+        }
+    };
+    if json_output() {
+        let items: Vec<Value> = rows
+            .iter()
+            .map(|&(i, due, done_by)| {
+                json!({
+                    "number": i + 1,
+                    "id": todos[i].id.to_hex(),
+                    "due": bson_to_json(&Bson::DateTime(due)),
+                    "done_by": bson_to_json(&Bson::DateTime(done_by)),
+                    "status": status(due, done_by),
+                })
+            })
+            .collect();
+        println!(
+            "{}",
+            json!({
+                "per_day": (per_day * 100.0).round() / 100.0,
+                "clear_by": clear_by.map(|d| d.format("%Y-%m-%d").to_string()),
+                "todos": items,
+            })
+        );
+        return;
+    }
+    let day = |dt: &BsonDateTime| {
+        Local
+            .timestamp_millis_opt(dt.timestamp_millis())
+            .single()
+            .map(|d| d.format("%a %m-%d").to_string())
+            .unwrap_or_default()
+    };
+    println!();
+    for line in narrative {
+        println!("  {}", line);
+    }
+    if rows.is_empty() {
+        println!();
+        return;
+    }
+
+    let header = format!("{:>3}  {:<9}  {:<9}  {:<8}  {}", "#", "Due", "Done by", "", "Title");
+    println!();
+    println!("  {}", header.dimmed());
+    println!("  {}", "─".repeat(header.chars().count()).dimmed());
+    for &(i, due, done_by) in rows {
+        let status = match status(due, done_by) {
+            "overdue" => format!("{:<8}", "overdue").red(),
+            "at risk" => format!("{:<8}", "at risk").yellow(),
+            on_track => format!("{:<8}", on_track).green(),
+        };
+        println!(
+            "  {}  {:<9}  {:<9}  {}  {}",
+            format!("{:>3}", i + 1).cyan(),
+            day(&due),
+            day(&done_by),
+            status,
+            todos[i].title
+        );
+    }
+    println!();
+}
+
+/// `todo occurrences`: the next firings of a todo's recurrence and repeating reminder,
+/// each given as its rule and dates
+pub fn print_occurrences(
+    index: usize,
+    title: &str,
+    due: Option<(&str, Vec<DateTime<Local>>)>,
+    reminder: Option<(&str, Vec<DateTime<Local>>)>,
+) {
+    if json_output() {
+        let dates = |series: &Option<(&str, Vec<DateTime<Local>>)>| -> Vec<String> {
+            series
+                .iter()
+                .flat_map(|(_, dates)| dates)
+                .map(|dt| dt.with_timezone(&Utc).to_rfc3339_opts(SecondsFormat::Secs, true))
+                .collect()
+        };
+        println!("{}", json!({ "due": dates(&due), "reminder": dates(&reminder) }));
+        return;
+    }
+    print_info(&format!("Upcoming for #{}: {}", index, title));
+    for (label, series) in [("Due", due), ("Reminder", reminder)] {
+        let Some((rule, dates)) = series else {
+            continue;
+        };
+        println!();
+        println!("  {} {}", label.dimmed(), rule.blue());
+        for (i, dt) in dates.iter().enumerate() {
+            println!(
+                "  {}  {}",
+                format!("{:>2}", i + 1).cyan(),
+                dt.format("%a %Y-%m-%d %H:%M")
+            );
+        }
+    }
+    println!();
+}
+
+/// A day's estimated load against capacity, with what to defer when overbooked
+pub fn print_plan(
+    todos: &[Todo],
+    rows: &[usize],
+    day: &str,
+    booked: i64,
+    capacity: i64,
+    defer: &[usize],
+) {
+    let unestimated = rows.iter().filter(|&&i| todos[i].estimate_minutes.is_none()).count();
+    if json_output() {
+        let items: Vec<Value> = rows.iter().map(|&i| todo_json(i + 1, &todos[i])).collect();
+        let defer: Vec<usize> = defer.iter().map(|&i| i + 1).collect();
+        println!(
+            "{}",
+            json!({
+                "day": day,
+                "booked_minutes": booked,
+                "capacity_minutes": capacity,
+                "unestimated": unestimated,
+                "todos": items,
+                "defer": defer,
+            })
+        );
+        return;
+    }
+    println!();
+    println!("  {}", day.bold());
+    if rows.is_empty() {
+        println!("  {}", "Nothing due or scheduled.".dimmed());
+        println!();
+        return;
+    }
+
+    for &i in rows {
+        let todo = &todos[i];
+        let estimate = match todo.estimate_minutes {
+            Some(minutes) => format!("{:>8}", i18n::duration(minutes)).normal(),
+            None => format!("{:>8}", "?").dimmed(),
+        };
+        println!("  {}  {}  {}", format!("{:>2}", i + 1).cyan(), estimate, todo.title);
+    }
+
+    let load = format!("{} of {} booked", i18n::duration(booked), i18n::duration(capacity));
+    println!();
+    if booked > capacity {
+        println!(
+            "  {}",
+            format!("⚠ {} — overbooked by {}", load, i18n::duration(booked - capacity)).red()
+        );
+    } else {
+        println!("  {}", load.green());
+    }
+    if unestimated > 0 {
+        println!(
+            "  {}",
+            format!("{} todo(s) without an estimate (todo estimate <#> 1h)", unestimated).dimmed()
+        );
+    }
+
+    if !defer.is_empty() {
+        println!();
+        println!("  {}", "Consider deferring:".yellow());
+        for &i in defer {
+            println!(
+                "    {} {} ({})",
+                format!("#{}", i + 1).cyan(),
+                todos[i].title,
+                i18n::duration(todos[i].estimate_minutes.unwrap_or(0))
+            );
+        }
+    }
+    println!();
+}
+
+/// The focus task, big and alone
+pub fn print_focus(index: usize, todo: &Todo, since: &BsonDateTime) {
+    if json_output() {
+        println!("{}", todo_json(index, todo));
+        return;
+    }
+    let minutes = (BsonDateTime::now().timestamp_millis() - since.timestamp_millis()) / 60_000;
+    let title = format!("  ▶  #{}  {}  ", index, todo.title);
+    let width = title.chars().count();
+
+    println!();
+    println!("  {}", format!("╭{}╮", "─".repeat(width)).cyan());
+    println!("  {}{}{}", "│".cyan(), title.bold(), "│".cyan());
+    println!("  {}", format!("╰{}╯", "─".repeat(width)).cyan());
+    println!("  {}", format!("Focused for {}", i18n::duration(minutes)).dimmed());
+    if let Some(ref d) = todo.due_at {
+        println!("  {}", format!("Due {}", format_datetime(d)).blue());
+    }
+    println!();
+}
+
+/// One-line reminder of the focus task, on stderr so piped output stays clean
+pub fn print_focus_banner(index: usize, todo: &Todo) {
+    eprintln!("{}", format!("▶ Focus: #{} {}", index, todo.title).cyan().dimmed());
+}
+
+/// Delegated todos with who they're waiting on and for how long
+pub fn print_waiting(todos: &[Todo], rows: &[usize]) {
+    if json_output() {
+        return print_json_rows(todos, rows);
+    }
+    if rows.is_empty() {
+        println!(
+            "{}",
+            "Nothing is waiting on anyone. Delegate with: todo delegate <#> <name>".yellow()
+        );
+        return;
+    }
+
+    let now = BsonDateTime::now().timestamp_millis();
+    let title_width = rows
+        .iter()
+        .map(|&i| todos[i].title.chars().count())
+        .max()
+        .unwrap_or(5)
+        .clamp(5, 40);
+    let name_width = rows
+        .iter()
+        .filter_map(|&i| todos[i].waiting_on.as_ref())
+        .map(|n| n.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(10);
+
+    let header = format!(
+        "{:>2}  {:title_width$}  {:name_width$}  {:8}",
+        "#", "Title", "Waiting on", "For"
+    );
+    println!();
+    println!("  {}", header.dimmed());
+    println!("  {}", "─".repeat(header.chars().count()).dimmed());
+
+    for &i in rows {
+        let todo = &todos[i];
+        let waited = todo
+            .waiting_since
+            .map(|since| i18n::duration((now - since.timestamp_millis()) / 60_000))
+            .unwrap_or_else(|| "-".to_string());
+        println!(
+            "  {}  {:title_width$}  {}  {}",
+            format!("{:>2}", i + 1).cyan(),
+            truncate_str(&todo.title, title_width),
+            format!("{:name_width$}", todo.waiting_on.as_deref().unwrap_or_default()).magenta(),
+            waited.yellow()
+        );
+    }
+    println!();
+}
+
+/// Blocked todos with why they're stuck and for how long
+pub fn print_blocked(todos: &[Todo], rows: &[usize]) {
+    if json_output() {
+        return print_json_rows(todos, rows);
+    }
+    if rows.is_empty() {
+        println!("{}", "Nothing is blocked. Mark a todo with: todo block <#> <reason>".yellow());
+        return;
+    }
+
+    let now = BsonDateTime::now().timestamp_millis();
+    println!();
+    for &i in rows {
+        let todo = &todos[i];
+        let stuck = todo
+            .blocked_since
+            .map(|since| i18n::duration((now - since.timestamp_millis()) / 60_000))
+            .unwrap_or_else(|| "-".to_string());
+        println!(
+            "  {}  {}  {}",
+            format!("{:>2}", i + 1).cyan(),
+            todo.title.bold(),
+            format!("stuck {}", stuck).yellow()
+        );
+        let mut why = todo.block_reason.clone().unwrap_or_default();
+        if let Some(ref name) = todo.waiting_on {
+            why.push_str(&format!(" (waiting on {})", name));
+        }
+        println!("      {}", why.dimmed());
+    }
+    println!();
+}
+
+/// `todo import --dry-run`: how the file's fields map onto todos, then what would be
+/// created and skipped
+pub fn print_import_plan(
+    format: &str,
+    mapping: &[(String, String, bool)],
+    plan: &[(&Todo, Option<&str>)],
+) {
+    let creates = plan.iter().filter(|p| p.1.is_none()).count();
+    if json_output() {
+        let fields: Vec<Value> = mapping
+            .iter()
+            .map(|(source, field, used)| json!({ "source": source, "field": field, "used": used }))
+            .collect();
+        let todos: Vec<Value> = plan
+            .iter()
+            .map(|(todo, skip)| {
+                let action = if skip.is_some() { "skip" } else { "create" };
+                json!({ "action": action, "reason": skip, "todo": todo_json(0, todo) })
+            })
+            .collect();
+        println!("{}", json!({ "mapping": fields, "todos": todos }));
+        return;
+    }
+
+    println!();
+    println!("  {}", format!("Field mapping ({})", format).bold());
+    let width = mapping.iter().map(|m| m.0.chars().count()).max().unwrap_or(0);
+    for (source, field, used) in mapping {
+        let field = if *used { field.normal() } else { field.yellow() };
+        println!("    {:width$}  {} {}", source, "→".dimmed(), field);
+    }
+
+    println!();
+    println!(
+        "  {}",
+        format!("Would create {}, skip {}", creates, plan.len() - creates).bold()
+    );
+    for (todo, skip) in plan {
+        let mut notes = Vec::new();
+        if todo.done {
+            notes.push("done".to_string());
+        }
+        if todo.parent_id.is_some() {
+            notes.push("subtask".to_string());
+        }
+        for tag in &todo.tags {
+            notes.push(format!("+{}", tag));
+        }
+        match skip {
+            None => println!("    {} {}  {}", "+".green(), todo.title, notes.join(", ").dimmed()),
+            Some(reason) => println!(
+                "    {} {}  {}",
+                "-".dimmed(),
+                todo.title.dimmed(),
+                format!("({})", reason).dimmed()
+            ),
+        }
+    }
+    println!();
+    println!("{}", "Dry run: nothing was changed. Drop --dry-run to import.".dimmed());
+}
+
+/// `todo lists`: (name, pending, done, in use)
+pub fn print_lists(rows: &[(String, usize, usize, bool)]) {
+    if json_output() {
+        let items: Vec<Value> = rows
+            .iter()
+            .map(|(name, pending, done, in_use)| {
+                json!({ "list": name, "pending": pending, "done": done, "current": in_use })
+            })
+            .collect();
+        println!("{}", Value::Array(items));
+        return;
+    }
+    let name_width = rows.iter().map(|r| r.0.chars().count()).max().unwrap_or(4).max(4);
+
+    let header = format!("  {:name_width$}  {:>7}  {:>5}", "List", "Pending", "Done");
+    println!();
+    println!("  {}", header.dimmed());
+    println!("  {}", "─".repeat(header.chars().count()).dimmed());
+    for (name, pending, done, in_use) in rows {
+        let line = format!("{:name_width$}  {:>7}  {:>5}", name, pending, done);
+        if *in_use {
+            println!("  {} {}", "*".green().bold(), line.green());
+        } else {
+            println!("    {}", line);
+        }
+    }
+    println!();
+}
+
+/// `todo serve token list`
+pub fn print_api_tokens(tokens: &[ApiToken]) {
+    if json_output() {
+        let items: Vec<Value> = tokens
+            .iter()
+            .map(|t| {
+                json!({
+                    "id": t.id,
+                    "name": t.name,
+                    "user": t.user,
+                    "write": t.write,
+                    "lists": t.lists,
+                    "created_at": bson_to_json(&Bson::DateTime(t.created_at)),
+                })
+            })
+            .collect();
+        println!("{}", Value::Array(items));
+        return;
+    }
+    if tokens.is_empty() {
+        print_info("No API tokens; todo serve token add makes one");
+        return;
+    }
+
+    let header = format!(
+        "{:8}  {:12}  {:10}  {:10}  {:16}  {}",
+        "Id", "Name", "User", "Access", "Created", "Lists"
+    );
+    println!();
+    println!("  {}", header.dimmed());
+    println!("  {}", "─".repeat(header.chars().count()).dimmed());
+    for token in tokens {
+        let access = if token.write { "read-write" } else { "read-only" };
+        let lists = match token.lists.is_empty() {
+            true => "all".to_string(),
+            false => token.lists.join(", "),
+        };
+        println!(
+            "  {:8}  {:12}  {:10}  {:10}  {:16}  {}",
+            token.id.yellow(),
+            token.name.as_deref().unwrap_or("-"),
+            token.user.as_deref().unwrap_or("-"),
+            access,
+            format_datetime(&token.created_at),
+            lists
+        );
+    }
+    println!();
+}
+
+/// `todo trash`: deleted todos, numbered for `todo restore --trash`, and when each goes for
+/// good (never when `keep_days` is `None`)
+pub fn print_trash(entries: &[TrashedTodo], keep_days: Option<u64>) {
+    if json_output() {
+        let items: Vec<Value> = entries
+            .iter()
+            .enumerate()
+            .map(|(i, e)| {
+                let mut record = Map::new();
+                let deleted_at = bson_to_json(&Bson::DateTime(e.deleted_at));
+                record.insert("deleted_at".to_string(), deleted_at);
+                if let Value::Object(todo) = todo_json(i + 1, &e.todo) {
+                    record.extend(todo);
+                }
+                Value::Object(record)
+            })
+            .collect();
+        println!("{}", Value::Array(items));
+        return;
+    }
+    if entries.is_empty() {
+        print_info("The trash is empty");
+        return;
+    }
+
+    let header = format!("{:>3}  {:16}  {:16}  {}", "#", "Deleted", "Goes for good", "Title");
+    println!();
+    println!("  {}", header.dimmed());
+    println!("  {}", "─".repeat(header.chars().count()).dimmed());
+    for (i, entry) in entries.iter().enumerate() {
+        let expires = match keep_days {
+            Some(days) => {
+                let millis = entry.deleted_at.timestamp_millis() + days as i64 * 86_400_000;
+                format_datetime(&BsonDateTime::from_millis(millis))
+            }
+            None => "never".to_string(),
+        };
+        println!(
+            "  {:>3}  {:16}  {:16}  {}",
+            (i + 1).to_string().yellow(),
+            format_datetime(&entry.deleted_at),
+            expires.dimmed(),
+            entry.todo.title
+        );
+    }
+    println!();
+    print_info("Bring one back with: todo restore --trash <#>");
+}
+
+/// `todo serve user list`
+pub fn print_serve_users(users: &[ServeUser]) {
+    if json_output() {
+        let items: Vec<Value> = users
+            .iter()
+            .map(|u| {
+                json!({
+                    "name": u.name,
+                    "role": u.role.as_str(),
+                    "lists": u.lists,
+                    "read": u.read,
+                    "created_at": bson_to_json(&Bson::DateTime(u.created_at)),
+                })
+            })
+            .collect();
+        println!("{}", Value::Array(items));
+        return;
+    }
+    if users.is_empty() {
+        print_info("No server users; todo serve user add makes one");
+        return;
+    }
+
+    let header = format!("{:12}  {:7}  {:16}  {}", "Name", "Role", "Created", "Lists");
+    println!();
+    println!("  {}", header.dimmed());
+    println!("  {}", "─".repeat(header.chars().count()).dimmed());
+    for user in users {
+        let lists = match user.role {
+            Role::Admin => "all".to_string(),
+            Role::Member => {
+                let read = user.read.iter().filter(|l| !user.lists.contains(l));
+                let read = read.map(|l| format!("{} (read-only)", l));
+                user.lists.iter().cloned().chain(read).collect::<Vec<_>>().join(", ")
+            }
+        };
+        println!(
+            "  {:12}  {:7}  {:16}  {}",
+            user.name.yellow(),
+            user.role.as_str(),
+            format_datetime(&user.created_at),
+            lists
+        );
+    }
+    println!();
+}
+
+/// `todo sync status`
+pub fn print_sync_status(status: &SyncStatus) {
+    if json_output() {
+        let devices: Vec<Value> = status
+            .devices
+            .iter()
+            .map(|(name, at)| {
+                json!({ "device": name, "last_sync": bson_to_json(&Bson::DateTime(*at)) })
+            })
+            .collect();
+        println!(
+            "{}",
+            json!({
+                "command": status.command,
+                "dir": status.dir,
+                "auto_sync_delay": status.auto_delay,
+                "device": status.device,
+                "devices": devices,
+                "pending": status.pending,
+                "queued": status.queued,
+                "conflicts": status.conflicts,
+            })
+        );
+        return;
+    }
+
+    println!();
+    if let Some(ref dir) = status.dir {
+        println!("  {} {}", "Folder:".bold(), dir);
+    }
+    match status.command {
+        Some(ref command) => println!("  {} {}", "Backend:".bold(), command),
+        None if status.dir.is_some() => {}
+        None => println!("  {} {}", "Backend:".bold(), "none (set sync_command or sync_dir)".dimmed()),
+    }
+    match status.auto_delay {
+        Some(delay) => println!("  {} after {}s of quiet", "Auto-sync:".bold(), delay),
+        None => println!("  {} {}", "Auto-sync:".bold(), "off".dimmed()),
+    }
+
+    println!();
+    if status.devices.is_empty() {
+        println!("  {}", "No device has synced yet".dimmed());
+    }
+    let width = status.devices.iter().map(|d| d.0.chars().count()).max().unwrap_or(0);
+    for (name, at) in &status.devices {
+        let this = status.device.as_deref() == Some(name.as_str());
+        let line = format!("{:width$}  {}", name, format_datetime(at));
+        if this {
+            println!("  {} {}", line.green(), "(this device)".dimmed());
+        } else {
+            println!("  {}", line);
+        }
+    }
+
+    println!();
+    let queued = if status.queued { ", a background sync is queued" } else { "" };
+    match status.pending {
+        0 => println!("  {}{}", "No changes since the last sync".dimmed(), queued),
+        n => println!("  {} change(s) since the last sync{}", n.to_string().yellow(), queued),
+    }
+    if !status.conflicts.is_empty() {
+        println!(
+            "  {} waiting for todo merge in a terminal:",
+            format!("{} conflict(s)", status.conflicts.len()).red()
+        );
+        for conflict in &status.conflicts {
+            println!("    {}", conflict);
+        }
+    }
+    println!();
+}
+
+/// `todo search --everywhere` results: where each todo was found (list, archive or
+/// deleted), its number there, status, title and when it was done or created
+pub fn print_search_results(hits: &[(&str, Option<usize>, &Todo)]) {
+    if json_output() {
+        let items: Vec<Value> = hits
+            .iter()
+            .map(|(source, number, todo)| {
+                // Same record as elsewhere, with the source in front and no number for
+                // deleted todos
+                let mut record = Map::new();
+                record.insert("source".to_string(), json!(source));
+                record.insert("number".to_string(), json!(number));
+                if let Value::Object(fields) = todo_json(number.unwrap_or_default(), todo) {
+                    record.extend(fields.into_iter().filter(|(key, _)| key != "number"));
+                }
+                Value::Object(record)
+            })
+            .collect();
+        println!("{}", Value::Array(items));
+        return;
+    }
+    if hits.is_empty() {
+        println!("{}", "Nothing found in the list, the archive or deleted todos.".yellow());
+        return;
+    }
+
+    println!();
+    for (source, number, todo) in hits {
+        let number = number.map_or("  -".to_string(), |n| format!("{:>3}", n));
+        let checkbox = if todo.done { "[x]" } else { "[ ]" };
+        let when = match todo.done_at {
+            Some(ref d) => format!("done {}", format_datetime(d)),
+            None => format!("added {}", format_datetime(&todo.created_at)),
+        };
+        let source = match *source {
+            "list" => format!("{:8}", source).green(),
+            "archive" => format!("{:8}", source).blue(),
+            _ => format!("{:8}", source).red(),
+        };
+        println!(
+            "  {} {} {} {}  {}",
+            source,
+            number.cyan(),
+            checkbox,
+            todo.title,
+            when.dimmed()
+        );
+    }
+    println!();
+    println!("{}", format!("{} found", hits.len()).dimmed());
+    if hits.iter().any(|h| h.0 == "archive") {
+        println!("{}", "Bring archived todos back with: todo restore <#>".dimmed());
+    }
+    if hits.iter().any(|h| h.0 == "deleted") {
+        println!(
+            "{}",
+            "Deleted todos are kept by the undo journal; todo undo takes back the latest changes"
+                .dimmed()
+        );
+    }
+}
+
+/// Cycle-time table: the first row is the overall figure, the rest are groups.
+/// Groups whose median exceeds the overall median are highlighted as lingering.
+/// One bar per row, scaled so the largest count fills `CHART_WIDTH`
+fn print_bars(rows: &[(String, usize)]) {
+    const CHART_WIDTH: usize = 30;
+    let max = rows.iter().map(|r| r.1).max().unwrap_or(0).max(1);
+    for (label, count) in rows {
+        let cells = (count * CHART_WIDTH).div_ceil(max);
+        let bar = if *count == 0 { "·".dimmed() } else { "█".repeat(cells).green() };
+        println!("  {}  {} {}", label.dimmed(), bar, count);
+    }
+}
+
+/// `todo stats`: completions per day and per week, average completion time, streak and
+/// busiest tags / projects
+pub fn print_productivity(
+    days: &[(NaiveDate, usize)],
+    weeks: &[(NaiveDate, usize)],
+    average: Option<i64>,
+    streak: usize,
+    tags: &[(String, usize)],
+    projects: &[(String, usize)],
+) {
+    if json_output() {
+        let counts = |rows: &[(NaiveDate, usize)], key: &str| -> Vec<Value> {
+            rows.iter()
+                .map(|(d, n)| json!({ key: d.format("%Y-%m-%d").to_string(), "done": n }))
+                .collect()
+        };
+        let groups = |rows: &[(String, usize)]| -> Vec<Value> {
+            rows.iter().map(|(name, n)| json!({ "name": name, "done": n })).collect()
+        };
+        println!(
+            "{}",
+            json!({
+                "per_day": counts(days, "day"),
+                "per_week": counts(weeks, "week"),
+                "average_minutes": average,
+                "streak_days": streak,
+                "busiest_tags": groups(tags),
+                "busiest_projects": groups(projects),
+            })
+        );
+        return;
+    }
+    println!();
+    println!("  {}", "Completed per day".bold());
+    let rows: Vec<(String, usize)> =
+        days.iter().map(|(d, n)| (d.format("%a %m-%d").to_string(), *n)).collect();
+    print_bars(&rows);
+
+    println!();
+    println!("  {}", "Completed per week".bold());
+    let rows: Vec<(String, usize)> =
+        weeks.iter().map(|(d, n)| (d.format("wk %m-%d").to_string(), *n)).collect();
+    print_bars(&rows);
+
+    println!();
+    match average {
+        Some(minutes) => println!("  Average time to done: {}", i18n::duration(minutes).cyan()),
+        None => println!("  Average time to done: {}", "no completion times yet".dimmed()),
+    }
+    let days_word = if streak == 1 { "day" } else { "days" };
+    println!("  Current streak:       {} {}", streak.to_string().cyan(), days_word);
+
+    for (title, rows, prefix) in [("Busiest tags", tags, "+"), ("Busiest projects", projects, "")] {
+        if rows.is_empty() {
+            continue;
+        }
+        let list: Vec<String> =
+            rows.iter().map(|(name, n)| format!("{}{} ({})", prefix, name, n)).collect();
+        println!("  {:<22}{}", format!("{}:", title), list.join(", "));
+    }
+    println!();
+}
+
+pub fn print_cycle_times(rows: &[(String, usize, i64, i64)]) {
+    if json_output() {
+        let items: Vec<Value> = rows
+            .iter()
+            .map(|(label, count, median, p90)| {
+                json!({
+                    "group": label,
+                    "done": count,
+                    "median_minutes": median,
+                    "p90_minutes": p90,
+                })
+            })
+            .collect();
+        println!("{}", Value::Array(items));
+        return;
+    }
+    let overall_median = rows.first().map(|r| r.2).unwrap_or(0);
+    let label_width = rows.iter().map(|r| r.0.chars().count()).max().unwrap_or(5).max(5);
+
+    let header = format!(
+        "{:label_width$}  {:>5}  {:>12}  {:>12}",
+        "Group", "Done", "Median", "p90"
+    );
+    println!();
+    println!("  {}", header.dimmed());
+    println!("  {}", "─".repeat(header.chars().count()).dimmed());
+
+    for (i, (label, count, median, p90)) in rows.iter().enumerate() {
+        let line = format!(
+            "{:label_width$}  {:>5}  {:>12}  {:>12}",
+            label,
+            count,
+            i18n::duration(*median),
+            i18n::duration(*p90)
+        );
+        if i == 0 {
+            println!("  {}", line.bold());
+        } else if *median > overall_median {
+            println!("  {}", line.yellow());
+        } else {
+            println!("  {}", line);
+        }
+    }
+    println!();
+}
+
+/// Projects with pending / done counts and the soonest due date among pending todos
+pub fn print_projects(rows: &[(String, usize, usize, Option<BsonDateTime>)]) {
+    if json_output() {
+        let items: Vec<Value> = rows
+            .iter()
+            .map(|(name, pending, done, next_due)| {
+                let next_due = next_due.map(Bson::DateTime).unwrap_or(Bson::Null);
+                json!({
+                    "project": name,
+                    "pending": pending,
+                    "done": done,
+                    "next_due": bson_to_json(&next_due),
+                })
+            })
+            .collect();
+        println!("{}", Value::Array(items));
+        return;
+    }
+    let name_width = rows.iter().map(|r| r.0.chars().count() + 1).max().unwrap_or(7).max(7);
+
+    let header = format!(
+        "{:name_width$}  {:>7}  {:>5}  {}",
+        "Project", "Pending", "Done", "Next due"
+    );
+    println!();
+    println!("  {}", header.dimmed());
+    println!("  {}", "─".repeat(header.chars().count()).dimmed());
+
+    for (name, pending, done, next_due) in rows {
+        let line = format!(
+            "{:name_width$}  {:>7}  {:>5}",
+            format!("@{}", name),
+            pending,
+            done
+        );
+        let due = match next_due {
+            Some(d) if is_overdue(d) => format_reminder(d).red(),
+            Some(d) => format_reminder(d).blue(),
+            None => "-".dimmed(),
+        };
+        if *pending == 0 {
+            println!("  {}  {}", line.dimmed(), due);
+        } else {
+            println!("  {}  {}", line, due);
+        }
+    }
+    println!();
+}
+
+/// Upcoming reminders in firing order; overdue ones that never fired are flagged in red
+pub fn print_reminder_preview(todos: &[Todo], rows: &[usize], window: &str) {
+    if json_output() {
+        return print_json_rows(todos, rows);
+    }
+    if rows.is_empty() {
+        println!("{}", format!("No reminders in the next {}.", window).yellow());
+        return;
+    }
+
+    let now = BsonDateTime::now().timestamp_millis();
+
+    println!();
+    for &i in rows {
+        let todo = &todos[i];
+        let Some(ref remind_at) = todo.remind_at else {
+            continue;
+        };
+
+        let minutes = (remind_at.timestamp_millis() - now) / 60_000;
+        let countdown = if minutes < 0 {
+            format!("{:>16}", i18n::overdue_by(-minutes)).red()
+        } else {
+            format!("{:>16}", i18n::in_time(minutes)).magenta()
+        };
+
+        println!(
+            "  {}  {}  {}  {}",
+            format!("{:>2}", i + 1).cyan(),
+            countdown,
+            format_datetime(remind_at).dimmed(),
+            todo.title
+        );
+    }
+    println!();
+}
+
+/// Short cheat-sheet shown after first-run setup
+pub fn print_quickstart() {
+    println!("\n{}", "QUICKSTART:".yellow().bold());
+    println!("    {} \"Buy milk\"          Add a todo", "todo add".green());
+    println!("    {} \"Call Sam\" -r 2h    Add with a reminder", "todo add".green());
+    println!("    {}                     See your list", "todo list".green());
+    println!("    {}                   Mark #1 as done", "todo done 1".green());
+    println!("    {}                     Everything else", "todo help".green());
+    println!();
+}
+
+pub fn print_usage() {
+    let title = "Todo CLI".cyan().bold();
+    let version = "v0.3.0".dimmed();
+
+    println!("\n{} {}\n", title, version);
+    println!("{}", "USAGE:".yellow().bold());
+    println!("    {} <command> [arguments]\n", "todo".green());
+
+    println!("{}", "COMMANDS:".yellow().bold());
+    println!("    {}   Add a new todo", "add <title> [-r <time>]".green());
+    println!("    {}    Add from a note; --split for a todo per sentence", "add --from-file <file>".green());
+    println!("    {}            Capture a thought into the inbox", "in <title>".green());
+    println!("    {}         Add from one line: \"report friday 3pm +work !high\"", "quick <text>".green());
+    println!("    {}                File inbox items one by one", "triage".green());
+    println!("    {}                   Full-screen list", "tui".green());
+    println!("    {}                  List all todos", "list".green());
+    println!("    {}         List todos matching a filter", "list [filter]".green());
+    println!("    {}           List todos linked to this repo", "list --repo".green());
+    println!("    {}                  Project view for todo list: .todo/config.toml", "init".green());
+    println!("    {}      List todos with reminders", "list --reminders".green());
+    println!("    {}            Show how long pending todos have waited", "list --age".green());
+    println!("    {}       Only the first 50 matching todos", "list --limit 50".green());
+    println!("    {}  Highest priority last", "list --sort priority --reverse".green());
+    println!("    {}  Only pending (or --done) todos due today", "list --pending --due-today".green());
+    println!("    {}           List todos assigned to you", "list --mine".green());
+    println!("    {}        List archived todos", "list --archived".green());
+    println!("    {} List todos at a location", "list --location <place>".green());
+    println!("    {}    List todos that match your energy", "list --effort low".green());
+    println!("    {}                   Completions per day/week, streak, busiest tags", "stats".green());
+    println!("    {}    Median/p90 time to complete, per tag and repo", "stats --cycle-time".green());
+    println!("    {}       Show a todo as a QR code for your phone", "share <#> --qr".green());
+    println!("    {}                 Plain-text checklist to print", "print".green());
+    println!("    {}  Print pending todos as a restore script", "export --format script".green());
+    println!("    {}  Export every todo to a file", "export --format md|csv|json|todotxt [file]".green());
+    println!("    {}  Due dates and reminders for a calendar", "export --format ics [file]".green());
+    println!("    {}  Import an export, todo.txt or - [ ] checklists", "import [--format md|csv|json|todotxt] <file>".green());
+    println!("    {}  Pack up or put in place your config", "settings export|import <file>".green());
+    println!("    {}  Preview an import without changing anything", "import --dry-run <file>".green());
+    println!("    {}  Apply edited --json output, keyed by id", "apply <patch.json>".green());
+    println!("    {}   Serve a calendar feed and a JSON API for the list", "serve [--port 8080]".green());
+    println!("    {}         Make a read-only (or --write) key for serve", "serve token add".green());
+    println!("    {}   Share serve: an --admin, or a member of --lists", "serve user add <name>".green());
+    println!("    {}                Show a todo in detail", "show <#>".green());
+    println!("    {}  Also search archived and deleted todos", "search <words> --everywhere".green());
+    println!("    {}     Move a todo to a project", "project <#> <name>".green());
+    println!("    {}              Summary of each project", "projects".green());
+    println!("    {}  Skip a date in business-day scheduling", "holidays add <date>".green());
+    println!("    {}  Plan a todo for an ISO week", "sprint assign <#> <week>".green());
+    println!("    {}           This week's sprint and what slipped", "sprint show".green());
+    println!("    {}   Set priority: low, medium, high, critical", "priority <#> <level>".green());
+    println!("    {}     Show which title rules fire", "rules test <title>".green());
+    println!("    {}     Set how much energy a todo takes", "effort <#> <level>".green());
+    println!("    {}     Set how long a todo will take", "estimate <#> <2h>".green());
+    println!("    {}     Compare a day's estimates to your capacity", "plan [--day date]".green());
+    println!("    {}  When the backlog clears, due dates at risk", "forecast [--window 28d]".green());
+    println!("    {}    Leave a comment on a todo", "comment <#> <text>".green());
+    println!("    {}       Set notes, or edit them in $EDITOR", "note <#> [text]".green());
+    println!("    {}                   List todos you can act on now", "ready".green());
+    println!("    {}     Show, set or end your single focus task", "focus [<#>|clear]".green());
+    println!("    {}   Hand a todo to someone and track the wait", "delegate <#> <name>".green());
+    println!("    {}    Mark a todo as stuck, with why", "block <#> [reason]".green());
+    println!("    {}               List blocked todos and how long they've been stuck", "blocked".green());
+    println!("    {}               List delegated todos and how long they've waited", "waiting".green());
+    println!("    {}                Open the first link in a todo", "open <#>".green());
+    println!("    {}                Mark a todo as done", "done <#>".green());
+    println!("    {}               Short ids from the ID column work as well", "done k3f9".green());
+    println!("    {}        Add a subtask under todo #", "sub <#> <title>".green());
+    println!("    {}              Mark a todo as pending", "undone <#>".green());
+    println!("    {}  Complete/reopen every match", "done|undone --all [filter]".green());
+    println!("    {}  Several at once (asks first)", "done|undone <1 3 5|2-6|all>".green());
+    println!("    {}      Edit a todo's title", "edit <#> <title>".green());
+    println!("    {}      Set/clear a reminder", "remind <#> <time>".green());
+    println!("    {}       Reminders due soon", "reminders [24h]".green());
+    println!("    {}      Push a reminder back", "snooze <#> [10m]".green());
+    println!("    {}         Set/clear a due date", "due <#> <when>".green());
+    println!("    {}  Also notify 1 day and 2 hours before", "due <#> <when> --notify 1d,2h".green());
+    println!("    {}       Make a todo recurring", "recur <#> <rule>".green());
+    println!("    {}                Skip a recurring todo's occurrence", "skip <#>".green());
+    println!("    {}    Preview upcoming occurrences", "occurrences <#> [n]".green());
+    println!("    {}     Make a todo wait on others", "depend <#> <#>...".green());
+    println!("    {}      Add (+tag) or remove (-tag) tags", "tag <#> <tags>...".green());
+    println!("    {}                  Walk through stale todos", "review".green());
+    println!("    {}  Merge look-alike pending todos", "dedupe [--threshold 0.85]".green());
+    println!("    {}  Set when to look at a todo again", "review-at <#> <when>".green());
+    println!("    {}              List todos whose review date has passed", "review-due".green());
+    println!("    {}     Link a todo to the current git branch", "git link|unlink <#>".green());
+    println!("    {}  Manage goals (add/status/link/rm)", "goal <command> ...".green());
+    println!("    {}                  Remove a todo (asks first)", "rm <#>".green());
+    println!("    {}   Remove several (asks first)", "rm <2-6|all|filter>".green());
+    println!("    {}  Delete without asking, or only preview", "rm --force|--dry-run".green());
+    println!("    {}                 Clear completed todos", "clear".green());
+    println!(
+        "    {}  Clear by scope",
+        "clear [--all] [--before 30d] [--tag x]".green()
+    );
+    println!("    {}  Clear todos done over 30 days ago", "clear --older-than 30d".green());
+    println!("    {}     Permanently delete done/everything", "purge --done|--all".green());
+    println!("    {}           Put todos away without deleting them", "archive <#>".green());
+    println!("    {}           Bring archived todos back", "restore <#>".green());
+    println!("    {}                 Deleted todos; restore --trash <#> brings them back", "trash".green());
+    println!("    {}              Take back the last change(s)", "undo [n]".green());
+    println!("    {}              Apply an undone change again", "redo [n]".green());
+    println!(
+        "    {}                Send due notifications",
+        "notify".green()
+    );
+    println!("    {}  Send notifications on a timer", "daemon [--interval 1m] [--detach]".green());
+    println!("    {}                  Snapshot or log to sync_dir, run sync_command", "sync".green());
+    println!("    {}           Last sync per device, pending changes", "sync status".green());
+    println!("    {}     Push to and pull from a CalDAV task list", "sync caldav <url>".green());
+    println!("    {}    Push to and pull from a Google Tasks list", "sync gtasks [list]".green());
+    println!("    {}     Merge in another copy, asking about conflicts", "merge <db|folder>".green());
+    println!("    {}      Time loading and listing this database", "bench [--runs 5]".green());
+    println!("    {}                 Named lists with their counts", "lists".green());
+    println!("    {}            Switch to a named list", "use <name>".green());
+    println!("    {}                  Show this help", "help".green());
+
+    println!("\n{}", "GLOBAL OPTIONS:".yellow().bold());
+    println!("    {}           Refuse any change to the database", "--read-only".green());
+    println!("    {}                Machine-readable JSON output", "--json".green());
+    println!("    {}             Report where the command spent its time", "--timings".green());
+    println!("    {}           Use this database file instead", "--db <path>".green());
+    println!("    {}         Use a named list for this command", "--list <name>".green());
+
+    println!("\n{}", "ALIASES:".yellow().bold());
+    println!(
+        "    {}  {}  {}  {}  {}",
+        "a → add".dimmed(),
+        "l, ls → list".dimmed(),
+        "d → done".dimmed(),
+        "e → edit".dimmed(),
+        "x → rm".dimmed()
     );
-    println!("    {}                  Show this help", "help".green());
 
     println!("\n{}", "REMINDER FORMATS:".yellow().bold());
     println!("    {}            15 minutes from now", "15m".dimmed());
@@ -228,12 +1929,20 @@ pub fn print_usage() {
     println!("    {}             1 week from now", "1w".dimmed());
     println!("    {}           At 2:30 PM today/tomorrow", "14:30".dimmed());
     println!("    {}        Tomorrow same time", "tomorrow".dimmed());
+    println!("    {}      Repeats until done", "every monday 9am".dimmed());
 
     println!("\n{}", "EXAMPLES:".yellow().bold());
     println!("    {} \"Buy groceries\"", "todo add".dimmed());
     println!("    {} \"Meeting\" -r 2h", "todo add".dimmed());
+    println!("    {} \"Write tests\" --parent 1", "todo add".dimmed());
+    println!("    {} \"Deploy\" --after 2 --start 2025-09-01", "todo add".dimmed());
+    println!("    {} \"Water plants\" --every \"monday 9am\"", "todo add".dimmed());
+    println!("    {} \"Ship v1\" --due 2025-09-01", "todo goal add".dimmed());
+    println!("    {} tag:work status:pending", "todo list".dimmed());
     println!("    {} 1 15m", "todo remind".dimmed());
     println!("    {} 1 clear", "todo remind".dimmed());
+    println!("    {} --snooze-all 30m", "todo snooze".dimmed());
+    println!("    {} 1 9:00 --escalate 30m:high,1h:critical:push", "todo remind".dimmed());
 
     println!("\n{}", "NOTIFICATIONS:".yellow().bold());
     println!("    Run {} periodically via cron or launchd", "todo notify".dimmed());
@@ -0,0 +1,225 @@
+use std::collections::HashSet;
+
+use anyhow::{anyhow, Result};
+use chrono::{Local, NaiveDate};
+use polodb_core::bson::{oid::ObjectId, DateTime as BsonDateTime};
+
+use crate::config::Config;
+use crate::models::{Effort, Priority, Todo};
+use crate::remind::to_local;
+
+/// A list filter built from terms like `tag:work`, `+work`, `status:done`, `@work` or plain words
+#[derive(Debug, Default, Clone)]
+pub struct Filter {
+    pub tags: Vec<String>,
+    pub done: Option<bool>,
+    pub has_reminder: Option<bool>,
+    /// Todos assigned to this person, or owned by them and not handed to anyone else
+    pub assignee: Option<String>,
+    pub project: Option<String>,
+    /// Case-insensitive substring of the todo's location
+    pub location: Option<String>,
+    pub effort: Option<Effort>,
+    /// Todos due on this (local) day
+    pub due_on: Option<NaiveDate>,
+    pub words: Vec<String>,
+}
+
+impl Filter {
+    pub fn parse(terms: &[String]) -> Result<Self> {
+        let mut filter = Self::default();
+
+        for term in terms {
+            if let Some(tag) = term.strip_prefix("tag:").or_else(|| term.strip_prefix('+')) {
+                filter.tags.push(tag.to_string());
+            } else if let Some(name) = term.strip_prefix("assignee:") {
+                filter.assignee = Some(name.to_string());
+            } else if let Some(project) = term
+                .strip_prefix("project:")
+                .or_else(|| term.strip_prefix('@'))
+            {
+                filter.project = Some(project.to_string());
+            } else if let Some(location) = term.strip_prefix("location:") {
+                filter.location = Some(location.to_lowercase());
+            } else if let Some(effort) = term.strip_prefix("effort:") {
+                filter.effort = Some(parse_effort(effort)?);
+            } else if let Some(day) = term.strip_prefix("due:") {
+                let today = Local::now().date_naive();
+                filter.due_on = Some(match day {
+                    "today" => today,
+                    "tomorrow" => today.succ_opt().unwrap_or(today),
+                    day => NaiveDate::parse_from_str(day, "%Y-%m-%d").map_err(|_| {
+                        anyhow!(
+                            "Unknown due day '{}'. Use due:today, due:tomorrow or due:2025-06-30",
+                            day
+                        )
+                    })?,
+                });
+            } else if let Some(status) = term.strip_prefix("status:") {
+                filter.done = match status {
+                    "done" => Some(true),
+                    "pending" => Some(false),
+                    other => {
+                        return Err(anyhow!(
+                            "Unknown status '{}'. Use status:pending or status:done",
+                            other
+                        ));
+                    }
+                };
+            } else {
+                filter.words.push(term.to_lowercase());
+            }
+        }
+
+        Ok(filter)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+            && self.done.is_none()
+            && self.has_reminder.is_none()
+            && self.assignee.is_none()
+            && self.project.is_none()
+            && self.location.is_none()
+            && self.effort.is_none()
+            && self.due_on.is_none()
+            && self.words.is_empty()
+    }
+
+    pub fn matches(&self, todo: &Todo) -> bool {
+        if let Some(done) = self.done
+            && todo.done != done
+        {
+            return false;
+        }
+
+        if let Some(has_reminder) = self.has_reminder
+            && todo.remind_at.is_some() != has_reminder
+        {
+            return false;
+        }
+
+        if let Some(ref name) = self.assignee {
+            let responsible = todo.assigned_to.as_ref().or(todo.owner.as_ref());
+            if responsible != Some(name) {
+                return false;
+            }
+        }
+
+        if self.project.is_some() && todo.project != self.project {
+            return false;
+        }
+
+        if self.effort.is_some() && todo.effort != self.effort {
+            return false;
+        }
+
+        if let Some(day) = self.due_on {
+            let due = todo.due_at.as_ref().and_then(to_local).map(|d| d.date_naive());
+            if due != Some(day) {
+                return false;
+            }
+        }
+
+        if let Some(ref location) = self.location {
+            let here = todo.location.as_deref().unwrap_or_default().to_lowercase();
+            if !here.contains(location.as_str()) {
+                return false;
+            }
+        }
+
+        let title = todo.title.to_lowercase();
+        self.tags.iter().all(|tag| todo.tags.contains(tag))
+            && self.words.iter().all(|word| title.contains(word))
+    }
+}
+
+pub fn parse_effort(input: &str) -> Result<Effort> {
+    Effort::parse(input)
+        .ok_or_else(|| anyhow!("Unknown effort '{}'. Use low, medium or high", input))
+}
+
+pub fn parse_priority(input: &str) -> Result<Priority> {
+    Priority::parse(input).ok_or_else(|| {
+        anyhow!("Unknown priority '{}'. Use low, medium, high or critical", input)
+    })
+}
+
+/// Move subtasks right after their parent when it's among the rows, keeping the existing
+/// order among siblings
+pub fn tree_order(todos: &[Todo], rows: &mut [usize]) {
+    fn visit(todos: &[Todo], rows: &[usize], i: usize, ordered: &mut Vec<usize>) {
+        ordered.push(i);
+        for &child in rows.iter().filter(|&&c| todos[c].parent_id == Some(todos[i].id)) {
+            visit(todos, rows, child, ordered);
+        }
+    }
+
+    let visible: HashSet<ObjectId> = rows.iter().map(|&i| todos[i].id).collect();
+    let mut ordered = Vec::with_capacity(rows.len());
+    for &i in rows.iter() {
+        if !todos[i].parent_id.is_some_and(|p| visible.contains(&p)) {
+            visit(todos, rows, i, &mut ordered);
+        }
+    }
+
+    // A parent loop in the data would leave todos unreached; keep the old order then
+    if ordered.len() == rows.len() {
+        rows.copy_from_slice(&ordered);
+    }
+}
+
+/// Order rows (positions in `todos`) by a sort key; list numbers stay attached to each todo
+pub fn sort_rows(todos: &[Todo], rows: &mut [usize], key: &str, config: &Config) -> Result<()> {
+    match key {
+        // list_all already returns pending first, by priority, oldest first
+        "default" => {
+            rows.sort_unstable();
+            tree_order(todos, rows);
+        }
+        "created" => rows.sort_by_key(|&i| todos[i].created_at.timestamp_millis()),
+        "title" => rows.sort_by_key(|&i| todos[i].title.to_lowercase()),
+        // Highest first, unset counting as medium, as in the default order
+        "priority" => rows.sort_by_key(|&i| {
+            std::cmp::Reverse(todos[i].priority.unwrap_or(Priority::Medium))
+        }),
+        // Todos without a due date / reminder go last
+        "due" => rows.sort_by_key(|&i| {
+            todos[i]
+                .due_at
+                .map(|d| d.timestamp_millis())
+                .unwrap_or(i64::MAX)
+        }),
+        "remind" => rows.sort_by_key(|&i| {
+            todos[i]
+                .remind_at
+                .map(|r| r.timestamp_millis())
+                .unwrap_or(i64::MAX)
+        }),
+        // Most recently completed first; pending todos go last
+        "done" => rows.sort_by_key(|&i| {
+            let done_at = todos[i].done_at.filter(|_| todos[i].done);
+            std::cmp::Reverse(done_at.map(|d| d.timestamp_millis()))
+        }),
+        // Most urgent first; done todos score as nothing
+        "urgency" => {
+            let now = BsonDateTime::now();
+            let score = |i: usize| {
+                if todos[i].done {
+                    f64::MIN
+                } else {
+                    todos[i].urgency(now, config.effort_weight)
+                }
+            };
+            rows.sort_by(|&a, &b| score(b).total_cmp(&score(a)));
+        }
+        other => {
+            return Err(anyhow!(
+                "Unknown sort '{}'. Use default, created, title, due, remind, priority, done or \
+                 urgency",
+                other
+            ));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use anyhow::{anyhow, Result};
+use std::path::Path;
+use std::process::Command;
+
+use crate::models::GitLink;
+
+fn git(dir: Option<&Path>, args: &[&str]) -> Option<String> {
+    let mut cmd = Command::new("git");
+    if let Some(dir) = dir {
+        cmd.arg("-C").arg(dir);
+    }
+    let output = cmd.args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let out = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!out.is_empty()).then_some(out)
+}
+
+/// Top-level directory of the git repository containing the working directory
+pub fn current_repo() -> Option<String> {
+    git(None, &["rev-parse", "--show-toplevel"])
+}
+
+/// Link for the repository and branch the working directory is on
+pub fn current_link() -> Result<GitLink> {
+    let repo = current_repo().ok_or_else(|| anyhow!("Not inside a git repository"))?;
+    let branch = git(None, &["rev-parse", "--abbrev-ref", "HEAD"])
+        .ok_or_else(|| anyhow!("Could not determine the current branch"))?;
+    Ok(GitLink { repo, branch })
+}
+
+/// Short hash and subject of the latest commit on a linked branch, if still reachable
+pub fn last_commit(link: &GitLink) -> Option<String> {
+    git(
+        Some(Path::new(&link.repo)),
+        &["log", "-1", "--format=%h %s", &link.branch, "--"],
+    )
+}
@@ -0,0 +1,103 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use anyhow::{anyhow, Context, Result};
+
+/// What came back from a request
+pub struct Response {
+    pub status: u16,
+    headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Response {
+    /// A response header, matched without regard to case
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+/// A value quoted for a curl config file
+fn quote(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+        .replace('\t', "\\t");
+    format!("\"{}\"", escaped)
+}
+
+/// Send a request with `curl`, which brings TLS and proxies along. Everything, headers
+/// and body included, goes to curl on stdin, so tokens never show up in `ps`. Credentials
+/// for a host can live in `~/.netrc`.
+pub fn request(
+    method: &str,
+    url: &str,
+    headers: &[(&str, &str)],
+    body: Option<&str>,
+) -> Result<Response> {
+    let mut config = vec![
+        "silent".to_string(),
+        "show-error".to_string(),
+        "include".to_string(),
+        "netrc-optional".to_string(),
+        format!("request = {}", quote(method)),
+        format!("url = {}", quote(url)),
+        // No "100 Continue" round trip before the body
+        format!("header = {}", quote("Expect:")),
+    ];
+    for (name, value) in headers {
+        config.push(format!("header = {}", quote(&format!("{}: {}", name, value))));
+    }
+    if let Some(body) = body {
+        config.push(format!("data-binary = {}", quote(body)));
+    }
+
+    let mut child = Command::new("curl")
+        .args(["--config", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("failed to run curl; is it installed?")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(config.join("\n").as_bytes())
+            .context("failed to send the request to curl")?;
+    }
+    let output = child.wait_with_output().context("failed to run curl")?;
+    if !output.status.success() {
+        let error = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!("{} {} failed: {}", method, url, error.trim()));
+    }
+
+    let text = String::from_utf8_lossy(&output.stdout).replace("\r\n", "\n");
+    let mut rest = text.as_str();
+    // Skip interim responses ("100 Continue") and a proxy's handshake to the final one
+    loop {
+        let (head, body) = rest.split_once("\n\n").unwrap_or((rest, ""));
+        let mut lines = head.lines();
+        let status: u16 = lines
+            .next()
+            .and_then(|line| line.split_whitespace().nth(1))
+            .and_then(|code| code.parse().ok())
+            .ok_or_else(|| anyhow!("{} {}: no response", method, url))?;
+        if body.starts_with("HTTP/") {
+            rest = body;
+            continue;
+        }
+        let headers = lines
+            .filter_map(|line| line.split_once(':'))
+            .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+            .collect();
+        return Ok(Response { status, headers, body: body.to_string() });
+    }
+}
@@ -0,0 +1,128 @@
+use std::sync::OnceLock;
+
+use anyhow::{anyhow, Result};
+
+/// How relative times are spelled: `1h 25m` or `1 hour 25 minutes`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeStyle {
+    Compact,
+    Verbose,
+}
+
+/// Translated strings for one locale. `{}` in a template is replaced by a duration.
+struct Strings {
+    now: &'static str,
+    overdue: &'static str,
+    in_time: &'static str,
+    overdue_by: &'static str,
+    /// Singular and plural names for days, hours and minutes (verbose style)
+    days: (&'static str, &'static str),
+    hours: (&'static str, &'static str),
+    minutes: (&'static str, &'static str),
+}
+
+const EN: Strings = Strings {
+    now: "now",
+    overdue: "overdue",
+    in_time: "in {}",
+    overdue_by: "overdue {}",
+    days: ("day", "days"),
+    hours: ("hour", "hours"),
+    minutes: ("minute", "minutes"),
+};
+
+const ES: Strings = Strings {
+    now: "ahora",
+    overdue: "vencido",
+    in_time: "en {}",
+    overdue_by: "vencido hace {}",
+    days: ("día", "días"),
+    hours: ("hora", "horas"),
+    minutes: ("minuto", "minutos"),
+};
+
+const DE: Strings = Strings {
+    now: "jetzt",
+    overdue: "überfällig",
+    in_time: "in {}",
+    overdue_by: "seit {} überfällig",
+    days: ("Tag", "Tagen"),
+    hours: ("Stunde", "Stunden"),
+    minutes: ("Minute", "Minuten"),
+};
+
+static SETTINGS: OnceLock<(&'static Strings, TimeStyle)> = OnceLock::new();
+
+/// Select the locale ("en", "es", "de") and time style ("compact", "verbose") for this run
+pub fn init(locale: &str, style: &str) -> Result<()> {
+    let strings = match locale.split(['_', '-', '.']).next().unwrap_or_default() {
+        "en" | "" => &EN,
+        "es" => &ES,
+        "de" => &DE,
+        other => return Err(anyhow!("Unsupported locale '{}'. Use en, es or de", other)),
+    };
+    let style = match style {
+        "compact" => TimeStyle::Compact,
+        "verbose" => TimeStyle::Verbose,
+        other => {
+            return Err(anyhow!(
+                "Unknown time_style '{}'. Use compact or verbose",
+                other
+            ));
+        }
+    };
+    let _ = SETTINGS.set((strings, style));
+    Ok(())
+}
+
+fn settings() -> (&'static Strings, TimeStyle) {
+    SETTINGS.get().copied().unwrap_or((&EN, TimeStyle::Compact))
+}
+
+pub fn now() -> &'static str {
+    settings().0.now
+}
+
+pub fn overdue() -> &'static str {
+    settings().0.overdue
+}
+
+/// A positive duration in its two largest units: `1h 25m` / `1 hour 25 minutes`
+pub fn duration(minutes: i64) -> String {
+    let (strings, style) = settings();
+    let minutes = minutes.max(0);
+    if minutes == 0 {
+        return match style {
+            TimeStyle::Compact => "0m".to_string(),
+            TimeStyle::Verbose => format!("0 {}", strings.minutes.1),
+        };
+    }
+
+    let parts = [
+        (minutes / 1440, "d", strings.days),
+        (minutes % 1440 / 60, "h", strings.hours),
+        (minutes % 60, "m", strings.minutes),
+    ];
+
+    parts
+        .iter()
+        .skip_while(|(n, _, _)| *n == 0)
+        .take(2)
+        .filter(|(n, _, _)| *n > 0)
+        .map(|(n, short, (one, many))| match style {
+            TimeStyle::Compact => format!("{}{}", n, short),
+            TimeStyle::Verbose => format!("{} {}", n, if *n == 1 { one } else { many }),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// "in 1h 25m"
+pub fn in_time(minutes: i64) -> String {
+    settings().0.in_time.replace("{}", &duration(minutes))
+}
+
+/// "overdue 3h"
+pub fn overdue_by(minutes: i64) -> String {
+    settings().0.overdue_by.replace("{}", &duration(minutes))
+}
@@ -0,0 +1,272 @@
+use chrono::{Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use polodb_core::bson::DateTime as BsonDateTime;
+
+use crate::models::Todo;
+use crate::remind::parse_reminder;
+
+/// UTC timestamp in iCalendar form: 20250901T090000Z
+fn format_utc(dt: &BsonDateTime) -> String {
+    Utc.timestamp_millis_opt(dt.timestamp_millis())
+        .single()
+        .unwrap_or_default()
+        .format("%Y%m%dT%H%M%SZ")
+        .to_string()
+}
+
+/// Escape a TEXT value (RFC 5545 §3.3.11)
+fn escape_text(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Fold a content line to 75 octets, continuing with a leading space
+fn fold(line: &str) -> String {
+    let mut out = String::new();
+    let mut len = 0;
+    for c in line.chars() {
+        if len + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            len = 1;
+        }
+        out.push(c);
+        len += c.len_utf8();
+    }
+    out.push_str("\r\n");
+    out
+}
+
+/// An alarm going off at a pending todo's reminder
+fn alarm(todo: &Todo) -> Vec<String> {
+    match todo.remind_at {
+        Some(ref remind) if !todo.done => vec![
+            "BEGIN:VALARM".to_string(),
+            "ACTION:DISPLAY".to_string(),
+            format!("DESCRIPTION:{}", escape_text(&todo.title)),
+            format!("TRIGGER;VALUE=DATE-TIME:{}", format_utc(remind)),
+            "END:VALARM".to_string(),
+        ],
+        _ => Vec::new(),
+    }
+}
+
+/// Calendar with a VTODO for every todo that has a due date or a reminder, and a VEVENT
+/// for the pending ones; reminders become alarms on both
+pub fn render_calendar(todos: &[Todo]) -> String {
+    let stamp = format_utc(&BsonDateTime::now());
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//rust-todo//todo//EN".to_string(),
+        "X-WR-CALNAME:todo".to_string(),
+    ];
+
+    for todo in todos {
+        // The event sits at the due time, or at the reminder for todos with no due date
+        let (at, kind) = match (todo.due_at, todo.remind_at) {
+            (Some(due), _) => (due, "due"),
+            (None, Some(remind)) => (remind, "remind"),
+            (None, None) => continue,
+        };
+        lines.extend(vtodo(todo, &uid(todo), &stamp));
+
+        // Most calendar apps ignore VTODO, so pending todos also appear as an event
+        if !todo.done {
+            lines.push("BEGIN:VEVENT".to_string());
+            lines.push(format!("UID:{}-{}@rust-todo", todo.id.to_hex(), kind));
+            lines.push(format!("DTSTAMP:{}", stamp));
+            lines.push(format!("DTSTART:{}", format_utc(&at)));
+            lines.push("DURATION:PT30M".to_string());
+            lines.push(format!("SUMMARY:{}", escape_text(&todo.title)));
+            lines.extend(todo.location.as_ref().map(|l| format!("LOCATION:{}", escape_text(l))));
+            lines.extend(alarm(todo));
+            lines.push("END:VEVENT".to_string());
+        }
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+    lines.iter().map(|l| fold(l)).collect()
+}
+
+/// The UID a todo goes by in calendars it's exported or synced to
+pub fn uid(todo: &Todo) -> String {
+    format!("{}@rust-todo", todo.id.to_hex())
+}
+
+/// A todo as a VTODO component
+fn vtodo(todo: &Todo, uid: &str, stamp: &str) -> Vec<String> {
+    let mut lines = vec![
+        "BEGIN:VTODO".to_string(),
+        format!("UID:{}", escape_text(uid)),
+        format!("DTSTAMP:{}", stamp),
+        format!("CREATED:{}", format_utc(&todo.created_at)),
+        format!("SUMMARY:{}", escape_text(&todo.title)),
+    ];
+    lines.extend(todo.location.as_ref().map(|l| format!("LOCATION:{}", escape_text(l))));
+    if let Some(ref due) = todo.due_at {
+        lines.push(format!("DUE:{}", format_utc(due)));
+    }
+    if todo.done {
+        lines.push("STATUS:COMPLETED".to_string());
+        if let Some(ref done_at) = todo.done_at {
+            lines.push(format!("COMPLETED:{}", format_utc(done_at)));
+        }
+    } else {
+        lines.push("STATUS:NEEDS-ACTION".to_string());
+    }
+    if !todo.tags.is_empty() {
+        let tags: Vec<String> = todo.tags.iter().map(|t| escape_text(t)).collect();
+        lines.push(format!("CATEGORIES:{}", tags.join(",")));
+    }
+    lines.extend(alarm(todo));
+    lines.push("END:VTODO".to_string());
+    lines
+}
+
+/// A calendar object holding just one todo, as a CalDAV server stores it; `uid` keeps
+/// the one the server already knows it by
+pub fn render_vtodo(todo: &Todo, uid: &str) -> String {
+    let lines = [
+        vec![
+            "BEGIN:VCALENDAR".to_string(),
+            "VERSION:2.0".to_string(),
+            "PRODID:-//rust-todo//todo//EN".to_string(),
+        ],
+        vtodo(todo, uid, &format_utc(&BsonDateTime::now())),
+        vec!["END:VCALENDAR".to_string()],
+    ];
+    lines.concat().iter().map(|l| fold(l)).collect()
+}
+
+/// What `parse_vtodo` reads from a calendar object
+pub struct VTodo {
+    pub uid: String,
+    pub summary: String,
+    pub completed: bool,
+    pub due: Option<BsonDateTime>,
+    /// The first alarm, wherever its trigger points
+    pub alarm: Option<BsonDateTime>,
+}
+
+fn unescape_text(s: &str) -> String {
+    let mut out = String::new();
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => {}
+        }
+    }
+    out
+}
+
+/// A DATE or DATE-TIME value: UTC with a trailing Z, otherwise local time (TZID isn't
+/// looked up); a bare date is taken at reminder_time, as everywhere else
+fn parse_time(value: &str) -> Option<BsonDateTime> {
+    let value = value.trim();
+    if let Some(utc) = value.strip_suffix('Z') {
+        let at = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some(BsonDateTime::from_millis(at.and_utc().timestamp_millis()));
+    }
+    if let Ok(at) = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S") {
+        let local = Local.from_local_datetime(&at).earliest()?;
+        return Some(BsonDateTime::from_millis(local.timestamp_millis()));
+    }
+    let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+    parse_reminder(&date.format("%Y-%m-%d").to_string()).ok()
+}
+
+/// A DURATION value (RFC 5545 §3.3.6) in seconds: -PT15M, P1D, -P1DT2H
+fn parse_duration(value: &str) -> Option<i64> {
+    let (sign, rest) = match value.trim().strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, value.trim().trim_start_matches('+')),
+    };
+    let rest = rest.strip_prefix('P')?;
+    let mut seconds = 0;
+    let mut number = String::new();
+    for c in rest.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'T' => {}
+            unit => {
+                let n: i64 = number.parse().ok()?;
+                number.clear();
+                seconds += n * match unit {
+                    'W' => 7 * 86_400,
+                    'D' => 86_400,
+                    'H' => 3_600,
+                    'M' => 60,
+                    'S' => 1,
+                    _ => return None,
+                };
+            }
+        }
+    }
+    Some(sign * seconds)
+}
+
+/// The first VTODO in a calendar object, or None if it holds none. An alarm set relative to
+/// the task counts from its due time, or its start if it has no due time.
+pub fn parse_vtodo(text: &str) -> Option<VTodo> {
+    // Unfold continuation lines first
+    let text = text.replace("\r\n", "\n").replace("\n ", "").replace("\n\t", "");
+    let mut found = None;
+    let mut start = None;
+    let mut triggers = Vec::new();
+    let mut in_alarm = false;
+    for line in text.lines() {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let (name, params) = name.split_once(';').unwrap_or((name, ""));
+        match (name.to_uppercase().as_str(), value) {
+            ("BEGIN", "VTODO") if found.is_none() => {
+                found = Some(VTodo {
+                    uid: String::new(),
+                    summary: String::new(),
+                    completed: false,
+                    due: None,
+                    alarm: None,
+                });
+            }
+            ("END", "VTODO") if found.is_some() => break,
+            ("BEGIN", "VALARM") => in_alarm = true,
+            ("END", "VALARM") => in_alarm = false,
+            (name, value) => {
+                let Some(todo) = found.as_mut() else {
+                    continue;
+                };
+                match name {
+                    "TRIGGER" if in_alarm => {
+                        triggers.push((params.to_string(), value.to_string()));
+                    }
+                    _ if in_alarm => {}
+                    "UID" => todo.uid = unescape_text(value),
+                    "SUMMARY" => todo.summary = unescape_text(value),
+                    "STATUS" => todo.completed = value.eq_ignore_ascii_case("COMPLETED"),
+                    "DUE" => todo.due = parse_time(value),
+                    "DTSTART" => start = parse_time(value),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    let mut todo = found?;
+    todo.alarm = triggers.iter().find_map(|(params, value)| {
+        if params.to_uppercase().contains("VALUE=DATE-TIME") {
+            return parse_time(value);
+        }
+        let anchor = todo.due.or(start)?;
+        let offset = parse_duration(value)?;
+        Some(BsonDateTime::from_millis(anchor.timestamp_millis() + offset * 1000))
+    });
+    Some(todo)
+}
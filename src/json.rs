@@ -0,0 +1,141 @@
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, SecondsFormat, TimeZone, Utc};
+use polodb_core::bson::{self, oid::ObjectId, Bson, DateTime as BsonDateTime, Document};
+use serde_json::{Map, Number, Value};
+
+use crate::models::Todo;
+
+/// Plain JSON for a BSON value: ObjectIds as hex strings, dates as RFC 3339 (UTC)
+pub fn bson_to_json(value: &Bson) -> Value {
+    match value {
+        Bson::Null | Bson::Undefined => Value::Null,
+        Bson::Boolean(b) => Value::Bool(*b),
+        Bson::Int32(n) => Value::from(*n),
+        Bson::Int64(n) => Value::from(*n),
+        // Infinity and NaN have no JSON spelling
+        Bson::Double(n) => Number::from_f64(*n).map_or(Value::Null, Value::Number),
+        Bson::String(s) => Value::String(s.clone()),
+        Bson::ObjectId(id) => Value::String(id.to_hex()),
+        Bson::DateTime(dt) => Utc
+            .timestamp_millis_opt(dt.timestamp_millis())
+            .single()
+            .map_or(Value::Null, |utc| {
+                Value::String(utc.to_rfc3339_opts(SecondsFormat::Secs, true))
+            }),
+        Bson::Array(items) => Value::Array(items.iter().map(bson_to_json).collect()),
+        Bson::Document(doc) => Value::Object(
+            doc.iter()
+                .map(|(key, value)| (key.clone(), bson_to_json(value)))
+                .collect(),
+        ),
+        other => Value::String(other.to_string()),
+    }
+}
+
+/// A todo as a JSON object led by its list number and short id, with `_id` spelled `id`
+pub fn todo_json(number: usize, todo: &Todo) -> Value {
+    let mut fields = Map::new();
+    fields.insert("number".to_string(), Value::from(number));
+    fields.insert("short_id".to_string(), Value::String(todo.short_id()));
+    if let Ok(doc) = bson::to_document(todo) {
+        for (key, value) in doc.iter() {
+            let key = if key == "_id" { "id" } else { key.as_str() };
+            fields.insert(key.to_string(), bson_to_json(value));
+        }
+    }
+    Value::Object(fields)
+}
+
+/// Fields (at any depth) holding timestamps, written as RFC 3339 strings
+const DATE_KEYS: &[&str] = &[
+    "created_at",
+    "remind_at",
+    "start_at",
+    "due_at",
+    "done_at",
+    "waiting_since",
+    "blocked_since",
+    "review_at",
+    "notifications",
+    "last_notified_at",
+    "occurrence",
+    "at",
+    "since",
+];
+
+/// Fields holding ObjectIds, written as hex strings
+const ID_KEYS: &[&str] = &["id", "_id", "parent_id", "depends_on", "goal_id", "todo_id"];
+
+/// Back from plain JSON to BSON, restoring dates and ids from the field they sit under
+/// (array items take their array's field name)
+pub fn json_to_bson(key: &str, value: &Value) -> Result<Bson> {
+    Ok(match value {
+        Value::Null => Bson::Null,
+        Value::Bool(b) => Bson::Boolean(*b),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => Bson::Int64(i),
+            None => Bson::Double(n.as_f64().unwrap_or_default()),
+        },
+        Value::String(s) if DATE_KEYS.contains(&key) => {
+            let dt = DateTime::parse_from_rfc3339(s)
+                .with_context(|| format!("{} is not an RFC 3339 timestamp: {}", key, s))?;
+            Bson::DateTime(BsonDateTime::from_millis(dt.timestamp_millis()))
+        }
+        Value::String(s) if ID_KEYS.contains(&key) => Bson::ObjectId(
+            ObjectId::parse_str(s).with_context(|| format!("{} is not a todo id: {}", key, s))?,
+        ),
+        Value::String(s) => Bson::String(s.clone()),
+        Value::Array(items) => Bson::Array(
+            items
+                .iter()
+                .map(|item| json_to_bson(key, item))
+                .collect::<Result<_>>()?,
+        ),
+        Value::Object(map) => {
+            let mut doc = Document::new();
+            for (k, v) in map {
+                doc.insert(k.clone(), json_to_bson(k, v)?);
+            }
+            Bson::Document(doc)
+        }
+    })
+}
+
+/// A todo from the object `todo_json` writes; `number` and `short_id` are ignored and `id`
+/// is kept
+pub fn todo_from_json(value: &Value) -> Result<Todo> {
+    let Bson::Document(mut doc) = json_to_bson("", value)? else {
+        return Err(anyhow!("expected a JSON object for each todo"));
+    };
+    doc.remove("number");
+    doc.remove("short_id");
+    if let Some(id) = doc.remove("id") {
+        doc.insert("_id", id);
+    }
+    if !doc.contains_key("_id") {
+        doc.insert("_id", ObjectId::new());
+    }
+    bson::from_document(doc).context("not a valid todo")
+}
+
+/// `todo` with the fields in `patch` (plain JSON, as `todo_json` writes them) replaced.
+/// `id` and `number` only say which todo the patch is for and are left alone.
+pub fn patch_todo(todo: &Todo, patch: &Map<String, Value>) -> Result<(Todo, Vec<String>)> {
+    let mut doc = bson::to_document(todo).context("failed to encode todo")?;
+    let mut changed = Vec::new();
+    for (key, value) in patch {
+        if matches!(key.as_str(), "id" | "_id" | "number" | "short_id") {
+            continue;
+        }
+        if !doc.contains_key(key) {
+            return Err(anyhow!("unknown field '{}'", key));
+        }
+        let value = json_to_bson(key, value)?;
+        if doc.get(key) != Some(&value) {
+            doc.insert(key.clone(), value);
+            changed.push(key.clone());
+        }
+    }
+    let todo = bson::from_document(doc).context("not a valid todo after the patch")?;
+    Ok((todo, changed))
+}
@@ -0,0 +1,50 @@
+//! The todo store behind the `todo` command, for embedding in other Rust programs.
+//!
+//! ```no_run
+//! use todo_core::{parse_reminder, Todo, TodoDb};
+//!
+//! let db = TodoDb::open_path("todo.db".as_ref(), false)?;
+//! let mut todo = Todo::new("Water the plants".to_string());
+//! todo.remind_at = Some(parse_reminder("tomorrow 9am")?);
+//! db.insert(&todo)?;
+//! for todo in db.list_all()? {
+//!     println!("{} {}", if todo.done { "[x]" } else { "[ ]" }, todo.title);
+//! }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+pub mod config;
+pub mod db;
+pub mod models;
+pub mod remind;
+pub mod schedule;
+
+// The CLI itself; public only so the `todo` binary can reach it
+#[doc(hidden)]
+pub mod commands;
+#[doc(hidden)]
+pub mod display;
+#[doc(hidden)]
+pub mod i18n;
+#[doc(hidden)]
+pub mod setup;
+#[doc(hidden)]
+pub mod sync;
+
+mod filter;
+mod git;
+mod http;
+mod ics;
+mod json;
+mod links;
+mod natural;
+mod normalize;
+mod oplog;
+mod remote;
+mod snapshot;
+mod tar;
+
+pub use config::Config;
+pub use db::TodoDb;
+pub use models::Todo;
+pub use remind::{format_remind_at, is_repeating, parse_reminder, parse_repeating};
@@ -0,0 +1,89 @@
+use anyhow::{anyhow, Result};
+use std::process::Command;
+
+/// Characters that commonly trail a URL in prose but aren't part of it
+const TRAILING_PUNCTUATION: &[char] = &['.', ',', ';', ':', '!', '?', ')', ']', '}', '"', '\''];
+
+/// Find every http(s) URL in a piece of text, in order of appearance
+pub fn find_urls(text: &str) -> Vec<&str> {
+    text.split_whitespace()
+        .filter_map(|word| {
+            let start = word.find("https://").or_else(|| word.find("http://"))?;
+            let url = word[start..].trim_end_matches(TRAILING_PUNCTUATION);
+            let host = url.split_once("://")?.1;
+            (!host.is_empty()).then_some(url)
+        })
+        .collect()
+}
+
+/// Shorten a URL to its domain plus an ellipsis, e.g. `github.com…`
+pub fn shorten_url(url: &str) -> String {
+    let rest = url.split_once("://").map(|(_, r)| r).unwrap_or(url);
+    let (host, path) = match rest.find(['/', '?', '#']) {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, ""),
+    };
+    let host = host.strip_prefix("www.").unwrap_or(host);
+
+    if path.trim_matches('/').is_empty() {
+        host.to_string()
+    } else {
+        format!("{}…", host)
+    }
+}
+
+/// Replace every URL in a title with its shortened form, for tidy table columns
+pub fn shorten_urls(text: &str) -> String {
+    let mut out = text.to_string();
+    for url in find_urls(text) {
+        out = out.replacen(url, &shorten_url(url), 1);
+    }
+    out
+}
+
+/// Wrap `label` in an OSC 8 escape so supporting terminals render it as a clickable link
+pub fn hyperlink(label: &str, url: &str) -> String {
+    format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, label)
+}
+
+/// Make the URLs of `title` clickable inside `rendered`, which may show them shortened.
+/// Links are left as plain text when colors are off (pipes, NO_COLOR).
+pub fn linkify(rendered: &str, title: &str) -> String {
+    if !colored::control::SHOULD_COLORIZE.should_colorize() {
+        return rendered.to_string();
+    }
+
+    let mut out = String::with_capacity(rendered.len());
+    let mut rest = rendered;
+    for url in find_urls(title) {
+        let label = if rest.contains(url) {
+            url.to_string()
+        } else {
+            shorten_url(url)
+        };
+        if let Some(pos) = rest.find(&label) {
+            out.push_str(&rest[..pos]);
+            out.push_str(&hyperlink(&label, url));
+            rest = &rest[pos + label.len()..];
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Open a URL in the default browser using the platform's launcher
+pub fn open_url(url: &str) -> Result<()> {
+    let status = if cfg!(target_os = "macos") {
+        Command::new("open").arg(url).status()
+    } else if cfg!(target_os = "windows") {
+        Command::new("cmd").args(["/C", "start", "", url]).status()
+    } else {
+        Command::new("xdg-open").arg(url).status()
+    }
+    .map_err(|e| anyhow!("Failed to launch browser: {}", e))?;
+
+    if !status.success() {
+        return Err(anyhow!("Browser launcher exited with {}", status));
+    }
+    Ok(())
+}
@@ -1,23 +1,51 @@
-mod commands;
-mod db;
-mod display;
-mod models;
-mod remind;
-
 use std::env;
+use std::time::Instant;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use colored::Colorize;
 
-use commands::{
-    cmd_add, cmd_clear, cmd_done, cmd_edit, cmd_list, cmd_notify, cmd_remind, cmd_remove,
-    cmd_undone,
+use todo_core::commands::{
+    begin_intent, cmd_add, cmd_apply, cmd_archive, cmd_bench, cmd_block, cmd_blocked, cmd_clear,
+    cmd_comment, cmd_daemon, cmd_dedupe, cmd_delegate, cmd_depend, cmd_done, cmd_due, cmd_edit,
+    cmd_effort, cmd_estimate, cmd_export, cmd_focus, cmd_forecast, cmd_git, cmd_goal, cmd_holidays,
+    cmd_import, cmd_inbox_add, cmd_init, cmd_list, cmd_lists, cmd_merge, cmd_note, cmd_notify,
+    cmd_occurrences, cmd_open, cmd_plan, cmd_print, cmd_priority, cmd_project, cmd_projects,
+    cmd_purge, cmd_quick, cmd_ready, cmd_recur, cmd_redo, cmd_remind, cmd_reminders, cmd_remove,
+    cmd_restore, cmd_review, cmd_review_at, cmd_review_due, cmd_rules, cmd_search, cmd_serve,
+    cmd_settings, cmd_share, cmd_show, cmd_skip, cmd_snooze, cmd_sprint, cmd_stats, cmd_sub,
+    cmd_tag, cmd_trash, cmd_triage, cmd_tui, cmd_undo, cmd_undone, cmd_use, cmd_waiting,
+    current_focus, end_intent, is_undoable, record_undo, recover_interrupted,
+};
+use todo_core::config::Config;
+use todo_core::db::TodoDb;
+use todo_core::display::{
+    enable_age_column, enable_json_output, json_output, print_error, print_focus_banner,
+    print_timings, print_usage, print_warning,
 };
-use db::TodoDb;
-use display::{print_error, print_usage};
+use todo_core::{db, i18n, remind, schedule, setup, sync};
 
 fn run() -> Result<()> {
     let mut args: Vec<String> = env::args().skip(1).collect();
+
+    // Global flags may appear anywhere on the command line
+    let read_only = args.iter().any(|a| a == "--read-only");
+    args.retain(|a| a != "--read-only");
+    let json = args.iter().any(|a| a == "--json");
+    args.retain(|a| a != "--json");
+    let timings = args.iter().any(|a| a == "--timings");
+    args.retain(|a| a != "--timings");
+    let db_flag = match args.iter().position(|a| a == "--db") {
+        Some(i) if i + 1 < args.len() => Some(args.drain(i..=i + 1).nth(1).unwrap_or_default()),
+        Some(_) => return Err(anyhow!("--db needs a path, e.g. --db ~/work-todo.db")),
+        None => None,
+    };
+    let list_flag = match args.iter().position(|a| a == "--list") {
+        Some(i) if i + 1 < args.len() => Some(args.drain(i..=i + 1).nth(1).unwrap_or_default()),
+        Some(_) => return Err(anyhow!("--list needs a name, e.g. --list work")),
+        None => None,
+    };
+    let started = Instant::now();
+
     let cmd = args.first().cloned().unwrap_or_else(|| "help".to_string());
 
     if matches!(cmd.as_str(), "help" | "--help" | "-h") {
@@ -31,24 +59,183 @@ fn run() -> Result<()> {
         vec![]
     };
 
-    let db = TodoDb::open()?;
+    // On a new machine, `todo settings import` is the setup
+    if !read_only
+        && db_flag.is_none()
+        && list_flag.is_none()
+        && cmd != "settings"
+        && setup::is_first_run()?
+    {
+        setup::run_wizard()?;
+    }
 
+    let mut config = Config::load()?;
+    config.read_only |= read_only;
+    // Flags beat the environment and config: --db names a file, --list a named list
+    if db_flag.is_some() {
+        config.db_path = db_flag;
+    } else if list_flag.is_some() {
+        config.db_path = None;
+        config.list = list_flag;
+    }
+    config.apply_color();
+    if json {
+        enable_json_output();
+    }
+    remind::set_default_time(&config.reminder_time)?;
+    i18n::init(&config.locale, &config.time_style)?;
+    if config.age_column {
+        enable_age_column(&config);
+    }
+
+    // The daemon opens the database for each check rather than holding it, the
+    // benchmark times opening it, the list commands open other lists' databases, and
+    // `init` and `settings` only read and write config files
     match cmd.as_str() {
-        "add" | "a" => cmd_add(&db, cmd_args),
-        "list" | "ls" | "l" => cmd_list(&db),
-        "done" | "d" => cmd_done(&db, cmd_args),
+        "init" => return cmd_init(cmd_args),
+        "settings" => return cmd_settings(cmd_args),
+        "daemon" => return cmd_daemon(&config, cmd_args),
+        "bench" => return cmd_bench(&config, cmd_args),
+        "lists" => return cmd_lists(&config),
+        "use" => return cmd_use(&config, cmd_args),
+        _ => {}
+    }
+
+    let config_time = started.elapsed();
+    let opening = Instant::now();
+    let db = TodoDb::open(&config)?;
+    schedule::set_calendar(&config.weekend, &db.get_holidays()?)?;
+    let open_time = opening.elapsed();
+    let running = Instant::now();
+
+    recover_interrupted(&db, &config)?;
+
+    if cmd != "focus"
+        && !json_output()
+        && let Ok(Some((index, todo, _))) = current_focus(&db)
+    {
+        print_focus_banner(index, &todo);
+    }
+
+    let command_line: Vec<&str> =
+        std::iter::once(&cmd).chain(&cmd_args).map(String::as_str).collect();
+    let command_line = command_line.join(" ");
+
+    // The list as it was, so `todo undo` can put back whatever this command changes, and
+    // a crash partway through can be rolled back next time
+    let undo_from = if is_undoable(&cmd) && !config.read_only {
+        let before = db.list_all()?;
+        begin_intent(&config, &command_line, &before)?;
+        Some(before)
+    } else {
+        None
+    };
+
+    let result = match cmd.as_str() {
+        "add" | "a" => cmd_add(&db, &config, cmd_args),
+        "in" => cmd_inbox_add(&db, &config, cmd_args),
+        "quick" | "q" => cmd_quick(&db, &config, cmd_args),
+        "triage" => cmd_triage(&db),
+        "tui" => cmd_tui(&db, &config),
+        "list" | "ls" | "l" => cmd_list(&db, &config, cmd_args),
+        "ready" => cmd_ready(&db),
+        "focus" => cmd_focus(&db, cmd_args),
+        "show" | "s" => cmd_show(&db, cmd_args),
+        "search" | "find" => cmd_search(&db, cmd_args),
+        "open" | "o" => cmd_open(&db, cmd_args),
+        "done" | "d" => cmd_done(&db, &config, cmd_args),
         "undone" | "u" => cmd_undone(&db, cmd_args),
         "edit" | "e" => cmd_edit(&db, cmd_args),
         "remind" => cmd_remind(&db, cmd_args),
-        "rm" | "remove" | "r" => cmd_remove(&db, cmd_args),
-        "clear" => cmd_clear(&db),
-        "notify" => cmd_notify(&db),
+        "reminders" => cmd_reminders(&db, cmd_args),
+        "snooze" => cmd_snooze(&db, cmd_args),
+        "due" => cmd_due(&db, cmd_args),
+        "recur" => cmd_recur(&db, cmd_args),
+        "skip" => cmd_skip(&db, cmd_args),
+        "occurrences" | "next" => cmd_occurrences(&db, cmd_args),
+        "depend" => cmd_depend(&db, cmd_args),
+        "goal" | "goals" => cmd_goal(&db, cmd_args),
+        "tag" => cmd_tag(&db, cmd_args),
+        "comment" => cmd_comment(&db, &config, cmd_args),
+        "note" | "notes" => cmd_note(&db, cmd_args),
+        "delegate" => cmd_delegate(&db, cmd_args),
+        "block" => cmd_block(&db, cmd_args),
+        "blocked" => cmd_blocked(&db),
+        "effort" => cmd_effort(&db, cmd_args),
+        "priority" | "p" => cmd_priority(&db, cmd_args),
+        "project" => cmd_project(&db, cmd_args),
+        "projects" => cmd_projects(&db),
+        "rules" => cmd_rules(&config, cmd_args),
+        "print" => cmd_print(&db, cmd_args),
+        "share" => cmd_share(&db, cmd_args),
+        "sub" => cmd_sub(&db, &config, cmd_args),
+        "sprint" => cmd_sprint(&db, cmd_args),
+        "holidays" | "holiday" => cmd_holidays(&db, cmd_args),
+        "estimate" => cmd_estimate(&db, cmd_args),
+        "plan" => cmd_plan(&db, &config, cmd_args),
+        "waiting" => cmd_waiting(&db),
+        "git" => cmd_git(&db, cmd_args),
+        "review" => cmd_review(&db, &config),
+        "review-at" => cmd_review_at(&db, cmd_args),
+        "review-due" => cmd_review_due(&db),
+        "dedupe" => cmd_dedupe(&db, cmd_args),
+        "stats" => cmd_stats(&db, cmd_args),
+        "forecast" => cmd_forecast(&db, cmd_args),
+        "export" => cmd_export(&db, cmd_args),
+        "import" => cmd_import(&db, &config, cmd_args),
+        "apply" => cmd_apply(&db, cmd_args),
+        "serve" => cmd_serve(&db, &config, cmd_args),
+        "rm" | "remove" | "r" | "x" => cmd_remove(&db, &config, cmd_args),
+        "clear" => cmd_clear(&db, &config, cmd_args),
+        "archive" => cmd_archive(&db, cmd_args),
+        "restore" => cmd_restore(&db, &config, cmd_args),
+        "trash" => cmd_trash(&db, &config, cmd_args),
+        "purge" => cmd_purge(&db, cmd_args),
+        "undo" => cmd_undo(&db, cmd_args),
+        "redo" => cmd_redo(&db, cmd_args),
+        "notify" => cmd_notify(&db, &config),
+        "sync" if cmd_args.first().is_some_and(|a| a == "status") => sync::status(&db, &config),
+        "sync" if cmd_args.first().is_some_and(|a| a == "caldav" || a == "gtasks") => {
+            sync::remote(&db, &config, &cmd_args)
+        }
+        "sync" => sync::run(&db, &config),
+        "merge" => cmd_merge(&db, &config, cmd_args),
         unknown => {
             print_error(&format!("Unknown command: {}", unknown));
             println!("Run {} for usage information", "todo help".cyan());
             Ok(())
         }
+    };
+
+    if let Some(before) = undo_from {
+        if config.undo_levels > 0
+            && let Err(e) = record_undo(&db, &config, &command_line, before)
+        {
+            print_warning(&format!("Could not record this for undo: {:#}", e));
+        }
+        end_intent(&config)?;
+    }
+
+    let mutated = matches!(
+        cmd.as_str(),
+        "add" | "a" | "in" | "quick" | "q" | "done" | "d" | "rm" | "remove" | "r" | "x"
+            | "undo" | "redo" | "archive" | "restore" | "apply" | "merge"
+    );
+    if result.is_ok()
+        && mutated
+        && let Err(e) = sync::schedule(&db, &config)
+    {
+        print_warning(&format!("Auto-sync skipped: {:#}", e));
     }
+
+    if timings {
+        print_timings(
+            &[("config", config_time), ("db open", open_time), ("command", running.elapsed())],
+            db::list_stats(),
+        );
+    }
+
+    result
 }
 
 fn main() {
@@ -1,6 +1,8 @@
 use polodb_core::bson::{oid::ObjectId, DateTime};
 use serde::{Deserialize, Serialize};
 
+use crate::snapshot::fnv1a;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Todo {
     #[serde(rename = "_id")]
@@ -12,6 +14,91 @@ pub struct Todo {
     pub remind_at: Option<DateTime>,
     #[serde(default)]
     pub notified: bool,
+    #[serde(default)]
+    pub parent_id: Option<ObjectId>,
+    #[serde(default)]
+    pub start_at: Option<DateTime>,
+    #[serde(default)]
+    pub depends_on: Vec<ObjectId>,
+    #[serde(default)]
+    pub goal_id: Option<ObjectId>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub git: Option<GitLink>,
+    /// Repeating reminder rule ("every monday 9am"); remind_at holds the next firing
+    #[serde(default)]
+    pub remind_every: Option<String>,
+    #[serde(default)]
+    pub escalation: Vec<EscalationStep>,
+    /// How many escalation steps have fired since the reminder was set
+    #[serde(default)]
+    pub escalation_step: u32,
+    #[serde(default)]
+    pub due_at: Option<DateTime>,
+    /// Recurrence rule ("every monday 9am"); due_at holds the current occurrence
+    #[serde(default)]
+    pub recur: Option<String>,
+    /// Completed and skipped occurrences of a recurring todo
+    #[serde(default)]
+    pub history: Vec<HistoryEntry>,
+    /// When the todo was last marked done; unset for pending todos
+    #[serde(default)]
+    pub done_at: Option<DateTime>,
+    /// Which `todo serve` user marked the todo done; unset when it was done from here
+    #[serde(default)]
+    pub completed_by: Option<String>,
+    /// Who added the todo (the `user` setting at the time)
+    #[serde(default)]
+    pub owner: Option<String>,
+    #[serde(default)]
+    pub assigned_to: Option<String>,
+    #[serde(default)]
+    pub comments: Vec<Comment>,
+    /// Who the todo was delegated to with `todo delegate`, and since when
+    #[serde(default)]
+    pub waiting_on: Option<String>,
+    #[serde(default)]
+    pub waiting_since: Option<DateTime>,
+    /// Where the todo gets done ("post office"); free text for now
+    #[serde(default)]
+    pub location: Option<String>,
+    #[serde(default)]
+    pub effort: Option<Effort>,
+    /// Why the todo is stuck (set by `block`, `delegate` or `depend`), and since when
+    #[serde(default)]
+    pub block_reason: Option<String>,
+    #[serde(default)]
+    pub blocked_since: Option<DateTime>,
+    /// When to look at the todo again (tickler file), independent of due date and reminder
+    #[serde(default)]
+    pub review_at: Option<DateTime>,
+    /// Expected work in minutes, for `todo plan`
+    #[serde(default)]
+    pub estimate_minutes: Option<i64>,
+    /// Captured with `todo in` and not yet triaged
+    #[serde(default)]
+    pub inbox: bool,
+    #[serde(default)]
+    pub priority: Option<Priority>,
+    /// Project the todo belongs to, set with `@work` or `--project work`
+    #[serde(default)]
+    pub project: Option<String>,
+    /// ISO week the todo is planned for, e.g. "2025-W29"
+    #[serde(default)]
+    pub sprint: Option<String>,
+    /// Lead times (minutes before the due date) set with `todo due --notify 1d,2h`
+    #[serde(default)]
+    pub notify_before: Vec<i64>,
+    /// Lead-time notifications `todo notify` has yet to send, soonest first
+    #[serde(default)]
+    pub notifications: Vec<DateTime>,
+    /// When `todo notify` last sent anything for this todo
+    #[serde(default)]
+    pub last_notified_at: Option<DateTime>,
+    /// Longer free-form text, possibly several lines, set with `todo note`
+    #[serde(default)]
+    pub notes: Option<String>,
 }
 
 impl Todo {
@@ -23,17 +110,446 @@ impl Todo {
             created_at: DateTime::now(),
             remind_at: None,
             notified: false,
+            parent_id: None,
+            start_at: None,
+            depends_on: Vec::new(),
+            goal_id: None,
+            tags: Vec::new(),
+            git: None,
+            remind_every: None,
+            escalation: Vec::new(),
+            escalation_step: 0,
+            due_at: None,
+            recur: None,
+            history: Vec::new(),
+            done_at: None,
+            completed_by: None,
+            owner: None,
+            assigned_to: None,
+            comments: Vec::new(),
+            waiting_on: None,
+            waiting_since: None,
+            location: None,
+            effort: None,
+            block_reason: None,
+            blocked_since: None,
+            review_at: None,
+            estimate_minutes: None,
+            inbox: false,
+            priority: None,
+            project: None,
+            sprint: None,
+            notify_before: Vec::new(),
+            notifications: Vec::new(),
+            last_notified_at: None,
+            notes: None,
         }
     }
 
     pub fn with_reminder(title: String, remind_at: DateTime) -> Self {
+        Self {
+            remind_at: Some(remind_at),
+            ..Self::new(title)
+        }
+    }
+
+    /// A four-character alias that, unlike the list number, never changes: a letter and
+    /// three base-36 digits taken from a hash of the id, so it can't be mistaken for a number
+    pub fn short_id(&self) -> String {
+        const DIGITS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+        let mut hash = fnv1a(&self.id.bytes());
+        let mut id = String::with_capacity(4);
+        id.push(DIGITS[10 + (hash % 26) as usize] as char);
+        hash /= 26;
+        for _ in 0..3 {
+            id.push(DIGITS[(hash % 36) as usize] as char);
+            hash /= 36;
+        }
+        id
+    }
+
+    /// Notification times `notify_before` the due date that are still ahead of `now`
+    pub fn lead_notifications(due: DateTime, leads: &[i64], now: DateTime) -> Vec<DateTime> {
+        let mut times: Vec<DateTime> = leads
+            .iter()
+            .map(|minutes| DateTime::from_millis(due.timestamp_millis() - minutes * 60_000))
+            .filter(|t| t.timestamp_millis() > now.timestamp_millis())
+            .collect();
+        times.sort();
+        times.dedup();
+        times
+    }
+
+    /// Subtasks at any depth below this todo
+    pub fn descendants<'a>(&self, todos: &'a [Todo]) -> Vec<&'a Todo> {
+        let mut found: Vec<&Todo> = Vec::new();
+        let mut frontier = vec![self.id];
+        while let Some(id) = frontier.pop() {
+            for child in todos.iter().filter(|t| t.parent_id == Some(id)) {
+                if child.id != self.id && !found.iter().any(|f| f.id == child.id) {
+                    found.push(child);
+                    frontier.push(child.id);
+                }
+            }
+        }
+        found
+    }
+
+    /// Count (done, total) over the direct subtasks of this todo, if it has any
+    pub fn progress(&self, todos: &[Todo]) -> Option<(usize, usize)> {
+        let children: Vec<&Todo> = todos
+            .iter()
+            .filter(|t| t.parent_id == Some(self.id))
+            .collect();
+
+        if children.is_empty() {
+            return None;
+        }
+
+        let done = children.iter().filter(|t| t.done).count();
+        Some((done, children.len()))
+    }
+
+    /// The next escalation step, if it is due: the first reminder has fired,
+    /// the todo is still pending, and the step's offset has passed
+    pub fn due_escalation(&self, now: DateTime) -> Option<&EscalationStep> {
+        if self.done || !self.notified {
+            return None;
+        }
+
+        let remind_at = self.remind_at?;
+        let step = self.escalation.get(self.escalation_step as usize)?;
+        let fires_at = remind_at.timestamp_millis() + step.after_minutes * 60_000;
+        (fires_at <= now.timestamp_millis()).then_some(step)
+    }
+
+    /// How pressing a pending todo is, for `--sort urgency`: up to 10 for an approaching or
+    /// passed due date, up to 2 for age, plus `effort_weight` for low effort (quick wins)
+    /// and minus it for high effort
+    pub fn urgency(&self, now: DateTime, effort_weight: f64) -> f64 {
+        let day = 86_400_000.0;
+        let mut score = 0.0;
+
+        if let Some(due) = self.due_at {
+            let days_left = (due.timestamp_millis() - now.timestamp_millis()) as f64 / day;
+            score += if days_left <= 0.0 {
+                10.0
+            } else {
+                10.0 * (1.0 - days_left / 14.0).max(0.0)
+            };
+        }
+
+        let age_days = (now.timestamp_millis() - self.created_at.timestamp_millis()) as f64 / day;
+        score += 2.0 * (age_days / 30.0).clamp(0.0, 1.0);
+
+        score += match self.effort {
+            Some(Effort::Low) => effort_weight,
+            Some(Effort::High) => -effort_weight,
+            Some(Effort::Medium) | None => 0.0,
+        };
+
+        score
+    }
+
+    /// Delegated and still pending
+    pub fn is_waiting(&self) -> bool {
+        !self.done && self.waiting_on.is_some()
+    }
+
+    /// Marked as stuck with a reason and still pending
+    pub fn is_blocked(&self) -> bool {
+        !self.done && self.block_reason.is_some()
+    }
+
+    /// A todo is ready when it is pending, its start date has passed,
+    /// and every todo it depends on is done (or no longer exists)
+    pub fn is_ready(&self, todos: &[Todo], now: DateTime) -> bool {
+        if self.done || self.inbox || self.is_waiting() || self.is_blocked() {
+            return false;
+        }
+
+        if let Some(start) = self.start_at
+            && start.timestamp_millis() > now.timestamp_millis()
+        {
+            return false;
+        }
+
+        self.depends_on.iter().all(|dep| {
+            todos
+                .iter()
+                .find(|t| t.id == *dep)
+                .map(|t| t.done)
+                .unwrap_or(true)
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Urgency {
+    #[default]
+    Normal,
+    High,
+    Critical,
+}
+
+/// How important a todo is; declared lowest first so `Ord` ranks critical highest
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl Priority {
+    pub fn parse(input: &str) -> Option<Self> {
+        match input.to_lowercase().as_str() {
+            "low" | "l" => Some(Self::Low),
+            "medium" | "med" | "m" => Some(Self::Medium),
+            "high" | "h" => Some(Self::High),
+            "critical" | "crit" | "c" => Some(Self::Critical),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+            Self::Critical => "critical",
+        }
+    }
+
+    /// todo.txt's `(A)` to `(D)`, critical first
+    pub fn letter(&self) -> char {
+        match self {
+            Self::Critical => 'A',
+            Self::High => 'B',
+            Self::Medium => 'C',
+            Self::Low => 'D',
+        }
+    }
+
+    /// A todo.txt priority letter; E to Z count as low
+    pub fn from_letter(letter: char) -> Option<Self> {
+        match letter {
+            'A' => Some(Self::Critical),
+            'B' => Some(Self::High),
+            'C' => Some(Self::Medium),
+            'D'..='Z' => Some(Self::Low),
+            _ => None,
+        }
+    }
+}
+
+/// How much energy a todo takes, for picking work that matches your mood
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Effort {
+    Low,
+    Medium,
+    High,
+}
+
+impl Effort {
+    pub fn parse(input: &str) -> Option<Self> {
+        match input.to_lowercase().as_str() {
+            "low" | "l" => Some(Self::Low),
+            "medium" | "med" | "m" => Some(Self::Medium),
+            "high" | "h" => Some(Self::High),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+        }
+    }
+}
+
+/// A follow-up notification fired `after_minutes` past the reminder if the todo is still pending
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EscalationStep {
+    pub after_minutes: i64,
+    #[serde(default)]
+    pub urgency: Urgency,
+    /// Notification backend for this step ("desktop" or "push"); defaults to desktop
+    #[serde(default)]
+    pub backend: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryKind {
+    Completed,
+    Skipped,
+}
+
+/// What happened to one occurrence of a recurring todo
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HistoryEntry {
+    pub kind: HistoryKind,
+    pub occurrence: DateTime,
+    pub at: DateTime,
+    /// The `todo serve` user who completed or skipped it
+    #[serde(default)]
+    pub by: Option<String>,
+}
+
+/// A key for `todo serve`. Only a SHA-256 hash of the token is kept, so the token itself is
+/// shown once, when it's made.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ApiToken {
+    /// The start of the hash, for `todo serve token rm`
+    pub id: String,
+    pub hash: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    /// May add todos as well as read them
+    pub write: bool,
+    /// Lists the token reaches; empty for every list
+    #[serde(default)]
+    pub lists: Vec<String>,
+    /// The `todo serve` user the token signs in as, whose own access also applies
+    #[serde(default)]
+    pub user: Option<String>,
+    pub created_at: DateTime,
+}
+
+/// What a `todo serve` user may do
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// Reads and changes every list
+    Admin,
+    /// Reads and changes only the lists granted
+    Member,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Admin => "admin",
+            Self::Member => "member",
+        }
+    }
+}
+
+/// A todo's copy on a task server (`todo sync caldav|gtasks`), as of the last sync
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RemoteLink {
+    pub todo_id: ObjectId,
+    /// Where the server keeps it: a CalDAV href, a Google Tasks id
+    pub key: String,
+    /// The server's version tag, to tell whether it changed there
+    pub etag: String,
+    /// The fields both sides share, to tell whether they changed here
+    pub fingerprint: String,
+}
+
+/// Someone sharing `todo serve`, signing in with a token made for them. Todos they add or
+/// complete through the server are put down to them.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ServeUser {
+    pub name: String,
+    pub role: Role,
+    /// Lists a member can read and change
+    #[serde(default)]
+    pub lists: Vec<String>,
+    /// Lists a member can only read
+    #[serde(default)]
+    pub read: Vec<String>,
+    pub created_at: DateTime,
+}
+
+impl ServeUser {
+    /// Whether the user may read (or with `write`, change) `list`; only admins reach a
+    /// `--db` file, which has no list name
+    pub fn reaches(&self, list: Option<&str>, write: bool) -> bool {
+        let Some(list) = list else {
+            return self.role == Role::Admin;
+        };
+        let granted = |lists: &[String]| lists.iter().any(|l| l == list);
+        match self.role {
+            Role::Admin => true,
+            Role::Member => granted(&self.lists) || (!write && granted(&self.read)),
+        }
+    }
+}
+
+/// A deleted todo, kept in the trash until it's restored or `trash_days` have passed
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TrashedTodo {
+    /// The todo's own id, so it's in the trash at most once
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub todo: Todo,
+    pub deleted_at: DateTime,
+}
+
+/// The single todo you're working on, set with `todo focus`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Focus {
+    pub todo_id: ObjectId,
+    pub since: DateTime,
+}
+
+/// One command `todo undo` can take back: the todos it touched, as they were before and
+/// after it ran (absent from `before` if it added them, from `after` if it removed them)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct JournalEntry {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    /// Position in the journal; higher is newer
+    pub seq: i64,
+    /// The command as typed, e.g. "done 3"
+    pub command: String,
+    pub at: DateTime,
+    pub before: Vec<Todo>,
+    pub after: Vec<Todo>,
+    /// Taken back with `todo undo` and waiting for `todo redo`
+    #[serde(default)]
+    pub undone: bool,
+}
+
+/// A note left on a todo with `todo comment`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Comment {
+    pub author: Option<String>,
+    pub text: String,
+    pub at: DateTime,
+}
+
+/// Repository and branch a todo was linked to with `todo git link`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct GitLink {
+    pub repo: String,
+    pub branch: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Goal {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub title: String,
+    pub created_at: DateTime,
+    #[serde(default)]
+    pub due_at: Option<DateTime>,
+}
+
+impl Goal {
+    pub fn new(title: String, due_at: Option<DateTime>) -> Self {
         Self {
             id: ObjectId::new(),
             title,
-            done: false,
             created_at: DateTime::now(),
-            remind_at: Some(remind_at),
-            notified: false,
+            due_at,
         }
     }
 }
@@ -0,0 +1,244 @@
+use chrono::{Datelike, Duration, Months, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+
+use crate::schedule::{parse_clock, parse_weekday};
+
+/// Parse everyday phrases for a point in time, relative to `now`:
+///   - "in 3 hours", "in 10 minutes", "in a week", "in 2 months"
+///   - A day, optionally with a time ("at" is optional): "friday", "next monday 9am",
+///     "this fri at 5pm", "today 17:00", "tomorrow noon"
+///   - A calendar day: "jan 15", "15 jan", "january 15th 2026"
+///   - "end of week" (Friday), "end of month", "eom"
+///   - A time on its own: "9am", "noon"
+///
+/// Days without a time get `default_time`. Where a phrase could mean two things:
+///   - A bare weekday or time that has already passed today means the next one.
+///   - "next monday" is the first Monday after today, never today itself.
+///   - A month and day without a year that has passed this year means next year.
+pub fn parse_natural(
+    input: &str,
+    now: NaiveDateTime,
+    default_time: NaiveTime,
+) -> Option<NaiveDateTime> {
+    let words: Vec<&str> = input
+        .split_whitespace()
+        .map(|w| w.trim_end_matches(','))
+        .filter(|w| !w.is_empty())
+        .collect();
+    if let Some(dt) = parse_in(&words, now) {
+        return Some(dt);
+    }
+
+    let (day_words, time) = split_time(&words);
+    let today = now.date();
+    if day_words.is_empty() {
+        let dt = today.and_time(time?);
+        return Some(if dt <= now { dt + Duration::days(1) } else { dt });
+    }
+
+    let (date, rolls) = parse_day(day_words, today)?;
+    let dt = date.and_time(time.unwrap_or(default_time));
+    // "friday 9am" said on a Friday at noon means next week
+    Some(if rolls && dt <= now { dt + Duration::weeks(1) } else { dt })
+}
+
+/// "in <n> <unit>", where n may be "a" or "an"
+fn parse_in(words: &[&str], now: NaiveDateTime) -> Option<NaiveDateTime> {
+    let ["in", n, unit] = words else {
+        return None;
+    };
+    let n: u32 = match *n {
+        "a" | "an" | "one" => 1,
+        n => n.parse().ok()?,
+    };
+    let minutes = |m: i64| now.checked_add_signed(Duration::minutes(m * n as i64));
+    match unit.trim_end_matches('s') {
+        "minute" | "min" | "m" => minutes(1),
+        "hour" | "hr" | "h" => minutes(60),
+        "day" | "d" => minutes(60 * 24),
+        "week" | "wk" | "w" => minutes(60 * 24 * 7),
+        "month" | "mo" => now.checked_add_months(Months::new(n)),
+        "year" | "yr" | "y" => now.checked_add_months(Months::new(n * 12)),
+        _ => None,
+    }
+}
+
+fn clock(word: &str) -> Option<NaiveTime> {
+    match word {
+        "noon" | "midday" => NaiveTime::from_hms_opt(12, 0, 0),
+        "midnight" => NaiveTime::from_hms_opt(0, 0, 0),
+        word => parse_clock(word),
+    }
+}
+
+/// Split a trailing time ("9am", "9 am", "at 5pm") off the words that name the day
+fn split_time<'a>(words: &'a [&'a str]) -> (&'a [&'a str], Option<NaiveTime>) {
+    let (rest, time) = match words {
+        [rest @ .., hour, suffix @ ("am" | "pm")] => {
+            match clock(&format!("{}{}", hour, suffix)) {
+                Some(time) => (rest, time),
+                None => return (words, None),
+            }
+        }
+        [rest @ .., last] => match clock(last) {
+            Some(time) => (rest, time),
+            None => return (words, None),
+        },
+        [] => return (words, None),
+    };
+    match rest {
+        [rest @ .., "at"] => (rest, Some(time)),
+        rest => (rest, Some(time)),
+    }
+}
+
+/// The day a phrase names, and whether it's a bare weekday that should roll over to next
+/// week once its time has passed today
+fn parse_day(words: &[&str], today: NaiveDate) -> Option<(NaiveDate, bool)> {
+    let upcoming = |day: Weekday, skip_today: bool| {
+        let ahead = (7 + day.num_days_from_monday() - today.weekday().num_days_from_monday()) % 7;
+        let ahead = if ahead == 0 && skip_today { 7 } else { ahead };
+        today + Duration::days(ahead as i64)
+    };
+
+    let date = match words {
+        ["today"] => today,
+        ["tomorrow" | "tom"] => today.succ_opt()?,
+        ["end", "of", "week"] | ["eow"] => upcoming(Weekday::Fri, false),
+        ["end", "of", "month"] | ["eom"] => {
+            let first = today.with_day(1)?;
+            first.checked_add_months(Months::new(1))?.pred_opt()?
+        }
+        ["next", "week"] => upcoming(Weekday::Mon, true),
+        ["next", day] => upcoming(parse_weekday(day)?, true),
+        ["this", day] => upcoming(parse_weekday(day)?, false),
+        [day] if parse_weekday(day).is_some() => {
+            return Some((upcoming(parse_weekday(day)?, false), true));
+        }
+        _ => return parse_calendar_day(words, today).map(|d| (d, false)),
+    };
+    Some((date, false))
+}
+
+fn parse_month(word: &str) -> Option<u32> {
+    let months = [
+        "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+    ];
+    let word = if word == "sept" { "sep" } else { word };
+    let prefix = word.get(..3)?;
+    let index = months.iter().position(|m| *m == prefix)?;
+    // Full names must be spelled out right: "marchh" isn't March
+    let full = [
+        "january", "february", "march", "april", "may", "june", "july", "august", "september",
+        "october", "november", "december",
+    ];
+    (word.len() == 3 || full[index].starts_with(word)).then_some(index as u32 + 1)
+}
+
+/// "15", "15th", "1st", "22nd", "3rd"
+fn parse_day_number(word: &str) -> Option<u32> {
+    let digits = word.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    let suffix = &word[digits.len()..];
+    matches!(suffix, "" | "st" | "nd" | "rd" | "th")
+        .then(|| digits.parse().ok())
+        .flatten()
+        .filter(|d| (1..=31).contains(d))
+}
+
+/// "jan 15", "15 jan", "january 15th 2026"; without a year, the next time that day comes
+/// round (today included)
+fn parse_calendar_day(words: &[&str], today: NaiveDate) -> Option<NaiveDate> {
+    let (month, day, year) = match words {
+        [a, b] | [a, b, _] => match (parse_month(a), parse_month(b)) {
+            (Some(month), None) => (month, parse_day_number(b)?, words.get(2)),
+            (None, Some(month)) => (month, parse_day_number(a)?, words.get(2)),
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    if let Some(year) = year {
+        return NaiveDate::from_ymd_opt(year.parse().ok()?, month, day);
+    }
+    let this_year = NaiveDate::from_ymd_opt(today.year(), month, day);
+    match this_year {
+        Some(date) if date >= today => Some(date),
+        // Feb 29 only comes round in a leap year, which may be a few years off
+        _ => (1..=4).find_map(|n| NaiveDate::from_ymd_opt(today.year() + n, month, day)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(date: &str, time: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(&format!("{} {}", date, time), "%Y-%m-%d %H:%M")
+            .expect("valid test date")
+    }
+
+    /// `input` said at `now`, with days that lack a time at 09:00
+    fn parse(input: &str, now: NaiveDateTime) -> Option<NaiveDateTime> {
+        parse_natural(input, now, NaiveTime::from_hms_opt(9, 0, 0).unwrap())
+    }
+
+    // 2025-10-31 is a Friday and the last day of its month
+    const FRIDAY: &str = "2025-10-31";
+
+    #[test]
+    fn weekday_named_on_that_day_is_today_until_its_time_passes() {
+        assert_eq!(parse("friday", at(FRIDAY, "08:00")), Some(at(FRIDAY, "09:00")));
+        assert_eq!(parse("friday", at(FRIDAY, "12:00")), Some(at("2025-11-07", "09:00")));
+        assert_eq!(parse("friday 5pm", at(FRIDAY, "12:00")), Some(at(FRIDAY, "17:00")));
+        assert_eq!(parse("this fri", at(FRIDAY, "12:00")), Some(at(FRIDAY, "09:00")));
+    }
+
+    #[test]
+    fn next_weekday_never_means_today() {
+        assert_eq!(parse("next monday 9am", at(FRIDAY, "12:00")), Some(at("2025-11-03", "09:00")));
+        assert_eq!(
+            parse("next monday 9am", at("2025-11-03", "08:00")),
+            Some(at("2025-11-10", "09:00"))
+        );
+        assert_eq!(parse("next friday", at(FRIDAY, "08:00")), Some(at("2025-11-07", "09:00")));
+        assert_eq!(parse("next mon at 9 am", at(FRIDAY, "12:00")), Some(at("2025-11-03", "09:00")));
+    }
+
+    #[test]
+    fn month_and_day_already_past_rolls_to_next_year() {
+        assert_eq!(parse("jan 15", at(FRIDAY, "12:00")), Some(at("2026-01-15", "09:00")));
+        assert_eq!(parse("15 jan", at(FRIDAY, "12:00")), Some(at("2026-01-15", "09:00")));
+        assert_eq!(parse("jan 15", at("2025-01-15", "12:00")), Some(at("2025-01-15", "09:00")));
+        assert_eq!(parse("jan 15 2025", at(FRIDAY, "12:00")), Some(at("2025-01-15", "09:00")));
+        assert_eq!(parse("feb 29", at(FRIDAY, "12:00")), Some(at("2028-02-29", "09:00")));
+    }
+
+    #[test]
+    fn end_of_month_on_its_last_day_is_today() {
+        assert_eq!(parse("end of month", at(FRIDAY, "08:00")), Some(at(FRIDAY, "09:00")));
+        assert_eq!(parse("eom 5pm", at(FRIDAY, "12:00")), Some(at(FRIDAY, "17:00")));
+        let last_of_january = at("2025-01-31", "08:00");
+        assert_eq!(parse("end of month", last_of_january), Some(at("2025-01-31", "09:00")));
+        let leap_february = at("2024-02-10", "08:00");
+        assert_eq!(parse("end of month", leap_february), Some(at("2024-02-29", "09:00")));
+    }
+
+    #[test]
+    fn hours_from_now_cross_midnight() {
+        assert_eq!(parse("in 3 hours", at(FRIDAY, "22:30")), Some(at("2025-11-01", "01:30")));
+        assert_eq!(parse("in 3 hours", at("2025-12-31", "23:00")), Some(at("2026-01-01", "02:00")));
+        assert_eq!(parse("in an hour", at(FRIDAY, "23:45")), Some(at("2025-11-01", "00:45")));
+    }
+
+    #[test]
+    fn bare_time_already_past_means_tomorrow() {
+        assert_eq!(parse("9am", at(FRIDAY, "12:00")), Some(at("2025-11-01", "09:00")));
+        assert_eq!(parse("noon", at(FRIDAY, "08:00")), Some(at(FRIDAY, "12:00")));
+    }
+
+    #[test]
+    fn nonsense_is_not_a_date() {
+        assert_eq!(parse("marchh 3", at(FRIDAY, "12:00")), None);
+        assert_eq!(parse("feb 30 2025", at(FRIDAY, "12:00")), None);
+        assert_eq!(parse("in 3 fortnights", at(FRIDAY, "12:00")), None);
+    }
+}
@@ -0,0 +1,42 @@
+use anyhow::{anyhow, Result};
+
+use crate::config::Config;
+
+/// Clean up a new title per the config: expand snippets, collapse whitespace, apply the
+/// case rule and prefix. `todo add --raw` skips this.
+pub fn normalize_title(title: &str, config: &Config) -> Result<String> {
+    let mut title = title.to_string();
+    for (snippet, expansion) in &config.snippets {
+        title = title.replace(snippet.as_str(), expansion);
+    }
+
+    if config.collapse_whitespace {
+        title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+    }
+    let mut title = title.trim().to_string();
+
+    match config.title_case.as_str() {
+        "none" => {}
+        "sentence" => {
+            let mut chars = title.chars();
+            if let Some(first) = chars.next() {
+                title = first.to_uppercase().chain(chars).collect();
+            }
+        }
+        other => {
+            return Err(anyhow!(
+                "Unknown title_case '{}' in config. Use none or sentence",
+                other
+            ));
+        }
+    }
+
+    if let Some(ref prefix) = config.title_prefix
+        && !prefix.is_empty()
+        && !title.starts_with(prefix.as_str())
+    {
+        title = format!("{}{}", prefix, title);
+    }
+
+    Ok(title)
+}
@@ -0,0 +1,301 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use polodb_core::bson::{self, oid::ObjectId, DateTime as BsonDateTime, Document};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::db::TodoDb;
+use crate::json::{bson_to_json, json_to_bson};
+use crate::models::Todo;
+use crate::snapshot::write_whole;
+
+/// One line of a device's log: fields of a todo set to new values, or the todo deleted
+#[derive(Serialize, Deserialize)]
+struct Op {
+    /// Milliseconds since the epoch, when the change was synced
+    at: i64,
+    device: String,
+    id: String,
+    /// Plain JSON, as `todo_json` writes it
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    set: Map<String, Value>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    deleted: bool,
+}
+
+/// When a field was last written and by which device; the later one wins, and the device
+/// name settles a tie so every device picks the same
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+struct Clock {
+    at: i64,
+    device: String,
+}
+
+/// What this device knew after its last sync, kept beside the database
+#[derive(Default, Serialize, Deserialize)]
+struct State {
+    /// The list as it was, so the next sync can tell what changed here since
+    todos: Vec<Document>,
+    /// `<id>.<field>` → when it was last written
+    clocks: HashMap<String, Clock>,
+    /// Deleted todos by id, so a late edit from another device doesn't bring them back
+    deleted: HashMap<String, Clock>,
+    /// How many lines of each other device's log have been read
+    read: HashMap<String, usize>,
+}
+
+/// What `sync` did
+pub struct Synced {
+    /// Changes made here, written to this device's log
+    pub sent: usize,
+    /// Changes read from other devices' logs
+    pub received: usize,
+    /// Todos added, changed or deleted here by them
+    pub changed: usize,
+    /// Fields changed both here and on another device, and which change was kept
+    pub conflicts: Vec<String>,
+}
+
+/// This device's state, next to the database it belongs to
+pub fn state_path(db_path: &Path) -> PathBuf {
+    db_path.with_extension("oplog.bson")
+}
+
+fn log_path(dir: &Path, device: &str) -> PathBuf {
+    dir.join(format!("{}.jsonl", device))
+}
+
+fn load_state(path: &Path) -> Result<State> {
+    let Ok(bytes) = fs::read(path) else {
+        return Ok(State::default());
+    };
+    bson::from_slice(&bytes)
+        .with_context(|| format!("failed to read {}; delete it to carry on", path.display()))
+}
+
+fn documents(todos: &[Todo]) -> Result<HashMap<ObjectId, Document>> {
+    todos
+        .iter()
+        .map(|t| Ok((t.id, bson::to_document(t).context("failed to encode todo")?)))
+        .collect()
+}
+
+/// A field as plain JSON, as it goes in the log and comes out of it
+fn plain(doc: &Document, field: &str) -> Value {
+    doc.get(field).map(bson_to_json).unwrap_or(Value::Null)
+}
+
+/// Changes from the other devices' logs in `dir` that this device hasn't read yet, in the
+/// order they were synced. A line that doesn't parse may still be arriving, so reading
+/// that log stops there until next time.
+fn read_others(dir: &Path, device: &str, read: &mut HashMap<String, usize>) -> Result<Vec<Op>> {
+    let entries = fs::read_dir(dir).with_context(|| format!("failed to read {}", dir.display()))?;
+    let mut ops = Vec::new();
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Some(other) = name.strip_suffix(".jsonl").filter(|o| *o != device) else {
+            continue;
+        };
+        let text = fs::read_to_string(entry.path())
+            .with_context(|| format!("failed to read {}", entry.path().display()))?;
+        let seen = read.entry(other.to_string()).or_default();
+        for line in text.lines().skip(*seen) {
+            match serde_json::from_str::<Op>(line) {
+                Ok(op) => ops.push(op),
+                Err(_) => break,
+            }
+            *seen += 1;
+        }
+    }
+    ops.sort_by(|a, b| (a.at, &a.device).cmp(&(b.at, &b.device)));
+    Ok(ops)
+}
+
+/// Write what changed here since the last sync to this device's log in `dir`, then bring
+/// in what other devices wrote to theirs. Each field keeps its latest write. A deleted todo
+/// stays deleted, whatever edits were made to it before; only adding it back in full (as
+/// `todo undo` does) counts as later.
+pub fn sync(db: &TodoDb, dir: &Path, device: &str, state_path: &Path) -> Result<Synced> {
+    fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    let mut state = load_state(state_path)?;
+    let base: HashMap<ObjectId, Document> = state
+        .todos
+        .iter()
+        .filter_map(|d| Some((d.get_object_id("_id").ok()?, d.clone())))
+        .collect();
+    let local = documents(&db.list_all()?)?;
+    let now = Clock { at: BsonDateTime::now().timestamp_millis(), device: device.to_string() };
+
+    // What changed here: `<id>` for a deleted todo, `<id>.<field>` for a changed field
+    let mut changed_here = HashSet::new();
+    let mut sent = Vec::new();
+    let ids: BTreeSet<&ObjectId> = base.keys().chain(local.keys()).collect();
+    for id in ids {
+        let hex = id.to_hex();
+        let mut op = Op {
+            at: now.at,
+            device: device.to_string(),
+            id: hex.clone(),
+            set: Map::new(),
+            deleted: false,
+        };
+        match (base.get(id), local.get(id)) {
+            (Some(_), None) => {
+                op.deleted = true;
+                state.deleted.insert(hex.clone(), now.clone());
+                changed_here.insert(hex);
+            }
+            (before, Some(after)) => {
+                for (field, value) in after.iter().filter(|(f, _)| f.as_str() != "_id") {
+                    if before.and_then(|b| b.get(field)) == Some(value) {
+                        continue;
+                    }
+                    op.set.insert(field.clone(), plain(after, field));
+                    let key = format!("{}.{}", hex, field);
+                    state.clocks.insert(key.clone(), now.clone());
+                    changed_here.insert(key);
+                }
+                if op.set.is_empty() {
+                    continue;
+                }
+            }
+            (None, None) => continue,
+        }
+        sent.push(op);
+    }
+    if !sent.is_empty() {
+        let mut lines = String::new();
+        for op in &sent {
+            lines.push_str(&serde_json::to_string(op).context("failed to encode a change")?);
+            lines.push('\n');
+        }
+        let path = log_path(dir, device);
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .and_then(|mut file| file.write_all(lines.as_bytes()))
+            .with_context(|| format!("failed to write {}", path.display()))?;
+    }
+
+    // What changed elsewhere
+    let received = read_others(dir, device, &mut state.read)?;
+    let mut docs = local.clone();
+    let mut conflicts = Vec::new();
+    let title = |doc: Option<&Document>| {
+        doc.and_then(|d| d.get_str("title").ok()).unwrap_or("?").to_string()
+    };
+    for op in &received {
+        let Ok(id) = ObjectId::parse_str(&op.id) else {
+            continue;
+        };
+        let clock = Clock { at: op.at, device: op.device.clone() };
+        if op.deleted {
+            if state.deleted.get(&op.id).is_none_or(|d| *d < clock) {
+                state.deleted.insert(op.id.clone(), clock.clone());
+            }
+            // Added back in full since
+            let added = state.clocks.get(&format!("{}.created_at", op.id));
+            if added.is_some_and(|a| *a > clock) {
+                continue;
+            }
+            let prefix = format!("{}.", op.id);
+            let edited = changed_here.iter().any(|k| k.starts_with(&prefix));
+            if let Some(doc) = docs.remove(&id)
+                && edited
+            {
+                conflicts.push(format!(
+                    "\"{}\": deleted on {} though edited here",
+                    title(Some(&doc)),
+                    op.device
+                ));
+            }
+            continue;
+        }
+        if state.deleted.get(&op.id).is_some_and(|d| *d >= clock) {
+            if changed_here.contains(&op.id) {
+                conflicts.push(format!(
+                    "\"{}\": edited on {} though deleted here",
+                    title(base.get(&id)),
+                    op.device
+                ));
+            }
+            continue;
+        }
+
+        let doc = docs.entry(id).or_insert_with(|| {
+            let mut doc = Document::new();
+            doc.insert("_id", id);
+            doc
+        });
+        for (field, value) in &op.set {
+            let key = format!("{}.{}", op.id, field);
+            let ours_later = state.clocks.get(&key).is_some_and(|c| *c >= clock);
+            if plain(doc, field) == *value {
+                if !ours_later {
+                    state.clocks.insert(key, clock.clone());
+                }
+                continue;
+            }
+            if changed_here.contains(&key) {
+                let (kept, over) = if ours_later {
+                    ("this device's", op.device.as_str())
+                } else {
+                    (op.device.as_str(), "this device's")
+                };
+                conflicts.push(format!(
+                    "{} of \"{}\": kept {} change over {}",
+                    field,
+                    title(Some(doc)),
+                    kept,
+                    over
+                ));
+            }
+            if !ours_later {
+                doc.insert(field.clone(), json_to_bson(field, value)?);
+                state.clocks.insert(key, clock.clone());
+            }
+        }
+    }
+
+    // Into the list: todos that came out different, or deleted
+    let mut touched = Vec::new();
+    let mut results = Vec::new();
+    let ids: BTreeSet<&ObjectId> = local.keys().chain(docs.keys()).collect();
+    for id in ids {
+        match (local.get(id), docs.get(id)) {
+            (Some(before), Some(after)) if before == after => continue,
+            (_, Some(after)) => match bson::from_document::<Todo>(after.clone()) {
+                Ok(todo) => results.push(todo),
+                // Part of a todo this device never had, e.g. edits made to one deleted here
+                Err(_) if !local.contains_key(id) => continue,
+                Err(e) => return Err(e).context("failed to decode a synced todo"),
+            },
+            (_, None) => {}
+        }
+        touched.push(*id);
+    }
+    if !touched.is_empty() {
+        db.restore_todos(&touched, &results)?;
+    }
+
+    state.todos = db
+        .list_all()?
+        .iter()
+        .map(bson::to_document)
+        .collect::<Result<_, _>>()
+        .context("failed to encode todo")?;
+    let bytes = bson::to_vec(&state).context("failed to encode the sync state")?;
+    write_whole(state_path, &bytes)?;
+
+    Ok(Synced {
+        sent: sent.len(),
+        received: received.len(),
+        changed: touched.len(),
+        conflicts,
+    })
+}
@@ -1,17 +1,44 @@
 use anyhow::{anyhow, Result};
-use chrono::{Local, NaiveTime, TimeZone};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
 use polodb_core::bson::DateTime as BsonDateTime;
 use std::process::Command;
+use std::sync::OnceLock;
+
+use crate::config::Config;
+use crate::i18n;
+use crate::models::{EscalationStep, Urgency};
+use crate::natural::parse_natural;
+use crate::schedule::{add_business_days, Schedule};
+
+static DEFAULT_TIME: OnceLock<NaiveTime> = OnceLock::new();
+
+/// Set the time of day used for bare dates (from the `reminder_time` config key)
+pub fn set_default_time(time: &str) -> Result<()> {
+    let time = NaiveTime::parse_from_str(time, "%H:%M")
+        .map_err(|_| anyhow!("Invalid reminder_time '{}'. Use HH:MM, e.g. 09:00", time))?;
+    let _ = DEFAULT_TIME.set(time);
+    Ok(())
+}
+
+fn default_time() -> NaiveTime {
+    DEFAULT_TIME
+        .get()
+        .copied()
+        .unwrap_or_else(|| NaiveTime::from_hms_opt(9, 0, 0).unwrap_or_default())
+}
 
 /// Parse a reminder string into a BSON DateTime
 /// Formats supported:
 ///   - Duration: 15m, 1h, 2d, 1w (minutes, hours, days, weeks)
 ///   - Time today: 14:30, 9:00
 ///   - Relative: tomorrow, tom
+///   - Business days: next business day, 3bd (skipping the weekend and holidays)
+///   - Date: 2025-09-01 (at reminder_time, 09:00 by default), 2025-09-01 14:30
+///   - Phrases: next monday 9am, in 3 hours, friday, jan 15, end of month (see `natural`)
 pub fn parse_reminder(input: &str) -> Result<BsonDateTime> {
     let input = input.trim().to_lowercase();
 
-    // Try duration format (15m, 1h, 2d, 1w)
+    // Try duration format (15m, 1h, 2d, 1w, 6mo)
     if let Some(dt) = parse_duration(&input) {
         return Ok(dt);
     }
@@ -26,28 +53,75 @@ pub fn parse_reminder(input: &str) -> Result<BsonDateTime> {
         return Ok(dt);
     }
 
+    // Try business days (next business day, 3bd)
+    if let Some(dt) = parse_business_days(&input) {
+        return Ok(dt);
+    }
+
+    // Try calendar dates (2025-09-01, 2025-09-01 14:30)
+    if let Some(dt) = parse_date(&input) {
+        return Ok(dt);
+    }
+
+    // Try everyday phrases (next monday 9am, in 3 hours, jan 15)
+    if let Some(dt) = parse_phrase(&input) {
+        return Ok(dt);
+    }
+
     Err(anyhow!(
-        "Invalid reminder format: '{}'\nExamples: 15m, 2h, 1d, 14:30, tomorrow",
+        "Invalid reminder format: '{}'\nExamples: 15m, 2h, 14:30, tomorrow, next monday 9am, in 3 hours, jan 15, end of month, 2025-09-01",
         input
     ))
 }
 
-fn parse_duration(input: &str) -> Option<BsonDateTime> {
+/// Parse a repeating reminder ("every monday 9am") into its first firing after now
+pub fn parse_repeating(input: &str) -> Result<(BsonDateTime, Schedule)> {
+    let schedule = Schedule::parse(input)?;
+    let first = schedule
+        .next_after(Local::now())
+        .ok_or_else(|| anyhow!("Schedule '{}' never fires", input))?;
+    Ok((BsonDateTime::from_millis(first.timestamp_millis()), schedule))
+}
+
+/// Convert a stored BSON timestamp to local time
+pub fn to_local(dt: &BsonDateTime) -> Option<DateTime<Local>> {
+    Local.timestamp_millis_opt(dt.timestamp_millis()).single()
+}
+
+/// Whether a reminder string describes a repeating schedule rather than a single time
+pub fn is_repeating(input: &str) -> bool {
+    input.trim().to_lowercase().starts_with("every ")
+}
+
+/// Parse a duration like 15m, 1h, 2d, 1w, 6mo or 1y into minutes
+pub fn parse_minutes(input: &str) -> Option<i64> {
+    // Calendar-ish units for tickler dates: 6mo, 1y
+    if let Some(num) = input.strip_suffix("mo") {
+        return Some(num.parse::<i64>().ok()? * 60 * 24 * 30);
+    }
+    if let Some(num) = input.strip_suffix('y') {
+        return Some(num.parse::<i64>().ok()? * 60 * 24 * 365);
+    }
+
     let len = input.len();
-    if len < 2 {
+    if len < 2 || !input.is_char_boundary(len - 1) {
         return None;
     }
 
     let (num_str, unit) = input.split_at(len - 1);
     let num: i64 = num_str.parse().ok()?;
 
-    let minutes = match unit {
-        "m" => num,
-        "h" => num * 60,
-        "d" => num * 60 * 24,
-        "w" => num * 60 * 24 * 7,
-        _ => return None,
-    };
+    match unit {
+        "m" => Some(num),
+        "h" => Some(num * 60),
+        "d" => Some(num * 60 * 24),
+        "w" => Some(num * 60 * 24 * 7),
+        _ => None,
+    }
+}
+
+fn parse_duration(input: &str) -> Option<BsonDateTime> {
+    let minutes = parse_minutes(input)?;
 
     let now = Local::now();
     let future = now + chrono::Duration::minutes(minutes);
@@ -93,40 +167,270 @@ fn parse_relative(input: &str) -> Option<BsonDateTime> {
     Some(BsonDateTime::from_millis(future.timestamp_millis()))
 }
 
-/// Send a macOS notification
-pub fn send_notification(title: &str, message: &str) -> Result<()> {
+/// "next business day" or "3bd": that many working days from today, at reminder_time
+fn parse_business_days(input: &str) -> Option<BsonDateTime> {
+    let days = match input {
+        "next business day" | "business day" | "nbd" => 1,
+        other => other.strip_suffix("bd")?.parse().ok()?,
+    };
+    let date = add_business_days(Local::now().date_naive(), days)?;
+    let local_dt = Local.from_local_datetime(&date.and_time(default_time())).single()?;
+    Some(BsonDateTime::from_millis(local_dt.timestamp_millis()))
+}
+
+fn parse_date(input: &str) -> Option<BsonDateTime> {
+    let naive_dt = NaiveDateTime::parse_from_str(input, "%Y-%m-%d %H:%M")
+        .ok()
+        .or_else(|| {
+            let date = NaiveDate::parse_from_str(input, "%Y-%m-%d").ok()?;
+            Some(date.and_time(default_time()))
+        })?;
+
+    let local_dt = Local.from_local_datetime(&naive_dt).single()?;
+    Some(BsonDateTime::from_millis(local_dt.timestamp_millis()))
+}
+
+fn parse_phrase(input: &str) -> Option<BsonDateTime> {
+    let naive_dt = parse_natural(input, Local::now().naive_local(), default_time())?;
+    let local_dt = Local.from_local_datetime(&naive_dt).earliest()?;
+    Some(BsonDateTime::from_millis(local_dt.timestamp_millis()))
+}
+
+/// Parse lead times like `1d,2h`: how long before a due date to send each notification
+pub fn parse_leads(input: &str) -> Result<Vec<i64>> {
+    input
+        .split(',')
+        .map(|lead| {
+            let lead = lead.trim().to_lowercase();
+            parse_minutes(&lead)
+                .filter(|&m| m >= 0)
+                .ok_or_else(|| anyhow!("Invalid lead time: '{}'. Examples: 1d, 2h, 30m", lead))
+        })
+        .collect()
+}
+
+/// Parse escalation steps like `30m:high,1h:critical:push`.
+/// Each step is `<offset>[:<urgency>][:<backend>]`, the offset counted from the reminder time.
+pub fn parse_escalation(input: &str) -> Result<Vec<EscalationStep>> {
+    input
+        .split(',')
+        .map(|step| {
+            let mut parts = step.trim().split(':');
+            let offset = parts.next().unwrap_or_default().to_lowercase();
+            let after_minutes = parse_minutes(&offset).ok_or_else(|| {
+                anyhow!("Invalid escalation offset: '{}'. Examples: 30m, 1h", offset)
+            })?;
+
+            let mut urgency = Urgency::Normal;
+            let mut backend = None;
+            for part in parts {
+                match part.to_lowercase().as_str() {
+                    "normal" => urgency = Urgency::Normal,
+                    "high" => urgency = Urgency::High,
+                    "critical" => urgency = Urgency::Critical,
+                    "desktop" | "push" => backend = Some(part.to_lowercase()),
+                    other => {
+                        return Err(anyhow!(
+                            "Unknown escalation option '{}'. Use normal/high/critical or desktop/push",
+                            other
+                        ));
+                    }
+                }
+            }
+
+            Ok(EscalationStep {
+                after_minutes,
+                urgency,
+                backend,
+            })
+        })
+        .collect()
+}
+
+/// Deliver a notification through the named backend ("desktop" when unset, or "push")
+pub fn deliver(
+    config: &Config,
+    backend: Option<&str>,
+    urgency: Urgency,
+    title: &str,
+    message: &str,
+) -> Result<()> {
+    match backend.unwrap_or("desktop") {
+        "desktop" => send_notification(config, title, message, urgency),
+        "push" => send_push(config, title, message, urgency),
+        other => Err(anyhow!("Unknown notification backend: {}", other)),
+    }
+}
+
+/// Run the configured `push_command` with the notification in `TODO_*` env vars
+fn send_push(config: &Config, title: &str, message: &str, urgency: Urgency) -> Result<()> {
+    let command = config
+        .push_command
+        .as_deref()
+        .ok_or_else(|| anyhow!("No push_command configured for the push backend"))?;
+
+    let urgency = match urgency {
+        Urgency::Normal => "normal",
+        Urgency::High => "high",
+        Urgency::Critical => "critical",
+    };
+
+    let status = Command::new("sh")
+        .args(["-c", command])
+        .env("TODO_TITLE", title)
+        .env("TODO_MESSAGE", message)
+        .env("TODO_URGENCY", urgency)
+        .status()
+        .map_err(|e| anyhow!("Failed to run push_command: {}", e))?;
+
+    if !status.success() {
+        return Err(anyhow!("push_command exited with {}", status));
+    }
+    Ok(())
+}
+
+/// Run a notifier program, failing if it can't start or reports an error
+fn run_notifier(command: &mut Command, name: &str) -> Result<()> {
+    let output = command
+        .output()
+        .map_err(|e| anyhow!("Failed to run {}: {}", name, e))?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "{} exited with {}: {}",
+            name,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+/// Show a desktop notification with `desktop_notifier`, picking the platform's own
+/// mechanism when it's "auto"
+pub fn send_notification(
+    config: &Config,
+    title: &str,
+    message: &str,
+    urgency: Urgency,
+) -> Result<()> {
+    match config.desktop_notifier.as_str() {
+        "auto" if cfg!(target_os = "macos") => notify_macos(title, message, urgency),
+        "auto" if cfg!(target_os = "windows") => notify_windows(title, message, urgency),
+        // Fall back to talking to the notification daemon directly when libnotify's
+        // notify-send isn't installed
+        "auto" => notify_send(title, message, urgency).or_else(|e| {
+            notify_dbus(title, message, urgency)
+                .map_err(|dbus| anyhow!("{:#}; D-Bus fallback also failed: {:#}", e, dbus))
+        }),
+        "osascript" => notify_macos(title, message, urgency),
+        "notify-send" => notify_send(title, message, urgency),
+        "dbus" => notify_dbus(title, message, urgency),
+        "toast" => notify_windows(title, message, urgency),
+        other => Err(anyhow!(
+            "Unknown desktop_notifier '{}'. Use auto, osascript, notify-send, dbus or toast",
+            other
+        )),
+    }
+}
+
+/// macOS notification via AppleScript; urgent ones play a sound
+fn notify_macos(title: &str, message: &str, urgency: Urgency) -> Result<()> {
+    let sound = match urgency {
+        Urgency::Normal => "",
+        Urgency::High => r#" sound name "Glass""#,
+        Urgency::Critical => r#" sound name "Sosumi""#,
+    };
     let script = format!(
-        r#"display notification "{}" with title "Todo Reminder" subtitle "{}""#,
+        r#"display notification "{}" with title "Todo Reminder" subtitle "{}"{}"#,
         message.replace('"', "\\\""),
-        title.replace('"', "\\\"")
+        title.replace('"', "\\\""),
+        sound
     );
 
-    Command::new("osascript")
-        .args(["-e", &script])
-        .output()
-        .map_err(|e| anyhow!("Failed to send notification: {}", e))?;
+    run_notifier(Command::new("osascript").args(["-e", &script]), "osascript")
+}
 
-    Ok(())
+/// freedesktop urgency level: 1 normal, 2 critical (stays until dismissed)
+fn freedesktop_urgency(urgency: Urgency) -> u8 {
+    match urgency {
+        Urgency::Normal => 1,
+        Urgency::High | Urgency::Critical => 2,
+    }
+}
+
+/// Linux/BSD notification through libnotify's `notify-send`
+fn notify_send(title: &str, message: &str, urgency: Urgency) -> Result<()> {
+    let level = if freedesktop_urgency(urgency) == 2 { "critical" } else { "normal" };
+    run_notifier(
+        Command::new("notify-send").args(["--app-name=todo", "-u", level, title, message]),
+        "notify-send",
+    )
+}
+
+/// The same notification sent straight to org.freedesktop.Notifications over D-Bus
+fn notify_dbus(title: &str, message: &str, urgency: Urgency) -> Result<()> {
+    let quote = |s: &str| format!("'{}'", s.replace('\\', "\\\\").replace('\'', "\\'"));
+    let hints = format!("{{'urgency': <byte {}>}}", freedesktop_urgency(urgency));
+    run_notifier(
+        Command::new("gdbus").args([
+            "call",
+            "--session",
+            "--dest=org.freedesktop.Notifications",
+            "--object-path=/org/freedesktop/Notifications",
+            "--method=org.freedesktop.Notifications.Notify",
+            "'todo'",
+            "0",
+            "''",
+            &quote(title),
+            &quote(message),
+            "[]",
+            &hints,
+            "-1",
+        ]),
+        "gdbus",
+    )
+}
+
+/// Shows a Windows toast; the text arrives in environment variables so nothing needs
+/// escaping for PowerShell
+const TOAST_SCRIPT: &str = r#"
+[Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime] | Out-Null
+$xml = [Windows.UI.Notifications.ToastNotificationManager]::GetTemplateContent([Windows.UI.Notifications.ToastTemplateType]::ToastText02)
+$text = $xml.GetElementsByTagName('text')
+$text.Item(0).AppendChild($xml.CreateTextNode($env:TODO_TITLE)) | Out-Null
+$text.Item(1).AppendChild($xml.CreateTextNode($env:TODO_MESSAGE)) | Out-Null
+$toast = [Windows.UI.Notifications.ToastNotification]::new($xml)
+if ($env:TODO_URGENCY -ne 'normal') { $toast.Priority = [Windows.UI.Notifications.ToastNotificationPriority]::High }
+$app = '{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}\WindowsPowerShell\v1.0\powershell.exe'
+[Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier($app).Show($toast)
+"#;
+
+/// Windows toast notification through PowerShell
+fn notify_windows(title: &str, message: &str, urgency: Urgency) -> Result<()> {
+    let urgency = match urgency {
+        Urgency::Normal => "normal",
+        Urgency::High => "high",
+        Urgency::Critical => "critical",
+    };
+    run_notifier(
+        Command::new("powershell")
+            .args(["-NoProfile", "-NonInteractive", "-Command", TOAST_SCRIPT])
+            .env("TODO_TITLE", title)
+            .env("TODO_MESSAGE", message)
+            .env("TODO_URGENCY", urgency),
+        "powershell",
+    )
 }
 
 /// Format a reminder time for display
 pub fn format_remind_at(dt: &BsonDateTime) -> String {
-    let millis = dt.timestamp_millis();
-    let secs = millis / 1000;
-    let nsecs = ((millis % 1000) * 1_000_000) as u32;
-
-    if let Some(local_dt) = Local.timestamp_opt(secs, nsecs).single() {
-        let now = Local::now();
-        let diff = local_dt.signed_duration_since(now);
-
-        if diff.num_minutes() < 1 {
-            "now".to_string()
-        } else if diff.num_minutes() < 60 {
-            format!("in {}m", diff.num_minutes())
-        } else if diff.num_hours() < 24 {
-            format!("in {}h", diff.num_hours())
-        } else if diff.num_days() < 7 {
-            format!("in {}d", diff.num_days())
+    if let Some(local_dt) = to_local(dt) {
+        let minutes = local_dt.signed_duration_since(Local::now()).num_minutes();
+
+        if minutes < 1 {
+            i18n::now().to_string()
+        } else if minutes < 7 * 24 * 60 {
+            i18n::in_time(minutes)
         } else {
             local_dt.format("%m-%d %H:%M").to_string()
         }
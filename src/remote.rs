@@ -0,0 +1,602 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{SecondsFormat, TimeZone, Utc};
+use polodb_core::bson::{oid::ObjectId, DateTime as BsonDateTime};
+use serde_json::{json, Value};
+
+use crate::db::TodoDb;
+use crate::http::{self, Response};
+use crate::ics;
+use crate::models::{RemoteLink, Todo};
+use crate::remind::{parse_reminder, to_local};
+
+const CALENDAR_QUERY: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<c:calendar-query xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+  <d:prop><d:getetag/><c:calendar-data/></d:prop>
+  <c:filter>
+    <c:comp-filter name="VCALENDAR"><c:comp-filter name="VTODO"/></c:comp-filter>
+  </c:filter>
+</c:calendar-query>"#;
+
+const GOOGLE_TASKS: &str = "https://tasks.googleapis.com/tasks/v1/lists";
+
+/// A task as the server has it, in the terms both sides share
+struct Task {
+    /// Where the server keeps it: a CalDAV href, a Google Tasks id
+    key: String,
+    etag: String,
+    /// The iCalendar UID, which a CalDAV server won't let change
+    uid: String,
+    title: String,
+    done: bool,
+    due_at: Option<BsonDateTime>,
+    remind_at: Option<BsonDateTime>,
+}
+
+/// A task server to sync with
+pub enum Server {
+    /// A CalDAV calendar collection; credentials come from `~/.netrc`
+    CalDav { url: String },
+    /// A Google Tasks list ("@default" for the first one), with an OAuth access token
+    GoogleTasks { list: String, token: String },
+}
+
+/// What `sync` did
+pub struct Synced {
+    /// Tasks created, changed or deleted on the server
+    pub pushed: usize,
+    /// Todos added, changed or trashed here
+    pub pulled: usize,
+    /// Todos changed on both sides since the last sync; the server's change was kept
+    pub conflicts: Vec<String>,
+}
+
+fn whole_seconds(at: BsonDateTime) -> BsonDateTime {
+    let millis = at.timestamp_millis();
+    BsonDateTime::from_millis(millis - millis.rem_euclid(1000))
+}
+
+/// "2025-09-01" at reminder_time, as a bare due date is everywhere else
+fn due_date(date: &str) -> Option<BsonDateTime> {
+    parse_reminder(date).ok()
+}
+
+fn rfc3339(at: &BsonDateTime) -> String {
+    Utc.timestamp_millis_opt(at.timestamp_millis())
+        .single()
+        .unwrap_or_default()
+        .to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+/// The fields both sides share, to tell later whether either side changed them
+fn fingerprint(task: &Task) -> String {
+    let millis = |at: Option<BsonDateTime>| {
+        at.map(|a| a.timestamp_millis().to_string()).unwrap_or_default()
+    };
+    format!("{}|{}|{}|{}", task.done, millis(task.due_at), millis(task.remind_at), task.title)
+}
+
+/// The contents of each `name` element in `xml`, whatever its namespace prefix
+fn elements<'a>(xml: &'a str, name: &str) -> Vec<&'a str> {
+    let mut found = Vec::new();
+    let mut start = None;
+    let mut at = 0;
+    while let Some(open) = xml[at..].find('<').map(|i| at + i) {
+        // CDATA may hold anything, tags included
+        if xml[open..].starts_with("<![CDATA[") {
+            at = xml[open..].find("]]>").map_or(xml.len(), |end| open + end + 3);
+            continue;
+        }
+        let Some(close) = xml[open..].find('>').map(|i| open + i) else {
+            break;
+        };
+        let tag = &xml[open + 1..close];
+        at = close + 1;
+        let tag_name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or("");
+        if tag_name.rsplit(':').next() != Some(name) {
+            continue;
+        }
+        if tag.starts_with('/') {
+            if let Some(start) = start.take() {
+                found.push(&xml[start..open]);
+            }
+        } else if tag.ends_with('/') {
+            found.push("");
+        } else if start.is_none() {
+            start = Some(at);
+        }
+    }
+    found
+}
+
+fn unescape_xml(s: &str) -> String {
+    let mut out = String::new();
+    let mut rest = s;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let Some(end) = rest.find(';') else {
+            break;
+        };
+        let entity = &rest[1..end];
+        let decoded = match entity {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|n| n.parse().ok()))
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(c) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// An element's text, entities decoded and CDATA unwrapped
+fn xml_text(raw: &str) -> String {
+    let mut out = String::new();
+    let mut rest = raw;
+    while let Some(at) = rest.find("<![CDATA[") {
+        out.push_str(&unescape_xml(&rest[..at]));
+        let inner = &rest[at + 9..];
+        let end = inner.find("]]>").unwrap_or(inner.len());
+        out.push_str(&inner[..end]);
+        rest = inner.get(end + 3..).unwrap_or("");
+    }
+    out.push_str(&unescape_xml(rest));
+    out.trim().to_string()
+}
+
+fn first_text(xml: &str, name: &str) -> String {
+    elements(xml, name).first().map(|e| xml_text(e)).unwrap_or_default()
+}
+
+/// "https://dav.example.com" out of "https://dav.example.com/calendars/me/"
+fn origin(url: &str) -> &str {
+    let after = url.find("://").map_or(0, |i| i + 3);
+    url[after..].find('/').map_or(url, |i| &url[..after + i])
+}
+
+/// A key as compared: a server may escape an href differently from how it was written
+fn unescaped(key: &str) -> String {
+    let bytes = key.as_bytes();
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let byte = key.get(i + 1..i + 3).and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match byte {
+            Some(byte) if bytes[i] == b'%' => {
+                out.push(byte);
+                i += 3;
+            }
+            _ => {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+/// `s` percent-encoded for a query string: all but letters, digits and `-._~`
+fn query_escaped(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            b => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn gtask(item: &Value) -> Task {
+    let text = |field: &str| item[field].as_str().unwrap_or_default().to_string();
+    Task {
+        key: text("id"),
+        etag: text("etag"),
+        uid: text("id"),
+        title: text("title"),
+        done: item["status"] == "completed",
+        // Google keeps only the date of a due time
+        due_at: item["due"].as_str().and_then(|d| d.get(..10)).and_then(due_date),
+        remind_at: None,
+    }
+}
+
+fn gtask_body(todo: &Todo) -> String {
+    let due = todo
+        .due_at
+        .as_ref()
+        .and_then(to_local)
+        .map(|d| d.format("%Y-%m-%dT00:00:00.000Z").to_string());
+    let completed = todo
+        .done
+        .then(|| rfc3339(&todo.done_at.unwrap_or_else(BsonDateTime::now)));
+    json!({
+        "title": todo.title,
+        "status": if todo.done { "completed" } else { "needsAction" },
+        "due": due,
+        "completed": completed,
+    })
+    .to_string()
+}
+
+impl Server {
+    /// What the links to this server are saved under
+    pub fn name(&self) -> String {
+        match self {
+            Server::CalDav { url } => format!("caldav:{}", url.trim_end_matches('/')),
+            Server::GoogleTasks { list, .. } => format!("gtasks:{}", list),
+        }
+    }
+
+    /// A todo as the server would hold it: CalDAV keeps whole seconds and only pending
+    /// todos' reminders; Google Tasks keeps only the due date and no reminders
+    fn shape(&self, todo: &Todo) -> Task {
+        let mut task = Task {
+            key: String::new(),
+            etag: String::new(),
+            uid: ics::uid(todo),
+            title: todo.title.clone(),
+            done: todo.done,
+            due_at: todo.due_at.map(whole_seconds),
+            remind_at: todo.remind_at.filter(|_| !todo.done).map(whole_seconds),
+        };
+        if let Server::GoogleTasks { .. } = self {
+            let date = todo.due_at.as_ref().and_then(to_local);
+            task.due_at = date.and_then(|d| due_date(&d.format("%Y-%m-%d").to_string()));
+            task.remind_at = None;
+        }
+        task
+    }
+
+    fn check(&self, response: &Response, what: &str) -> Result<()> {
+        if response.is_success() {
+            return Ok(());
+        }
+        let hint = match (response.status, self) {
+            (401 | 403, Server::CalDav { .. }) => "; check the login for it in ~/.netrc",
+            (401, Server::GoogleTasks { .. }) => "; TODO_GOOGLE_TOKEN may have expired",
+            _ => "",
+        };
+        let detail: String = response.body.trim().chars().take(200).collect();
+        Err(anyhow!("{} failed with HTTP {}{}\n{}", what, response.status, hint, detail))
+    }
+
+    fn auth(&self) -> String {
+        match self {
+            Server::GoogleTasks { token, .. } => format!("Bearer {}", token),
+            Server::CalDav { .. } => String::new(),
+        }
+    }
+
+    /// Every task on the server
+    fn fetch(&self) -> Result<Vec<Task>> {
+        match self {
+            Server::CalDav { url } => {
+                let headers = [("Depth", "1"), ("Content-Type", "application/xml; charset=utf-8")];
+                let response = http::request("REPORT", url, &headers, Some(CALENDAR_QUERY))?;
+                self.check(&response, "Reading the calendar")?;
+                let mut tasks = Vec::new();
+                for entry in elements(&response.body, "response") {
+                    let Some(vtodo) = ics::parse_vtodo(&first_text(entry, "calendar-data")) else {
+                        continue;
+                    };
+                    let href = first_text(entry, "href");
+                    tasks.push(Task {
+                        key: href.strip_prefix(origin(&href)).unwrap_or(&href).to_string(),
+                        etag: first_text(entry, "getetag"),
+                        uid: vtodo.uid,
+                        title: vtodo.summary,
+                        done: vtodo.completed,
+                        due_at: vtodo.due,
+                        remind_at: vtodo.alarm.filter(|_| !vtodo.completed),
+                    });
+                }
+                Ok(tasks)
+            }
+            Server::GoogleTasks { list, .. } => {
+                let auth = self.auth();
+                let mut tasks = Vec::new();
+                let mut page = String::new();
+                loop {
+                    let mut url = format!(
+                        "{}/{}/tasks?showCompleted=true&showHidden=true&maxResults=100",
+                        GOOGLE_TASKS, list
+                    );
+                    if !page.is_empty() {
+                        url.push_str(&format!("&pageToken={}", query_escaped(&page)));
+                    }
+                    let response = http::request("GET", &url, &[("Authorization", &auth)], None)?;
+                    self.check(&response, "Reading the task list")?;
+                    let body: Value = serde_json::from_str(&response.body)
+                        .context("Google Tasks sent back something other than JSON")?;
+                    let items = body["items"].as_array().into_iter().flatten();
+                    tasks.extend(items.filter(|i| i["deleted"] != true).map(gtask));
+                    match body["nextPageToken"].as_str() {
+                        Some(next) => page = next.to_string(),
+                        None => break,
+                    }
+                }
+                Ok(tasks)
+            }
+        }
+    }
+
+    /// Put a todo on the server, returning where it went and its etag
+    fn create(&self, todo: &Todo) -> Result<(String, String)> {
+        match self {
+            Server::CalDav { url } => {
+                let path = url.strip_prefix(origin(url)).unwrap_or_default();
+                let key = format!("{}/{}.ics", path.trim_end_matches('/'), todo.id.to_hex());
+                let headers = [
+                    ("Content-Type", "text/calendar; charset=utf-8"),
+                    ("If-None-Match", "*"),
+                ];
+                let body = ics::render_vtodo(todo, &ics::uid(todo));
+                let target = format!("{}{}", origin(url), key);
+                let response = http::request("PUT", &target, &headers, Some(&body))?;
+                self.check(&response, &format!("Adding \"{}\"", todo.title))?;
+                Ok((key, response.header("ETag").unwrap_or_default().to_string()))
+            }
+            Server::GoogleTasks { list, .. } => {
+                let auth = self.auth();
+                let headers = [("Authorization", auth.as_str()), ("Content-Type", "application/json")];
+                let url = format!("{}/{}/tasks", GOOGLE_TASKS, list);
+                let response = http::request("POST", &url, &headers, Some(&gtask_body(todo)))?;
+                self.check(&response, &format!("Adding \"{}\"", todo.title))?;
+                let body: Value = serde_json::from_str(&response.body)
+                    .context("Google Tasks sent back something other than JSON")?;
+                let task = gtask(&body);
+                Ok((task.key, task.etag))
+            }
+        }
+    }
+
+    /// Overwrite the server's copy with the todo, returning the new etag, or None if the
+    /// server's copy changed in the meantime
+    fn update(&self, todo: &Todo, task: &Task) -> Result<Option<String>> {
+        let what = format!("Updating \"{}\"", todo.title);
+        match self {
+            Server::CalDav { url } => {
+                let mut headers = vec![("Content-Type", "text/calendar; charset=utf-8")];
+                if !task.etag.is_empty() {
+                    headers.push(("If-Match", task.etag.as_str()));
+                }
+                let body = ics::render_vtodo(todo, &task.uid);
+                let target = format!("{}{}", origin(url), task.key);
+                let response = http::request("PUT", &target, &headers, Some(&body))?;
+                if response.status == 412 {
+                    return Ok(None);
+                }
+                self.check(&response, &what)?;
+                Ok(Some(response.header("ETag").unwrap_or_default().to_string()))
+            }
+            Server::GoogleTasks { list, .. } => {
+                let auth = self.auth();
+                let headers = [("Authorization", auth.as_str()), ("Content-Type", "application/json")];
+                let url = format!("{}/{}/tasks/{}", GOOGLE_TASKS, list, task.key);
+                let response = http::request("PATCH", &url, &headers, Some(&gtask_body(todo)))?;
+                self.check(&response, &what)?;
+                let body: Value = serde_json::from_str(&response.body)
+                    .context("Google Tasks sent back something other than JSON")?;
+                Ok(Some(gtask(&body).etag))
+            }
+        }
+    }
+
+    /// Take a task off the server; one already gone, or changed since, is left be
+    fn delete(&self, task: &Task) -> Result<()> {
+        let response = match self {
+            Server::CalDav { url } => {
+                let mut headers = Vec::new();
+                if !task.etag.is_empty() {
+                    headers.push(("If-Match", task.etag.as_str()));
+                }
+                let target = format!("{}{}", origin(url), task.key);
+                http::request("DELETE", &target, &headers, None)?
+            }
+            Server::GoogleTasks { list, .. } => {
+                let auth = self.auth();
+                let url = format!("{}/{}/tasks/{}", GOOGLE_TASKS, list, task.key);
+                http::request("DELETE", &url, &[("Authorization", &auth)], None)?
+            }
+        };
+        if matches!(response.status, 404 | 410 | 412) {
+            return Ok(());
+        }
+        self.check(&response, &format!("Removing \"{}\"", task.title))
+    }
+}
+
+/// Bring a todo in line with the server's copy, leaving alone what the server can't hold
+/// (the time of a Google due date, say). Returns whether anything changed.
+fn pull(db: &TodoDb, server: &Server, todo: &Todo, task: &Task) -> Result<bool> {
+    let ours = server.shape(todo);
+    let mut changed = false;
+    if ours.title != task.title && !task.title.is_empty() {
+        db.update_title(&todo.id, &task.title)?;
+        changed = true;
+    }
+    if ours.done != task.done {
+        if task.done {
+            db.mark_done(&todo.id)?;
+        } else {
+            db.mark_undone(&todo.id)?;
+        }
+        changed = true;
+    }
+    if ours.due_at != task.due_at {
+        db.set_due(&todo.id, task.due_at)?;
+        changed = true;
+    }
+    if ours.remind_at != task.remind_at && !task.done {
+        db.set_reminder(&todo.id, task.remind_at)?;
+        changed = true;
+    }
+    Ok(changed)
+}
+
+fn link(todo_id: ObjectId, key: String, etag: String, task: &Task) -> RemoteLink {
+    RemoteLink { todo_id, key, etag, fingerprint: fingerprint(task) }
+}
+
+/// A new todo for a task first seen on the server
+fn add_todo(db: &TodoDb, task: &Task) -> Result<Todo> {
+    let mut todo = Todo::new(task.title.clone());
+    todo.done = task.done;
+    todo.done_at = task.done.then(BsonDateTime::now);
+    todo.due_at = task.due_at;
+    todo.remind_at = task.remind_at;
+    db.insert(&todo)?;
+    Ok(todo)
+}
+
+/// Push todos to a task server and pull its tasks in. Each linked todo is compared with
+/// how it stood at the last sync: a change on one side is copied to the other, and when
+/// both sides changed, the server's copy wins. A todo deleted on one side is deleted on the
+/// other, unless the other side changed it since. New pending todos on either side are
+/// added to the other.
+pub fn sync(db: &TodoDb, server: &Server) -> Result<Synced> {
+    let name = server.name();
+    let mut links: HashMap<ObjectId, RemoteLink> =
+        db.get_remote_links(&name)?.into_iter().map(|l| (l.todo_id, l)).collect();
+    let result = exchange(db, server, &mut links);
+    // Saved even if the server stopped answering partway, so nothing is added twice
+    let mut links: Vec<RemoteLink> = links.into_values().collect();
+    links.sort_by_key(|l| l.todo_id);
+    db.set_remote_links(&name, &links)?;
+    result
+}
+
+fn exchange(
+    db: &TodoDb,
+    server: &Server,
+    links: &mut HashMap<ObjectId, RemoteLink>,
+) -> Result<Synced> {
+    let tasks = server.fetch()?;
+    let mut remote: HashMap<String, &Task> = tasks.iter().map(|t| (unescaped(&t.key), t)).collect();
+    let todos = db.list_all()?;
+    let mut local: HashMap<ObjectId, &Todo> = todos.iter().map(|t| (t.id, t)).collect();
+    let known: HashSet<ObjectId> = links.keys().copied().collect();
+    let mut synced = Synced { pushed: 0, pulled: 0, conflicts: Vec::new() };
+
+    let mut linked: Vec<RemoteLink> = links.values().cloned().collect();
+    linked.sort_by_key(|l| l.todo_id);
+    for before in linked {
+        let todo = local.remove(&before.todo_id);
+        let task = remote.remove(&unescaped(&before.key));
+        let changed_there =
+            task.is_some_and(|t| t.etag != before.etag && fingerprint(t) != before.fingerprint);
+        let changed_here = todo.is_some_and(|t| fingerprint(&server.shape(t)) != before.fingerprint);
+        links.remove(&before.todo_id);
+
+        match (todo, task) {
+            (Some(todo), Some(task)) if changed_there => {
+                if changed_here {
+                    synced.conflicts.push(format!("\"{}\": kept the server's change", todo.title));
+                }
+                if pull(db, server, todo, task)? {
+                    synced.pulled += 1;
+                }
+                links.insert(todo.id, link(todo.id, before.key, task.etag.clone(), task));
+            }
+            (Some(todo), Some(task)) if changed_here => {
+                let ours = server.shape(todo);
+                match server.update(todo, task)? {
+                    Some(etag) => {
+                        links.insert(todo.id, link(todo.id, before.key, etag, &ours));
+                        synced.pushed += 1;
+                    }
+                    // Changed on the server meanwhile; the next sync brings that in
+                    None => {
+                        links.insert(todo.id, before);
+                    }
+                }
+            }
+            (Some(todo), Some(task)) => {
+                let etag = task.etag.clone();
+                links.insert(todo.id, RemoteLink { etag, ..before });
+            }
+            // Deleted on the server, though changed here since: put it back
+            (Some(todo), None) if changed_here => {
+                let (key, etag) = server.create(todo)?;
+                links.insert(todo.id, link(todo.id, key, etag, &server.shape(todo)));
+                synced.pushed += 1;
+            }
+            (Some(todo), None) => {
+                db.trash_many(std::slice::from_ref(todo))?;
+                synced.pulled += 1;
+            }
+            // Deleted here, though changed on the server since: bring it back
+            (None, Some(task)) if changed_there => {
+                let todo = add_todo(db, task)?;
+                links.insert(todo.id, link(todo.id, before.key, task.etag.clone(), task));
+                synced.pulled += 1;
+            }
+            (None, Some(task)) => {
+                server.delete(task)?;
+                synced.pushed += 1;
+            }
+            (None, None) => {}
+        }
+    }
+
+    // New on the server, or exported from here before (matched by UID)
+    let mut unlinked: Vec<&Task> = remote.into_values().collect();
+    unlinked.sort_by(|a, b| a.key.cmp(&b.key));
+    for task in unlinked {
+        let exported = task
+            .uid
+            .strip_suffix("@rust-todo")
+            .and_then(|hex| ObjectId::parse_str(hex).ok());
+        if exported.is_some_and(|id| known.contains(&id)) {
+            continue;
+        }
+        if let Some(todo) = exported.and_then(|id| local.remove(&id)) {
+            if pull(db, server, todo, task)? {
+                synced.pulled += 1;
+            }
+            links.insert(todo.id, link(todo.id, task.key.clone(), task.etag.clone(), task));
+            continue;
+        }
+        // Whatever was finished there before syncing began stays there
+        if task.done || task.title.is_empty() {
+            continue;
+        }
+        let todo = add_todo(db, task)?;
+        links.insert(todo.id, link(todo.id, task.key.clone(), task.etag.clone(), task));
+        synced.pulled += 1;
+    }
+
+    // New here
+    let mut fresh: Vec<&Todo> = local.into_values().filter(|t| !t.done).collect();
+    fresh.sort_by_key(|t| t.created_at);
+    for todo in fresh {
+        let (key, etag) = server.create(todo)?;
+        links.insert(todo.id, link(todo.id, key, etag, &server.shape(todo)));
+        synced.pushed += 1;
+    }
+    Ok(synced)
+}
@@ -0,0 +1,201 @@
+use std::sync::RwLock;
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveTime, TimeZone, Weekday};
+
+use crate::remind::parse_minutes;
+
+/// A repeating schedule parsed from phrases like "every monday 9am"
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Schedule {
+    /// Fixed interval in minutes: "every 2h", "every 3d"
+    Interval(i64),
+    /// Every day at a time: "every day 9am"
+    Daily(NaiveTime),
+    /// Monday to Friday at a time: "every weekday 8:30"
+    Weekdays(NaiveTime),
+    /// Working days at a time, skipping the configured weekend and holidays:
+    /// "every business day 9am"
+    BusinessDays(NaiveTime),
+    /// One day a week at a time: "every monday 9am"
+    Weekly(Weekday, NaiveTime),
+}
+
+const DEFAULT_TIME: (u32, u32) = (9, 0);
+
+/// Days off for business-day scheduling
+struct Calendar {
+    weekend: Vec<Weekday>,
+    holidays: Vec<NaiveDate>,
+}
+
+static CALENDAR: RwLock<Option<Calendar>> = RwLock::new(None);
+
+/// Set the business calendar from the `weekend` config key and the stored holidays;
+/// the daemon calls this again on each check to pick up changes
+pub fn set_calendar(weekend: &[String], holidays: &[NaiveDate]) -> Result<()> {
+    let weekend: Vec<Weekday> = weekend
+        .iter()
+        .map(|day| {
+            parse_weekday(&day.trim().to_lowercase())
+                .ok_or_else(|| anyhow!("Invalid weekend day '{}'. Use mon, tue, ... sun", day))
+        })
+        .collect::<Result<_>>()?;
+    if weekend.len() >= 7 {
+        return Err(anyhow!("The weekend can't cover the whole week"));
+    }
+    if let Ok(mut calendar) = CALENDAR.write() {
+        *calendar = Some(Calendar {
+            weekend,
+            holidays: holidays.to_vec(),
+        });
+    }
+    Ok(())
+}
+
+/// Whether `date` is a working day: not in the weekend (Saturday and Sunday unless
+/// configured otherwise) and not a holiday
+pub fn is_business_day(date: NaiveDate) -> bool {
+    match CALENDAR.read().as_deref() {
+        Ok(Some(calendar)) => {
+            !calendar.weekend.contains(&date.weekday()) && !calendar.holidays.contains(&date)
+        }
+        _ => !matches!(date.weekday(), Weekday::Sat | Weekday::Sun),
+    }
+}
+
+/// Further ahead than this many business days (about ten years) isn't worth counting
+const MAX_BUSINESS_DAYS: usize = 10 * 366;
+
+/// The `n`th business day after `date`, so `n = 1` is the next one; `None` past
+/// `MAX_BUSINESS_DAYS` or the last date chrono can represent
+pub fn add_business_days(date: NaiveDate, n: usize) -> Option<NaiveDate> {
+    if n > MAX_BUSINESS_DAYS {
+        return None;
+    }
+    // A year of holidays in a row is the most a calendar can skip
+    let span = i64::try_from(n).ok()?.checked_add(1)?.checked_mul(366)?;
+    (1..=span)
+        .map_while(|offset| date.checked_add_signed(Duration::days(offset)))
+        .filter(|&d| is_business_day(d))
+        .nth(n.checked_sub(1)?)
+}
+
+impl Schedule {
+    /// Parse "every <interval|day|weekday|monday..> [at] [time]"; the leading "every" is optional
+    pub fn parse(input: &str) -> Result<Self> {
+        let input = input.trim().to_lowercase();
+        let joined = input
+            .replace("business days", "businessday")
+            .replace("business day", "businessday");
+        let words: Vec<&str> = joined
+            .split_whitespace()
+            .filter(|w| *w != "every" && *w != "at")
+            .collect();
+
+        let invalid = || {
+            anyhow!(
+                "Invalid schedule: '{}'\nExamples: every 2h, every day 9am, every weekday 8:30, every business day 9am, every monday 9am",
+                input
+            )
+        };
+
+        let (first, rest) = words.split_first().ok_or_else(invalid)?;
+        let time = match rest {
+            [] => NaiveTime::from_hms_opt(DEFAULT_TIME.0, DEFAULT_TIME.1, 0).ok_or_else(invalid)?,
+            [t] => parse_clock(t).ok_or_else(invalid)?,
+            _ => return Err(invalid()),
+        };
+
+        if let Some(minutes) = parse_minutes(first) {
+            if !rest.is_empty() || minutes <= 0 {
+                return Err(invalid());
+            }
+            return Ok(Self::Interval(minutes));
+        }
+
+        match *first {
+            "day" | "daily" => Ok(Self::Daily(time)),
+            "weekday" | "weekdays" => Ok(Self::Weekdays(time)),
+            "businessday" | "workday" | "workdays" => Ok(Self::BusinessDays(time)),
+            day => parse_weekday(day)
+                .map(|d| Self::Weekly(d, time))
+                .ok_or_else(invalid),
+        }
+    }
+
+    /// The first firing strictly after `after`
+    pub fn next_after(&self, after: DateTime<Local>) -> Option<DateTime<Local>> {
+        let time = match self {
+            Self::Interval(minutes) => return Some(after + Duration::minutes(*minutes)),
+            Self::Daily(t) | Self::Weekdays(t) | Self::BusinessDays(t) | Self::Weekly(_, t) => *t,
+        };
+
+        // A matching day always occurs within the next 8 days, or the next year when
+        // holidays are involved
+        let horizon = if matches!(self, Self::BusinessDays(_)) { 366 } else { 8 };
+        (0..=horizon).find_map(|offset| {
+            let date = after.date_naive() + Duration::days(offset);
+            let matches = match self {
+                Self::Weekdays(_) => !matches!(date.weekday(), Weekday::Sat | Weekday::Sun),
+                Self::BusinessDays(_) => is_business_day(date),
+                Self::Weekly(day, _) => date.weekday() == *day,
+                _ => true,
+            };
+            if !matches {
+                return None;
+            }
+            let candidate = Local.from_local_datetime(&date.and_time(time)).earliest()?;
+            (candidate > after).then_some(candidate)
+        })
+    }
+
+    /// The first firing after `now`, skipping any occurrences missed since `last`
+    pub fn next_since(&self, last: DateTime<Local>, now: DateTime<Local>) -> Option<DateTime<Local>> {
+        let mut next = self.next_after(last)?;
+        while next <= now {
+            next = self.next_after(next)?;
+        }
+        Some(next)
+    }
+}
+
+/// Parse clock times like 9am, 9:30pm, 14:30
+pub fn parse_clock(input: &str) -> Option<NaiveTime> {
+    let input = input.trim().to_lowercase();
+    let (digits, offset) = if let Some(d) = input.strip_suffix("am") {
+        (d, Some(0))
+    } else if let Some(d) = input.strip_suffix("pm") {
+        (d, Some(12))
+    } else {
+        (input.as_str(), None)
+    };
+
+    let (hour, minute) = match digits.split_once(':') {
+        Some((h, m)) => (h.parse::<u32>().ok()?, m.parse::<u32>().ok()?),
+        None => (digits.parse::<u32>().ok()?, 0),
+    };
+
+    let hour = match offset {
+        Some(add) if (1..=12).contains(&hour) => hour % 12 + add,
+        Some(_) => return None,
+        // A bare number without am/pm or minutes is ambiguous
+        None if !digits.contains(':') => return None,
+        None => hour,
+    };
+
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+pub fn parse_weekday(input: &str) -> Option<Weekday> {
+    match input {
+        "mon" | "monday" => Some(Weekday::Mon),
+        "tue" | "tues" | "tuesday" => Some(Weekday::Tue),
+        "wed" | "wednesday" => Some(Weekday::Wed),
+        "thu" | "thur" | "thurs" | "thursday" => Some(Weekday::Thu),
+        "fri" | "friday" => Some(Weekday::Fri),
+        "sat" | "saturday" => Some(Weekday::Sat),
+        "sun" | "sunday" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
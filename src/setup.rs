@@ -0,0 +1,101 @@
+use std::io::IsTerminal;
+
+use anyhow::{Context, Result};
+
+use crate::config::{config_path, default_data_dir, Config};
+use crate::db::db_path;
+use crate::display::{print_info, print_quickstart, print_success, print_warning, prompt};
+use crate::schedule::parse_clock;
+
+/// True on the very first run: no config file, no database, and someone at the keyboard
+pub fn is_first_run() -> Result<bool> {
+    // A database picked with TODO_DB_PATH says the user already knows their way around
+    if !std::io::stdin().is_terminal()
+        || config_path()?.exists()
+        || std::env::var_os("TODO_DB_PATH").is_some()
+    {
+        return Ok(false);
+    }
+    Ok(!db_path(&Config::default())?.exists())
+}
+
+/// Ask with a default shown in brackets; an empty answer keeps the default
+fn ask(question: &str, default: &str) -> Result<String> {
+    let answer = prompt(&format!("{} [{}]", question, default))?.unwrap_or_default();
+    Ok(if answer.is_empty() {
+        default.to_string()
+    } else {
+        answer
+    })
+}
+
+fn ask_choice(question: &str, choices: &[&str]) -> Result<String> {
+    loop {
+        let answer = ask(&format!("{} ({})", question, choices.join("/")), choices[0])?;
+        if choices.contains(&answer.as_str()) {
+            return Ok(answer);
+        }
+        print_warning(&format!("Please choose one of: {}", choices.join(", ")));
+    }
+}
+
+fn quote(value: &str) -> String {
+    toml::Value::String(value.to_string()).to_string()
+}
+
+/// Interactive first-run setup; writes the config file and prints a cheat-sheet
+pub fn run_wizard() -> Result<()> {
+    let defaults = Config::default();
+    print_info("Welcome! Let's set up todo (press Enter to accept the default).");
+    println!();
+
+    let default_dir = default_data_dir()?;
+    let data_dir = ask(
+        "Where should your todos be stored?",
+        &default_dir.display().to_string(),
+    )?;
+
+    let reminder_time = loop {
+        let time = ask(
+            "Default time for reminders given as a date",
+            &defaults.reminder_time,
+        )?;
+        match parse_clock(&time) {
+            Some(t) => break t.format("%H:%M").to_string(),
+            None => print_warning("Please enter a time like 09:00 or 9am"),
+        }
+    };
+
+    let backend = ask_choice("Notification backend", &["desktop", "push"])?;
+    let push_command = if backend == "push" {
+        Some(ask(
+            "Command to send a push (gets $TODO_TITLE, $TODO_MESSAGE)",
+            "curl -s -d \"$TODO_TITLE\" ntfy.sh/my-todos",
+        )?)
+    } else {
+        None
+    };
+
+    let color = ask_choice("Colors", &["auto", "always", "never"])?;
+
+    let mut contents = String::from("# Created by the todo setup wizard\n");
+    contents.push_str(&format!("data_dir = {}\n", quote(&data_dir)));
+    contents.push_str(&format!("reminder_time = {}\n", quote(&reminder_time)));
+    contents.push_str(&format!("notification_backend = {}\n", quote(&backend)));
+    if let Some(ref command) = push_command {
+        contents.push_str(&format!("push_command = {}\n", quote(command)));
+    }
+    contents.push_str(&format!("color = {}\n", quote(&color)));
+
+    let path = config_path()?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).context("failed to create config directory")?;
+    }
+    std::fs::write(&path, contents)
+        .with_context(|| format!("failed to write {}", path.display()))?;
+
+    println!();
+    print_success(&format!("Saved settings to {}", path.display()));
+    print_quickstart();
+    Ok(())
+}
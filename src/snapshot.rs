@@ -0,0 +1,146 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use polodb_core::bson;
+use serde::{Deserialize, Serialize};
+
+use crate::models::Todo;
+
+/// A chunk ends after a todo whose id hashes to a multiple of this, so chunks hold about
+/// this many todos and where they split doesn't move when todos are added or edited
+const CHUNK_SPREAD: u64 = 32;
+
+/// Chunks no manifest mentions are removed once they're this old; younger ones may belong
+/// to a device that's still writing its snapshot
+const ORPHAN_AGE: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Serialize, Deserialize)]
+struct Chunk {
+    todos: Vec<Todo>,
+}
+
+/// What `push` had to write
+pub struct Pushed {
+    pub chunks: usize,
+    pub written: usize,
+    pub bytes: u64,
+    pub bytes_written: u64,
+}
+
+/// FNV-1a: stable across builds and platforms, which is all chunk names need
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| {
+        (hash ^ b as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+fn manifest_path(dir: &Path, device: &str) -> PathBuf {
+    dir.join(format!("{}.manifest", device))
+}
+
+fn chunk_path(dir: &Path, name: &str) -> PathBuf {
+    dir.join("chunks").join(name)
+}
+
+/// Write through a side file so a reader (or the service carrying the folder) never sees
+/// half a file
+pub(crate) fn write_whole(path: &Path, bytes: &[u8]) -> Result<()> {
+    let partial = path.with_extension("partial");
+    fs::write(&partial, bytes).with_context(|| format!("failed to write {}", partial.display()))?;
+    fs::rename(&partial, path).with_context(|| format!("failed to write {}", path.display()))
+}
+
+/// Every device's manifest in the folder, as (device, chunk names)
+fn manifests(dir: &Path) -> Result<Vec<(String, Vec<String>)>> {
+    let entries = fs::read_dir(dir).with_context(|| format!("failed to read {}", dir.display()))?;
+    let mut found = Vec::new();
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Some(device) = name.strip_suffix(".manifest") else {
+            continue;
+        };
+        let text = fs::read_to_string(entry.path())
+            .with_context(|| format!("failed to read {}", entry.path().display()))?;
+        found.push((device.to_string(), text.lines().map(String::from).collect()));
+    }
+    found.sort();
+    Ok(found)
+}
+
+/// Save `todos` as `device`'s snapshot in `dir`. Only chunks the folder doesn't have yet
+/// are written, so a sync service uploads just the parts that changed.
+pub fn push(dir: &Path, device: &str, todos: &[Todo]) -> Result<Pushed> {
+    fs::create_dir_all(dir.join("chunks"))
+        .with_context(|| format!("failed to create {}", dir.display()))?;
+
+    let mut todos = todos.to_vec();
+    todos.sort_by_key(|t| t.id);
+    let mut groups = Vec::new();
+    let mut group = Vec::new();
+    for todo in todos {
+        let boundary = fnv1a(&todo.id.bytes()).is_multiple_of(CHUNK_SPREAD);
+        group.push(todo);
+        if boundary {
+            groups.push(std::mem::take(&mut group));
+        }
+    }
+    if !group.is_empty() {
+        groups.push(group);
+    }
+
+    let mut pushed = Pushed { chunks: groups.len(), written: 0, bytes: 0, bytes_written: 0 };
+    let mut names = Vec::new();
+    for todos in groups {
+        let bytes = bson::to_vec(&Chunk { todos }).context("failed to encode snapshot")?;
+        let name = format!("{:016x}", fnv1a(&bytes));
+        let path = chunk_path(dir, &name);
+        pushed.bytes += bytes.len() as u64;
+        if !path.exists() {
+            write_whole(&path, &bytes)?;
+            pushed.written += 1;
+            pushed.bytes_written += bytes.len() as u64;
+        }
+        names.push(name);
+    }
+    write_whole(&manifest_path(dir, device), names.join("\n").as_bytes())?;
+
+    // Chunks no device's snapshot uses any more
+    let used: HashSet<String> = manifests(dir)?.into_iter().flat_map(|(_, names)| names).collect();
+    for entry in fs::read_dir(dir.join("chunks"))?.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let old = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .is_ok_and(|t| t.elapsed().unwrap_or_default() > ORPHAN_AGE);
+        if old && !used.contains(&name) {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+    Ok(pushed)
+}
+
+/// Every other device's snapshot in `dir`
+pub fn read_others(dir: &Path, device: &str) -> Result<Vec<(String, Vec<Todo>)>> {
+    let mut snapshots = Vec::new();
+    for (other, names) in manifests(dir)? {
+        if other == device {
+            continue;
+        }
+        let mut todos = Vec::new();
+        for name in names {
+            let path = chunk_path(dir, &name);
+            let bytes = fs::read(&path).ok().filter(|b| format!("{:016x}", fnv1a(b)) == name);
+            let bytes = bytes.ok_or_else(|| {
+                anyhow!("{}'s snapshot is missing chunk {}; it may still be arriving", other, name)
+            })?;
+            let chunk: Chunk = bson::from_slice(&bytes)
+                .with_context(|| format!("failed to decode {}", path.display()))?;
+            todos.extend(chunk.todos);
+        }
+        snapshots.push((other, todos));
+    }
+    Ok(snapshots)
+}
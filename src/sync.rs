@@ -0,0 +1,293 @@
+use std::cmp::Reverse;
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, Context, Result};
+use polodb_core::bson::DateTime as BsonDateTime;
+
+use crate::config::Config;
+use crate::db::{db_path, TodoDb};
+use crate::display::{
+    json_output, print_info, print_success, print_sync_status, print_warning, prompt,
+};
+use crate::{oplog, remote, snapshot};
+
+/// Waits out the debounce delay, then syncs only if no later command has re-queued; a
+/// successful sync leaves its time in `TODO_SYNC_DONE`
+const DEBOUNCED: &str = r#"sleep "$TODO_SYNC_DELAY"
+[ "$(cat "$TODO_SYNC_STAMP" 2>/dev/null)" = "$TODO_SYNC_TOKEN" ] || exit 0
+rm -f "$TODO_SYNC_STAMP"
+eval "$TODO_SYNC_COMMAND" && date +%s > "$TODO_SYNC_DONE""#;
+
+/// What `todo sync status` reports
+pub struct SyncStatus {
+    pub command: Option<String>,
+    pub dir: Option<String>,
+    /// Seconds of quiet before an automatic sync, if auto-sync is on
+    pub auto_delay: Option<u64>,
+    pub device: Option<String>,
+    /// Every device's last successful sync, most recent first
+    pub devices: Vec<(String, BsonDateTime)>,
+    /// Undoable changes made here since this device last synced
+    pub pending: usize,
+    /// An automatic sync is waiting out its delay
+    pub queued: bool,
+    pub conflicts: Vec<String>,
+}
+
+/// Runs `todo sync` itself, for background syncs that write a snapshot to `sync_dir`
+const SYNC_ITSELF: &str = r#""$TODO_EXE" --db "$TODO_DB_PATH" sync"#;
+
+fn check_configured(config: &Config) -> Result<()> {
+    if config.sync_command.is_none() && config.sync_dir.is_none() {
+        return Err(anyhow!("No sync_command or sync_dir configured"));
+    }
+    Ok(())
+}
+
+/// Byte counts for people: 512 B, 3.4 KB, 1.2 MB
+fn size(bytes: u64) -> String {
+    match bytes {
+        0..1_000 => format!("{} B", bytes),
+        1_000..1_000_000 => format!("{:.1} KB", bytes as f64 / 1e3),
+        _ => format!("{:.1} MB", bytes as f64 / 1e6),
+    }
+}
+
+/// This machine's name in `todo sync status`, kept in the data directory since the
+/// database itself travels between devices
+fn device_path(config: &Config) -> Result<PathBuf> {
+    Ok(config.data_dir()?.join("device-name"))
+}
+
+pub fn device_name(config: &Config) -> Result<Option<String>> {
+    let name = fs::read_to_string(device_path(config)?).unwrap_or_default();
+    Ok(Some(name.trim().to_string()).filter(|n| !n.is_empty()))
+}
+
+fn hostname() -> String {
+    let from_env = std::env::var("HOSTNAME")
+        .or_else(|_| std::env::var("COMPUTERNAME"))
+        .ok()
+        .filter(|h| !h.trim().is_empty());
+    from_env
+        .or_else(|| {
+            let output = Command::new("hostname").output().ok()?;
+            let name = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            (!name.is_empty()).then_some(name)
+        })
+        .unwrap_or_else(|| "this-device".to_string())
+}
+
+/// The device name, settled on the first sync: asked for when `ask` and in a terminal,
+/// else the host name
+fn name_device(config: &Config, ask: bool) -> Result<String> {
+    if let Some(name) = device_name(config)? {
+        return Ok(name);
+    }
+    let mut name = hostname();
+    if ask && !json_output() && io::stdin().is_terminal() {
+        let answer = prompt(&format!("Name this device for todo sync status [{}]:", name))?;
+        if let Some(answer) = answer.filter(|a| !a.is_empty()) {
+            name = answer;
+        }
+    }
+    let path = device_path(config)?;
+    fs::write(&path, &name).with_context(|| format!("failed to write {}", path.display()))?;
+    print_info(&format!("This device is \"{}\" in todo sync status", name));
+    Ok(name)
+}
+
+/// Where a successful sync from this device leaves its time (seconds since the epoch)
+fn done_path(config: &Config) -> Result<PathBuf> {
+    Ok(config.data_dir()?.join("sync.last"))
+}
+
+fn last_success(config: &Config) -> Result<Option<BsonDateTime>> {
+    let text = fs::read_to_string(done_path(config)?).unwrap_or_default();
+    Ok(text.trim().parse::<i64>().ok().map(|s| BsonDateTime::from_millis(s * 1000)))
+}
+
+/// Copy this device's last successful sync (which a background sync may have left) into
+/// the database, so other devices see it once it's synced
+fn record_success(db: &TodoDb, config: &Config, device: &str) -> Result<()> {
+    let Some(at) = last_success(config)? else {
+        return Ok(());
+    };
+    let known = db.get_sync_devices()?.into_iter().find(|(name, _)| name == device);
+    if known.is_none_or(|(_, synced)| synced < at) {
+        db.set_device_synced(device, at)?;
+    }
+    Ok(())
+}
+
+/// Append this device's changes to its log in `dir` and merge in every other device's
+fn exchange(db: &TodoDb, config: &Config, dir: &Path, device: &str) -> Result<()> {
+    let synced = oplog::sync(db, dir, device, &oplog::state_path(&db_path(config)?))?;
+    for conflict in &synced.conflicts {
+        print_warning(&format!("Changed on two devices: {}", conflict));
+    }
+    print_info(&format!(
+        "Log in {}: sent {} change(s), received {}, {} todo(s) updated here",
+        dir.display(),
+        synced.sent,
+        synced.received,
+        synced.changed
+    ));
+    Ok(())
+}
+
+/// Write the snapshot to `sync_dir` and run `sync_command`, whichever are set, and wait
+/// for them (`todo sync`)
+pub fn run(db: &TodoDb, config: &Config) -> Result<()> {
+    check_configured(config)?;
+    let device = name_device(config, true)?;
+    record_success(db, config, &device)?;
+
+    match (config.sync_dir(), config.sync_format.as_str()) {
+        (Some(dir), "oplog") => exchange(db, config, &dir, &device)?,
+        (Some(dir), "snapshot") => {
+            let pushed = snapshot::push(&dir, &device, &db.list_all()?)?;
+            print_info(&format!(
+                "Snapshot in {}: wrote {} of {} chunk(s), {} of {}",
+                dir.display(),
+                pushed.written,
+                pushed.chunks,
+                size(pushed.bytes_written),
+                size(pushed.bytes)
+            ));
+        }
+        (Some(_), other) => {
+            return Err(anyhow!("Unknown sync_format '{}'. Use snapshot or oplog", other));
+        }
+        (None, _) => {}
+    }
+
+    if let Some(ref command) = config.sync_command {
+        let status = Command::new("sh")
+            .args(["-c", command])
+            .env("TODO_DB_PATH", db_path(config)?)
+            .status()
+            .map_err(|e| anyhow!("Failed to run sync_command: {}", e))?;
+        if !status.success() {
+            return Err(anyhow!("sync_command exited with {}", status));
+        }
+    }
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    let path = done_path(config)?;
+    fs::write(&path, now.to_string())
+        .with_context(|| format!("failed to write {}", path.display()))?;
+    record_success(db, config, &device)
+}
+
+/// `todo sync caldav <url>` / `todo sync gtasks [list]`: push todos to a task server and
+/// pull its tasks in
+pub fn remote(db: &TodoDb, config: &Config, args: &[String]) -> Result<()> {
+    if config.read_only {
+        return Err(anyhow!("The database is open read-only; a sync would change it"));
+    }
+    let server = match (args.first().map(String::as_str), args.get(1)) {
+        (Some("caldav"), Some(url)) => remote::Server::CalDav { url: url.clone() },
+        (Some("caldav"), None) => return Err(anyhow!("Usage: todo sync caldav <calendar-url>")),
+        (_, list) => {
+            let token = std::env::var("TODO_GOOGLE_TOKEN")
+                .ok()
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty())
+                .ok_or_else(|| {
+                    anyhow!("Set TODO_GOOGLE_TOKEN to an OAuth access token for Google Tasks")
+                })?;
+            let list = list.cloned().unwrap_or_else(|| "@default".to_string());
+            remote::Server::GoogleTasks { list, token }
+        }
+    };
+
+    let synced = remote::sync(db, &server)?;
+    for conflict in &synced.conflicts {
+        print_warning(&format!("Changed here and on the server: {}", conflict));
+    }
+    print_success(&format!(
+        "Synced with {}: pushed {} change(s), pulled {}",
+        server.name(),
+        synced.pushed,
+        synced.pulled
+    ));
+    Ok(())
+}
+
+/// `todo sync status`: where syncing goes, when each device last synced, and what's
+/// waiting to go out from here
+pub fn status(db: &TodoDb, config: &Config) -> Result<()> {
+    let device = device_name(config)?;
+    if let Some(ref device) = device
+        && !config.read_only
+    {
+        record_success(db, config, device)?;
+    }
+
+    let since = last_success(config)?;
+    let pending = db
+        .list_journal()?
+        .iter()
+        .filter(|e| !e.undone && since.is_none_or(|s| e.at > s))
+        .count();
+    let mut devices = db.get_sync_devices()?;
+    devices.sort_by_key(|d| Reverse(d.1));
+
+    print_sync_status(&SyncStatus {
+        command: config.sync_command.clone(),
+        dir: config.sync_dir.clone(),
+        auto_delay: config.auto_sync.then_some(config.auto_sync_delay),
+        device,
+        devices,
+        pending,
+        queued: config.data_dir()?.join("sync.pending").exists(),
+        conflicts: db.get_merge_conflicts()?,
+    });
+    Ok(())
+}
+
+/// Queue a background sync `auto_sync_delay` seconds from now. Each call replaces the
+/// queued token, so a burst of commands ends in a single sync after the last one.
+pub fn schedule(db: &TodoDb, config: &Config) -> Result<()> {
+    if !config.auto_sync || check_configured(config).is_err() {
+        return Ok(());
+    }
+    let device = name_device(config, false)?;
+    record_success(db, config, &device)?;
+
+    let stamp = config.data_dir()?.join("sync.pending");
+    let token = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos()
+        .to_string();
+    std::fs::write(&stamp, &token).context("failed to queue sync")?;
+
+    // A snapshot needs todo itself, which runs sync_command afterwards
+    let command = match config.sync_command {
+        Some(ref command) if config.sync_dir.is_none() => command.as_str(),
+        _ => SYNC_ITSELF,
+    };
+    let exe = std::env::current_exe().context("failed to find the todo executable")?;
+
+    // Detached and silent: a failed sync never gets in the way of the command that triggered it
+    Command::new("sh")
+        .args(["-c", DEBOUNCED])
+        .env("TODO_SYNC_DELAY", config.auto_sync_delay.to_string())
+        .env("TODO_SYNC_STAMP", &stamp)
+        .env("TODO_SYNC_TOKEN", &token)
+        .env("TODO_SYNC_DONE", done_path(config)?)
+        .env("TODO_SYNC_COMMAND", command)
+        .env("TODO_EXE", exe)
+        .env("TODO_DB_PATH", db_path(config)?)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .context("failed to start background sync")?;
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use anyhow::{anyhow, Result};
+
+const BLOCK: usize = 512;
+
+/// `value` as a NUL-terminated octal field filling `width` bytes
+fn octal(value: u64, width: usize) -> Vec<u8> {
+    let mut field = format!("{:0w$o}", value, w = width - 1).into_bytes();
+    field.push(0);
+    field
+}
+
+fn parse_octal(field: &[u8]) -> Option<u64> {
+    let text = std::str::from_utf8(field).ok()?;
+    u64::from_str_radix(text.trim_matches(['\0', ' ']), 8).ok()
+}
+
+/// Sum of the header bytes with the checksum field counted as spaces
+fn checksum(header: &[u8]) -> u64 {
+    header
+        .iter()
+        .enumerate()
+        .map(|(i, &b)| if (148..156).contains(&i) { b' ' as u64 } else { b as u64 })
+        .sum()
+}
+
+/// A ustar archive of plain files, readable by `tar` anywhere
+pub fn write(files: &[(&str, Vec<u8>)], mtime: u64) -> Result<Vec<u8>> {
+    let mut out = Vec::new();
+    for (name, contents) in files {
+        if name.len() >= 100 {
+            return Err(anyhow!("{} is too long a name for the archive", name));
+        }
+        let mut header = vec![0u8; BLOCK];
+        header[..name.len()].copy_from_slice(name.as_bytes());
+        header[100..108].copy_from_slice(&octal(0o644, 8));
+        header[108..116].copy_from_slice(&octal(0, 8));
+        header[116..124].copy_from_slice(&octal(0, 8));
+        header[124..136].copy_from_slice(&octal(contents.len() as u64, 12));
+        header[136..148].copy_from_slice(&octal(mtime, 12));
+        header[156] = b'0';
+        header[257..263].copy_from_slice(b"ustar\0");
+        header[263..265].copy_from_slice(b"00");
+        let sum = octal(checksum(&header), 7);
+        header[148..155].copy_from_slice(&sum);
+        header[155] = b' ';
+
+        out.extend(header);
+        out.extend(contents);
+        out.resize(out.len().next_multiple_of(BLOCK), 0);
+    }
+    // Two empty blocks end the archive
+    out.resize(out.len() + 2 * BLOCK, 0);
+    Ok(out)
+}
+
+/// The plain files in a tar archive, as (name, contents); directories and links are skipped
+pub fn read(bytes: &[u8]) -> Result<Vec<(String, Vec<u8>)>> {
+    let broken = || anyhow!("not a tar archive, or a damaged one");
+    let mut files = Vec::new();
+    let mut at = 0;
+    while at + BLOCK <= bytes.len() {
+        let header = &bytes[at..at + BLOCK];
+        if header.iter().all(|&b| b == 0) {
+            break;
+        }
+        if parse_octal(&header[148..156]) != Some(checksum(header)) {
+            return Err(broken());
+        }
+        let size = parse_octal(&header[124..136]).ok_or_else(broken)? as usize;
+        let start = at + BLOCK;
+        let contents = bytes.get(start..start + size).ok_or_else(broken)?;
+        if matches!(header[156], b'0' | 0) {
+            let end = header[..100].iter().position(|&b| b == 0).unwrap_or(100);
+            let mut name = String::from_utf8_lossy(&header[..end]).to_string();
+            // ustar keeps the start of long paths in a separate prefix
+            let prefix_end = header[345..500].iter().position(|&b| b == 0).unwrap_or(155);
+            if prefix_end > 0 && &header[257..262] == b"ustar" {
+                let prefix = String::from_utf8_lossy(&header[345..345 + prefix_end]);
+                name = format!("{}/{}", prefix, name);
+            }
+            files.push((name, contents.to_vec()));
+        }
+        at = start + size.next_multiple_of(BLOCK);
+    }
+    Ok(files)
+}